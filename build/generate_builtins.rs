@@ -50,8 +50,23 @@ pub fn generate(out_dir: &Path) -> Result<(), std::io::Error> {
     // Create the BUILTINS_META constant with full metadata
     let builtins_meta_code = generate_builtins_meta(&builtins_meta, &builtin_names);
 
+    // Embed the evaluated builtin definitions from pkl:gen so non-Pkl configs
+    // can reference them
+    let steps_path = Path::new("pkl/builtins.json");
+    let builtin_steps_code = if steps_path.exists() {
+        format!(
+            "/// Builtin step definitions keyed by name, as JSON\npub const BUILTIN_STEPS_JSON: &str = include_str!(\"{}\");",
+            escape_string(&fs::canonicalize(steps_path)?.to_string_lossy())
+        )
+    } else {
+        "/// Builtin step definitions keyed by name, as JSON\npub const BUILTIN_STEPS_JSON: &str = \"{}\";".to_string()
+    };
+
     // Combine and write to file
-    let output = format!("{}\n{}", builtins_array, builtins_meta_code);
+    let output = format!(
+        "{}\n{}\n{}",
+        builtins_array, builtins_meta_code, builtin_steps_code
+    );
     let dest_path = out_dir.join("builtins.rs");
     fs::write(dest_path, output)?;
 
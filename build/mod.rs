@@ -12,6 +12,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("cargo:rerun-if-changed=build/");
     println!("cargo:rerun-if-changed=pkl/builtins");
     println!("cargo:rerun-if-changed=pkl/builtins_meta.json");
+    println!("cargo:rerun-if-changed=pkl/builtins.json");
     println!("cargo:rerun-if-changed=settings.toml");
 
     generate_builtins::generate(&out_dir)?;
@@ -1,5 +1,5 @@
 #!/usr/bin/env python3
-"""Generate pkl/Builtins.pkl and pkl/builtins_meta.json from all builtins/*.pkl files."""
+"""Generate pkl/Builtins.pkl, pkl/builtins.json and pkl/builtins_meta.json from all builtins/*.pkl files."""
 
 import glob
 import json
@@ -64,14 +64,31 @@ DEPRECATED_ALIASES = [
 ]
 
 
-def validate_effect_coverage():
+def evaluate_builtins():
     result = subprocess.run(
         ["pkl", "eval", "pkl/Builtins.pkl", "--format", "json"],
         capture_output=True,
         text=True,
         check=True,
     )
-    builtins = json.loads(result.stdout)
+    return json.loads(result.stdout)
+
+
+def write_atomically(path, data):
+    fd, tmpfile = tempfile.mkstemp(
+        dir=os.path.dirname(path), prefix=os.path.basename(path) + ".", suffix=".tmp"
+    )
+    try:
+        with os.fdopen(fd, "w", newline="\n") as f:
+            json.dump(data, f, indent=None)
+            f.write("\n")
+        os.replace(tmpfile, path)
+    except Exception:
+        os.unlink(tmpfile)
+        raise
+
+
+def validate_effect_coverage(builtins):
     missing = []
     for name, step in builtins.items():
         if not isinstance(step, dict):
@@ -112,7 +129,18 @@ def main():
 
     # pkl format (exits 11 after formatting, ignore that)
     subprocess.run(["pkl", "format", "--write", "pkl/Builtins.pkl"])
-    validate_effect_coverage()
+    builtins = evaluate_builtins()
+    validate_effect_coverage(builtins)
+
+    # Generate evaluated builtin steps for `builtin = "<name>"` in non-Pkl
+    # configs. Tests are only needed by `hk test` on the Pkl modules.
+    steps = {
+        name: {k: v for k, v in step.items() if k != "tests"}
+        for name, step in builtins.items()
+        if isinstance(step, dict)
+    }
+    write_atomically("pkl/builtins.json", steps)
+    print("pkl/builtins.json")
 
     # Generate builtins metadata JSON for build script
     reflect_script = os.path.join(os.getcwd(), "scripts", "reflect.pkl")
@@ -177,19 +205,7 @@ def main():
         except Exception:
             continue
 
-    # Write atomically
-    fd, tmpfile = tempfile.mkstemp(
-        dir="pkl", prefix="builtins_meta.json.", suffix=".tmp"
-    )
-    try:
-        with os.fdopen(fd, "w", newline="\n") as f:
-            json.dump(entries, f, indent=None)
-            f.write("\n")
-        os.replace(tmpfile, "pkl/builtins_meta.json")
-    except Exception:
-        os.unlink(tmpfile)
-        raise
-
+    write_atomically("pkl/builtins_meta.json", entries)
     print("pkl/builtins_meta.json")
 
 
@@ -1,2 +1,89 @@
 // Include generated builtins data
 include!(concat!(env!("OUT_DIR"), "/builtins.rs"));
+
+use std::sync::LazyLock;
+
+use serde_json::{Map, Value};
+
+static BUILTIN_STEPS: LazyLock<Map<String, Value>> =
+    LazyLock::new(|| serde_json::from_str(BUILTIN_STEPS_JSON).unwrap_or_default());
+
+/// The embedded definition of a builtin step, as evaluated from
+/// `pkl/builtins/<name>.pkl`.
+pub fn builtin_step(name: &str) -> Option<&'static Map<String, Value>> {
+    BUILTIN_STEPS.get(name).and_then(Value::as_object)
+}
+
+/// Names of the embedded builtin steps
+pub fn builtin_names() -> impl Iterator<Item = &'static str> {
+    BUILTIN_STEPS.keys().map(String::as_str)
+}
+
+/// Replaces `builtin = "<name>"` in a step with the builtin's definition.
+/// The step's other fields override the builtin's, so a config can change
+/// e.g. just the `glob` of a builtin.
+pub fn expand_builtin(step: &mut Map<String, Value>) -> Result<(), String> {
+    let Some(builtin) = step.remove("builtin") else {
+        return Ok(());
+    };
+    let Value::String(name) = builtin else {
+        return Err("builtin must be a string".to_string());
+    };
+    expand_builtin_with(step, &name, builtin_step(&name))
+}
+
+fn expand_builtin_with(
+    step: &mut Map<String, Value>,
+    name: &str,
+    definition: Option<&Map<String, Value>>,
+) -> Result<(), String> {
+    let Some(definition) = definition else {
+        if BUILTIN_STEPS.is_empty() {
+            return Err(format!(
+                "builtin {name:?} is not available: this build of hk has no embedded builtin definitions"
+            ));
+        }
+        return Err(format!(
+            "unknown builtin {name:?}, see `hk builtins` for the available builtins"
+        ));
+    };
+    let overrides = std::mem::replace(step, definition.clone());
+    step.extend(overrides);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn object(value: Value) -> Map<String, Value> {
+        value.as_object().unwrap().clone()
+    }
+
+    #[test]
+    fn test_expand_builtin_overrides_fields() {
+        let definition = object(json!({
+            "glob": ["**/*.py"],
+            "check": {"command": "ruff check {{files}}", "effect": "read"},
+            "fix": {"command": "ruff check --fix {{files}}", "effect": "write"},
+        }));
+        let mut step = object(json!({"glob": ["src/**/*.py"]}));
+        expand_builtin_with(&mut step, "ruff", Some(&definition)).unwrap();
+        assert_eq!(step["glob"], json!(["src/**/*.py"]));
+        assert_eq!(step["fix"], definition["fix"]);
+    }
+
+    #[test]
+    fn test_expand_builtin_without_builtin_is_a_noop() {
+        let mut step = object(json!({"check": "true"}));
+        expand_builtin(&mut step).unwrap();
+        assert_eq!(step, object(json!({"check": "true"})));
+
+        let mut step = object(json!({"builtin": 1}));
+        assert_eq!(
+            expand_builtin(&mut step).unwrap_err(),
+            "builtin must be a string"
+        );
+    }
+}
@@ -33,10 +33,15 @@ pub const EFFECTS: &[(&str, SpecCommandEffect)] = &[
     ("cache clear", Write),
     ("completion", Read),
     ("config", Read),
+    ("config diff", Read),
     ("config dump", Read),
     ("config explain", Read),
+    ("config export", Write),
     ("config get", Read),
+    ("config schema", Write),
     ("config sources", Read),
+    ("config vendor", Write),
+    ("doctor", Read),
     ("init", Write),
     ("install", Write),
     ("migrate", Read),
@@ -48,21 +53,33 @@ pub const EFFECTS: &[(&str, SpecCommandEffect)] = &[
     ("usage", Read),
     ("util", Read),
     ("util check-added-large-files", Read),
+    ("util check-branch-policy", Read),
     ("util check-byte-order-marker", Read),
     ("util check-case-conflict", Read),
+    ("util check-codeowners", Read),
+    ("util check-commit-msg", Write),
     ("util check-conventional-commit", Read),
     ("util check-executables-have-shebangs", Read),
+    ("util check-json", Read),
     ("util check-merge-conflict", Read),
+    ("util check-spelling", Write),
     ("util check-symlinks", Read),
+    ("util check-toml", Read),
+    ("util check-xml", Read),
+    ("util check-yaml", Read),
+    ("util codeowners", Read),
     ("util detect-private-key", Read),
     // These rewrite the files they inspect.
     ("util end-of-file-fixer", Write),
     ("util fix-byte-order-marker", Write),
     ("util fix-smart-quotes", Write),
+    ("util license-header", Write),
     ("util mixed-line-ending", Write),
     ("util no-commit-to-branch", Read),
+    ("util pretty-format-json", Write),
     ("util python-check-ast", Read),
     ("util python-debug-statements", Read),
+    ("util sort-keys", Write),
     ("util trailing-whitespace", Write),
     ("validate", Read),
     ("version", Read),
@@ -81,6 +98,10 @@ pub const EFFECTS: &[(&str, SpecCommandEffect)] = &[
 pub const UNCLASSIFIED: &[(&str, &str)] = &[
     ("check", "runs check steps declared in hk.pkl"),
     ("fix", "runs fix steps declared in hk.pkl"),
+    (
+        "lsp",
+        "runs check and fix steps for files edited in the editor",
+    ),
     (
         "mcp",
         "serves effect-classified project tools to MCP clients",
@@ -1,6 +1,10 @@
+use crate::config_diff;
+use crate::policy::Policy;
+use crate::schema;
 use crate::settings::generated::SETTINGS_META;
 use crate::{Result, settings::Settings};
 use serde_json::json;
+use std::path::PathBuf;
 
 fn unknown_config_key_error(key: &str) -> eyre::Report {
     let all_keys: Vec<&str> = SETTINGS_META
@@ -20,7 +24,7 @@ fn unknown_config_key_error(key: &str) -> eyre::Report {
 ///
 /// View and inspect hk's configuration from all sources.
 /// Configuration is merged from multiple sources in precedence order:
-/// CLI flags > Environment variables > Git config (local) > User config (.hkrc.pkl) >
+/// Org policy > CLI flags > Environment variables > Git config (local) > User config (.hkrc.pkl) >
 /// Git config (global) > Project config (hk.pkl) > Built-in defaults.
 #[derive(Debug, clap::Args)]
 #[clap(visible_alias = "cfg")]
@@ -31,6 +35,13 @@ pub struct Config {
 
 #[derive(Debug, clap::Subcommand)]
 enum ConfigCommand {
+    /// Compare the evaluated project config between two git revisions
+    ///
+    /// Each revision is checked out in a temporary worktree and evaluated
+    /// like `hk config export`, so Pkl `amends`, builtins and subprojects are
+    /// resolved. Prints added, removed and changed hooks and steps along with
+    /// the fields that changed.
+    Diff(ConfigDiff),
     /// Print effective runtime settings (JSON format)
     ///
     /// Shows the merged configuration from all sources including CLI flags,
@@ -41,16 +52,38 @@ enum ConfigCommand {
     /// Shows the resolved value, its source (env/git/cli/default), and
     /// the full precedence chain showing all layers that could affect it.
     Explain(ConfigExplain),
+    /// Export the evaluated project config as a standalone hk.json or hk.toml
+    ///
+    /// The export reflects Pkl evaluation, merged subprojects and the user
+    /// config overlays, so it can be loaded without Pkl (e.g. with
+    /// `HK_FILE=hk.json`) or diffed to review effective config changes.
+    Export(ConfigExport),
     /// Get a specific configuration value
     ///
     /// Available keys: jobs, enabled_profiles, disabled_profiles, fail_fast,
     /// display_skip_reasons, warnings, exclude, skip_steps, skip_hooks, stage
     Get(ConfigGet),
+    /// Print a JSON Schema for hk.yaml, hk.toml and hk.json
+    ///
+    /// Point an editor at the schema for completion and validation, e.g.
+    /// with `# yaml-language-server: $schema=hk.schema.json` in hk.yaml,
+    /// `#:schema hk.schema.json` in hk.toml or `"$schema"` in hk.json.
+    Schema(ConfigSchema),
     /// Show the configuration source precedence order
     ///
     /// Lists all configuration sources in order of precedence to help
-    /// understand where configuration values come from.
+    /// understand where configuration values come from, and the org policy
+    /// files in effect.
     Sources(ConfigSources),
+    /// Download the remote Pkl modules the project config imports
+    ///
+    /// Every http(s) module and package the config and its subprojects import
+    /// is written to `.hk/pkl/` with a `lock.json` of sha256 checksums. When
+    /// the lockfile exists, hk loads those modules from disk instead of the
+    /// network and fails if one no longer matches its checksum. Commit the
+    /// directory for CI without network access and re-run this command after
+    /// changing remote imports.
+    Vendor(ConfigVendor),
 }
 
 #[derive(Debug, clap::Args)]
@@ -60,6 +93,30 @@ struct ConfigDump {
     format: String,
 }
 
+#[derive(Debug, clap::Args)]
+struct ConfigDiff {
+    /// The revision to compare from
+    from: String,
+
+    /// The revision to compare to (default: the working tree)
+    to: Option<String>,
+
+    /// Output format
+    #[clap(long, value_parser = ["human", "json"], default_value = "human")]
+    format: String,
+}
+
+#[derive(Debug, clap::Args)]
+struct ConfigExport {
+    /// Output format (default: from the `--output` extension, or json)
+    #[clap(long, value_parser = ["json", "toml"])]
+    format: Option<String>,
+
+    /// Write the config to this file instead of stdout
+    #[clap(long, value_hint = clap::ValueHint::FilePath)]
+    output: Option<PathBuf>,
+}
+
 #[derive(Debug, clap::Args)]
 struct ConfigGet {
     /// Configuration key to retrieve
@@ -75,16 +132,34 @@ struct ConfigExplain {
     key: String,
 }
 
+#[derive(Debug, clap::Args)]
+struct ConfigSchema {
+    /// Print the schema of the legacy user config instead
+    #[clap(long)]
+    user: bool,
+
+    /// Write the schema to this file instead of stdout
+    #[clap(long, value_hint = clap::ValueHint::FilePath)]
+    output: Option<PathBuf>,
+}
+
 #[derive(Debug, clap::Args)]
 struct ConfigSources {}
 
+#[derive(Debug, clap::Args)]
+struct ConfigVendor {}
+
 impl Config {
     pub async fn run(&self) -> Result<()> {
         match &self.command {
+            Some(ConfigCommand::Diff(cmd)) => cmd.run(),
             Some(ConfigCommand::Dump(cmd)) => cmd.run(),
             Some(ConfigCommand::Get(cmd)) => cmd.run(),
             Some(ConfigCommand::Explain(cmd)) => cmd.run(),
+            Some(ConfigCommand::Export(cmd)) => cmd.run(),
+            Some(ConfigCommand::Schema(cmd)) => cmd.run(),
             Some(ConfigCommand::Sources(cmd)) => cmd.run(),
+            Some(ConfigCommand::Vendor(cmd)) => cmd.run(),
             None => {
                 warn!("this output is almost certain to change in a future version");
                 let dump = ConfigDump {
@@ -137,6 +212,61 @@ impl ConfigDump {
     }
 }
 
+impl ConfigDiff {
+    fn run(&self) -> Result<()> {
+        let before = config_diff::export_at(Some(&self.from))?;
+        let after = config_diff::export_at(self.to.as_deref())?;
+        let diff = config_diff::diff(&before, &after);
+        if self.format == "json" {
+            let output = json!({
+                "from": self.from,
+                "to": self.to,
+                "fields": diff.fields,
+                "hooks": diff.hooks,
+            });
+            println!("{}", serde_json::to_string_pretty(&output)?);
+        } else if diff.is_empty() {
+            info!("no changes to the evaluated config");
+        } else {
+            print!("{}", diff.render());
+        }
+        Ok(())
+    }
+}
+
+impl ConfigExport {
+    fn run(&self) -> Result<()> {
+        let format = match (&self.format, &self.output) {
+            (Some(format), _) => format.as_str(),
+            (None, Some(path)) if path.extension().is_some_and(|ext| ext == "toml") => "toml",
+            _ => "json",
+        };
+        let config = crate::config::Config::get()?;
+        let output = config.export(format)?;
+        match &self.output {
+            Some(path) => xx::file::write(path, output)?,
+            None => print!("{output}"),
+        }
+        Ok(())
+    }
+}
+
+impl ConfigSchema {
+    fn run(&self) -> Result<()> {
+        let schema = if self.user {
+            schema::user_config_schema()
+        } else {
+            schema::config_schema()
+        };
+        let output = serde_json::to_string_pretty(&schema)? + "\n";
+        match &self.output {
+            Some(path) => xx::file::write(path, output)?,
+            None => print!("{output}"),
+        }
+        Ok(())
+    }
+}
+
 impl ConfigGet {
     fn run(&self) -> Result<()> {
         let settings = Settings::try_get()?;
@@ -199,15 +329,43 @@ impl ConfigSources {
         // For now, we'll just show that the values come from the merged settings
         // In a more complete implementation, we'd track where each value originated
         println!("Configuration sources (in order of precedence):");
-        println!("1. CLI flags");
-        println!("2. Environment variables (HK_*)");
-        println!("3. Git config (local repo)");
-        println!("4. Git config (global/system)");
-        println!("5. User rc (.hkrc.pkl)");
-        println!("6. Project config (hk.pkl)");
-        println!("7. Built-in defaults");
+        println!("1. Org policy (/etc/hk/policy.*, HK_POLICY), cannot be overridden");
+        println!("2. CLI flags");
+        println!("3. Environment variables (HK_*)");
+        println!("4. Git config (local repo)");
+        println!("5. Git config (global/system)");
+        println!("6. User rc (.hkrc.pkl)");
+        println!("7. Project config (hk.pkl)");
+        println!("8. Built-in defaults");
+        println!();
+        let policies = Policy::paths();
+        if policies.is_empty() {
+            println!("No org policy is in effect");
+        } else {
+            for path in policies {
+                println!("Org policy: {}", xx::file::display_path(&path));
+            }
+        }
         println!();
         println!("Note: Use 'hk config dump' to see current effective values");
         Ok(())
     }
 }
+
+impl ConfigVendor {
+    fn run(&self) -> Result<()> {
+        let Some(path) = crate::config::Config::project_config_path() else {
+            eyre::bail!("no project config found");
+        };
+        let (dir, lockfile) = crate::config::Config::vendor(&path)?;
+        for url in lockfile.modules.keys() {
+            info!("vendored {url}");
+        }
+        info!(
+            "vendored {} remote modules into {}",
+            lockfile.modules.len(),
+            xx::file::display_rel_path(&dir)
+        );
+        Ok(())
+    }
+}
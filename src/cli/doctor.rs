@@ -0,0 +1,815 @@
+use std::collections::BTreeMap;
+use std::ffi::OsStr;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::{Duration, Instant, SystemTime};
+
+use eyre::bail;
+use serde::Serialize;
+
+use crate::config::Config;
+use crate::hook::StepOrGroup;
+use crate::step::{Command as StepCommand, CommandPrefix, Step};
+use crate::ui::style;
+use crate::{Result, env, git_util, version};
+
+use super::install;
+
+/// Config evaluation slower than this is reported as a warning
+const SLOW_EVAL: Duration = Duration::from_secs(2);
+
+/// Diagnose why hooks might not be running
+///
+/// Checks that hooks are installed and run this hk binary, that git
+/// settings like `core.hooksPath` don't bypass them, that every enabled
+/// step's executable can be found, and that the config, cache directory
+/// and repository are healthy.
+///
+/// Exits non-zero if any check fails.
+#[derive(Debug, clap::Args)]
+#[clap(verbatim_doc_comment)]
+pub struct Doctor {
+    /// Output format
+    #[clap(long, value_parser = ["human", "json"], default_value = "human")]
+    format: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum Status {
+    Ok,
+    Warn,
+    Error,
+}
+
+#[derive(Debug, Serialize)]
+struct Check {
+    name: String,
+    status: Status,
+    message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    hint: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    details: Option<serde_json::Value>,
+}
+
+impl Check {
+    fn new(name: impl Into<String>, status: Status, message: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            status,
+            message: message.into(),
+            hint: None,
+            details: None,
+        }
+    }
+
+    fn ok(name: impl Into<String>, message: impl Into<String>) -> Self {
+        Self::new(name, Status::Ok, message)
+    }
+
+    fn warn(name: impl Into<String>, message: impl Into<String>) -> Self {
+        Self::new(name, Status::Warn, message)
+    }
+
+    fn error(name: impl Into<String>, message: impl Into<String>) -> Self {
+        Self::new(name, Status::Error, message)
+    }
+
+    fn hint(mut self, hint: impl Into<String>) -> Self {
+        self.hint = Some(hint.into());
+        self
+    }
+
+    fn details(mut self, details: serde_json::Value) -> Self {
+        self.details = Some(details);
+        self
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct Report {
+    version: &'static str,
+    exe: Option<PathBuf>,
+    checks: Vec<Check>,
+}
+
+impl Doctor {
+    pub async fn run(&self) -> Result<()> {
+        let exe = std::env::current_exe().ok();
+        let mut checks = Vec::new();
+
+        let repo = check_repository(&mut checks);
+        let config = check_config(&mut checks);
+        if let Some(repo) = &repo {
+            let events = match &config {
+                Some(config) => install::hook_events(config),
+                None => install::CORE_GLOBAL_EVENTS
+                    .iter()
+                    .map(|e| e.to_string())
+                    .collect(),
+            };
+            check_hooks(&mut checks, repo, &events, exe.as_deref());
+            check_index_lock(&mut checks, repo);
+        }
+        if let Some(config) = &config {
+            check_executables(&mut checks, config).await;
+        }
+        check_cache(&mut checks);
+
+        let errors = checks.iter().filter(|c| c.status == Status::Error).count();
+        let report = Report {
+            version: version::version(),
+            exe,
+            checks,
+        };
+        if self.format == "json" {
+            println!("{}", serde_json::to_string_pretty(&report)?);
+        } else {
+            print_human(&report);
+        }
+        if errors > 0 {
+            bail!("hk doctor found {errors} problem(s)");
+        }
+        Ok(())
+    }
+}
+
+fn print_human(report: &Report) {
+    println!(
+        "hk {} ({})",
+        report.version,
+        report
+            .exe
+            .as_deref()
+            .map(xx::file::display_path)
+            .unwrap_or_default()
+    );
+    for check in &report.checks {
+        let icon = match check.status {
+            Status::Ok => style::nstyle("✓").green(),
+            Status::Warn => style::nyellow("!"),
+            Status::Error => style::nred("✗"),
+        };
+        println!("{icon} {}: {}", style::nbold(&check.name), check.message);
+        if let Some(hint) = &check.hint {
+            println!("  {}", style::ndim(hint));
+        }
+    }
+}
+
+/// Where the repository keeps its git data
+struct Repo {
+    git_dir: PathBuf,
+    common_dir: PathBuf,
+    hooks_dir: PathBuf,
+}
+
+fn check_repository(checks: &mut Vec<Check>) -> Option<Repo> {
+    let git_version = match git_util::git_version() {
+        Some((major, minor, patch)) => format!("{major}.{minor}.{patch}"),
+        None => {
+            checks.push(Check::error("git", "git was not found on PATH"));
+            return None;
+        }
+    };
+    let Some(git_dir) = git(["rev-parse", "--absolute-git-dir"]) else {
+        checks.push(
+            Check::error("repository", "not inside a git repository")
+                .hint("hooks only run inside a git repository; run `git init` first"),
+        );
+        return None;
+    };
+    let git_dir = PathBuf::from(git_dir);
+    // --git-common-dir may be relative to the current directory
+    let cwd = std::env::current_dir().unwrap_or_default();
+    let common_dir = git(["rev-parse", "--git-common-dir"])
+        .map(|dir| cwd.join(dir))
+        .map(|dir| dir.canonicalize().unwrap_or(dir))
+        .unwrap_or_else(|| git_dir.clone());
+    let hooks_dir = git_util::worktree_hooks_path().unwrap_or_else(|| common_dir.join("hooks"));
+    let repo = Repo {
+        git_dir,
+        common_dir,
+        hooks_dir,
+    };
+
+    let bare = git(["rev-parse", "--is-bare-repository"]).as_deref() == Some("true");
+    let in_work_tree = git(["rev-parse", "--is-inside-work-tree"]).as_deref() == Some("true");
+    let git_env = ["GIT_DIR", "GIT_WORK_TREE"]
+        .into_iter()
+        .filter(|var| std::env::var_os(var).is_some())
+        .collect::<Vec<_>>();
+    let linked_worktree = repo.git_dir.canonicalize().ok() != repo.common_dir.canonicalize().ok();
+    let details = serde_json::json!({
+        "git_version": git_version,
+        "git_dir": repo.git_dir,
+        "common_dir": repo.common_dir,
+        "bare": bare,
+        "worktree": linked_worktree,
+        "git_env": git_env,
+    });
+    let check = if bare && !in_work_tree {
+        Check::warn("repository", "bare repository without a work tree")
+            .hint("hk needs a work tree to check files; set GIT_WORK_TREE or run from a worktree")
+    } else if linked_worktree {
+        Check::ok(
+            "repository",
+            format!(
+                "linked worktree; hooks are shared from {}",
+                xx::file::display_path(&repo.hooks_dir)
+            ),
+        )
+    } else if !git_env.is_empty() {
+        Check::ok(
+            "repository",
+            format!("git {git_version}, using {}", git_env.join(" and ")),
+        )
+    } else {
+        Check::ok("repository", format!("git {git_version}"))
+    };
+    checks.push(check.details(details));
+    Some(repo)
+}
+
+/// Load the project config, timing an uncached evaluation
+fn check_config(checks: &mut Vec<Check>) -> Option<Config> {
+    let Some(path) = Config::project_config_path() else {
+        checks.push(
+            Check::warn(
+                "config",
+                "no hk config found; installed hooks will do nothing",
+            )
+            .hint("run `hk init` to create an hk.pkl"),
+        );
+        return None;
+    };
+    let display = xx::file::display_path(&path);
+    let start = Instant::now();
+    let evaluated = Config::evaluate(&path);
+    let elapsed = start.elapsed();
+    let details = serde_json::json!({
+        "path": path,
+        "eval_ms": elapsed.as_millis(),
+        "backend": env::HK_PKL_BACKEND.as_str(),
+    });
+    if let Err(err) = evaluated.and_then(|_| Config::get()) {
+        let message = format!("{err:#}");
+        if message.contains("minimum required version") {
+            checks.push(
+                Check::error("min_hk_version", message).hint("upgrade hk, e.g. with `mise up hk`"),
+            );
+        } else {
+            checks.push(
+                Check::error("config", format!("{display} failed to load: {message}"))
+                    .details(details),
+            );
+        }
+        return None;
+    }
+    let config = Config::get().ok()?;
+    let message = format!("{display} evaluated in {}ms", elapsed.as_millis());
+    checks.push(if elapsed > SLOW_EVAL {
+        Check::warn("config", message)
+            .hint("hooks pay this cost whenever the config cache is stale")
+            .details(details)
+    } else {
+        Check::ok("config", message).details(details)
+    });
+    if let Some(min) = &config.min_hk_version {
+        checks.push(Check::ok(
+            "min_hk_version",
+            format!("hk {} satisfies {min}", version::version()),
+        ));
+    }
+    Some(config)
+}
+
+/// How a single hook event is installed
+#[derive(Debug, Default, Serialize)]
+struct HookInstall {
+    local: Option<String>,
+    global: Option<String>,
+    shim: Option<String>,
+    disabled: bool,
+}
+
+fn check_hooks(checks: &mut Vec<Check>, repo: &Repo, events: &[String], exe: Option<&Path>) {
+    let config_hooks = git_util::git_at_least(2, 54);
+    let hooks_path = git(["config", "--get", "core.hooksPath"]);
+    // Script hooks run from core.hooksPath when set, so shims elsewhere are ignored
+    let shims_ignored = hooks_path.as_ref().is_some_and(|path| {
+        let path = git_util::find_work_tree_root().join(path);
+        path.canonicalize().ok() != repo.hooks_dir.canonicalize().ok()
+    });
+
+    let mut uses_shims = false;
+    for event in events {
+        let name = format!("hook:{event}");
+        let key = format!("hook.hk-{event}.command");
+        let install = HookInstall {
+            local: git(["config", "--local", "--get", &key]),
+            global: git(["config", "--global", "--get", &key]),
+            shim: std::fs::read_to_string(repo.hooks_dir.join(event))
+                .ok()
+                .filter(|content| install::is_hk_shim(content)),
+            disabled: git([
+                "config",
+                "--type=bool",
+                "--get",
+                &format!("hook.hk-{event}.enabled"),
+            ])
+            .as_deref()
+                == Some("false"),
+        };
+        let details = serde_json::to_value(&install).unwrap_or_default();
+        let commands = [&install.local, &install.global]
+            .into_iter()
+            .flatten()
+            .filter(|_| config_hooks)
+            .chain(install.shim.iter().filter(|_| !shims_ignored))
+            .collect::<Vec<_>>();
+        uses_shims |= install.shim.is_some();
+
+        let check = if install.disabled {
+            Check::warn(
+                &name,
+                format!("disabled with hook.hk-{event}.enabled = false"),
+            )
+        } else if commands.is_empty() {
+            let check = Check::error(&name, "not installed");
+            if install.shim.is_some() {
+                check.hint(format!(
+                    "core.hooksPath is set, so git ignores the hook in {}",
+                    xx::file::display_path(&repo.hooks_dir)
+                ))
+            } else if !config_hooks && (install.local.is_some() || install.global.is_some()) {
+                check.hint("config-based hooks need git 2.54+; run `hk install --legacy`")
+            } else {
+                check.hint("run `hk install --global` or `hk install`")
+            }
+        } else if commands.len() > 1 {
+            Check::warn(&name, "installed more than once; hk runs twice per event").hint(format!(
+                "disable one with `git config --local hook.hk-{event}.enabled false` or run `hk uninstall`"
+            ))
+        } else {
+            let scope = if install.local.is_some() && config_hooks {
+                "local git config"
+            } else if install.global.is_some() && config_hooks {
+                "global git config"
+            } else {
+                "hook script"
+            };
+            match hook_target(commands[0], exe) {
+                Target::Current => Check::ok(&name, format!("installed in {scope}")),
+                Target::Mise => Check::ok(&name, format!("installed in {scope}, runs hk via mise")),
+                Target::Other(path) => Check::warn(
+                    &name,
+                    format!("installed in {scope}, but runs {}", path.display()),
+                )
+                .hint("reinstall with `hk install` to use this binary"),
+                Target::Missing(program) => Check::error(
+                    &name,
+                    format!("installed in {scope}, but {program} was not found"),
+                )
+                .hint("reinstall with `hk install`"),
+            }
+        };
+        checks.push(check.details(details));
+    }
+
+    if let Some(path) = hooks_path {
+        checks.push(if uses_shims && shims_ignored {
+            Check::error(
+                "core.hooksPath",
+                format!("set to {path}; hk hook scripts are bypassed"),
+            )
+            .hint("run `git config --unset-all core.hooksPath` or reinstall on git 2.54+")
+        } else if shims_ignored {
+            Check::warn(
+                "core.hooksPath",
+                format!("set to {path}; another hook manager may also be running"),
+            )
+        } else {
+            Check::ok("core.hooksPath", format!("set to {path}"))
+        });
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Target {
+    /// The hook runs this hk binary
+    Current,
+    /// The hook resolves hk through `mise x`
+    Mise,
+    /// The hook runs a different hk binary
+    Other(PathBuf),
+    /// The hook's program doesn't exist
+    Missing(String),
+}
+
+/// Resolve which binary an installed hook command (or shim script) runs
+fn hook_target(command: &str, exe: Option<&Path>) -> Target {
+    let Some(program) = hook_program(command) else {
+        return Target::Missing(command.to_string());
+    };
+    if Path::new(&program).file_name() == Some(OsStr::new("mise")) {
+        return Target::Mise;
+    }
+    let path = match program.strip_prefix("~/") {
+        Some(rel) => dirs::home_dir().map(|home| home.join(rel)),
+        None if program.contains('/') => Some(PathBuf::from(&program)),
+        None => find_on_path(&program, std::env::var_os("PATH").as_deref()),
+    };
+    let Some(path) = path.filter(|p| p.is_file()) else {
+        return Target::Missing(program);
+    };
+    let canonical = |p: &Path| p.canonicalize().unwrap_or_else(|_| p.to_path_buf());
+    match exe {
+        Some(exe) if canonical(exe) != canonical(&path) => Target::Other(path),
+        _ => Target::Current,
+    }
+}
+
+/// The program an hk hook runs: the first word after the `HK=0` guard
+fn hook_program(command: &str) -> Option<String> {
+    let line = command
+        .lines()
+        .find(|line| line.contains(" run "))
+        .unwrap_or(command);
+    let invocation = line.split_once("|| ").map_or(line, |(_, rest)| rest);
+    let words = shell_words::split(invocation).ok()?;
+    words.into_iter().find(|word| word != "exec")
+}
+
+fn check_index_lock(checks: &mut Vec<Check>, repo: &Repo) {
+    let lock = repo.git_dir.join("index.lock");
+    let Ok(metadata) = lock.metadata() else {
+        return;
+    };
+    let age = metadata
+        .modified()
+        .ok()
+        .and_then(|modified| SystemTime::now().duration_since(modified).ok())
+        .unwrap_or_default();
+    checks.push(
+        Check::warn(
+            "index.lock",
+            format!("{} exists ({}s old)", lock.display(), age.as_secs()),
+        )
+        .hint("if no other git process is running, remove it to unblock commits and hk's stash"),
+    );
+}
+
+/// Check that every enabled step's executable is on PATH, or in the
+/// step's mise environment when HK_MISE is set
+async fn check_executables(checks: &mut Vec<Check>, config: &Config) {
+    let root = git_util::find_work_tree_root();
+    // (program, dir) -> steps that run it
+    let mut programs: BTreeMap<(String, Option<String>), Vec<String>> = BTreeMap::new();
+    for hook in config.hooks.values() {
+        for step in hook.steps.values().flat_map(|s| match s {
+            StepOrGroup::Step(step) => vec![step.as_ref()],
+            StepOrGroup::Group(group) => group.steps.values().collect(),
+        }) {
+            if step.profile_skip_reason().is_some() {
+                continue;
+            }
+            for program in step_programs(step) {
+                let steps = programs.entry((program, step.dir.clone())).or_default();
+                if !steps.contains(&step.name) {
+                    steps.push(step.name.clone());
+                }
+            }
+        }
+    }
+
+    let path = std::env::var_os("PATH");
+    let mut missing = Vec::new();
+    let mut via_mise = 0;
+    for ((program, dir), steps) in &programs {
+        let dir = root.join(dir.as_deref().unwrap_or(""));
+        let found = if program.contains('/') {
+            dir.join(program).is_file()
+        } else {
+            find_on_path(program, path.as_deref()).is_some()
+        };
+        if found {
+            continue;
+        }
+        if *env::HK_MISE && !program.contains('/') {
+            let mise_env = crate::mise_env::mise_env_for_dir(&dir).await;
+            if let Some(mise_path) = mise_env.get("PATH")
+                && find_on_path(program, Some(OsStr::new(mise_path))).is_some()
+            {
+                via_mise += 1;
+                continue;
+            }
+        }
+        missing.push(serde_json::json!({ "program": program, "steps": steps }));
+    }
+
+    let found = programs.len() - missing.len();
+    let details = serde_json::json!({ "missing": missing });
+    checks.push(if missing.is_empty() {
+        let mut message = format!("{found} executable(s) found");
+        if via_mise > 0 {
+            message.push_str(&format!(", {via_mise} via mise"));
+        }
+        Check::ok("executables", message)
+    } else {
+        let list = missing
+            .iter()
+            .map(|m| {
+                let steps = m["steps"].as_array().into_iter().flatten();
+                let steps = steps.filter_map(|s| s.as_str()).collect::<Vec<_>>();
+                format!(
+                    "{} ({})",
+                    m["program"].as_str().unwrap_or(""),
+                    steps.join(", ")
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+        let check = Check::error("executables", format!("not found: {list}"));
+        if *env::HK_MISE {
+            check.hint("install them with mise or add them to your PATH")
+        } else {
+            check.hint("add them to your PATH, or set HK_MISE=1 to use tools from mise")
+        }
+        .details(details)
+    });
+}
+
+/// Programs run by a step's commands, skipping ones that can't be known
+/// without rendering the command (templates, variables, shell builtins)
+fn step_programs(step: &Step) -> Vec<String> {
+    if let Some(program) = step.prefix.as_ref().and_then(prefix_program) {
+        return vec![program];
+    }
+    let mut programs = Vec::new();
+    for command in [
+        &step.check,
+        &step.check_diff,
+        &step.check_list_files,
+        &step.fix,
+    ]
+    .into_iter()
+    .flatten()
+    {
+        if let Some(program) = command_program(command)
+            && !programs.contains(&program)
+        {
+            programs.push(program);
+        }
+    }
+    programs
+}
+
+fn prefix_program(prefix: &CommandPrefix) -> Option<String> {
+    match prefix {
+        CommandPrefix::Shell(script) => shell_program(script),
+        CommandPrefix::Argv(argv) => argv.first().and_then(|p| static_program(p)),
+    }
+}
+
+fn command_program(command: &StepCommand) -> Option<String> {
+    match command {
+        StepCommand::Spec(spec) => command_program(&spec.command),
+        StepCommand::Argv(command) => command.argv.first().and_then(|p| static_program(p)),
+        StepCommand::Shell(script) => shell_program(&script.to_string()),
+    }
+}
+
+/// The first program in a shell script
+fn shell_program(script: &str) -> Option<String> {
+    let line = script
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty() && !line.starts_with('#'))?;
+    let words = shell_words::split(line).ok()?;
+    let program = words.iter().find(|word| {
+        !is_env_assignment(word) && !matches!(word.as_str(), "exec" | "command" | "env")
+    })?;
+    static_program(program)
+}
+
+fn static_program(program: &str) -> Option<String> {
+    const SHELL_BUILTINS: &[&str] = &[
+        "!", ".", ":", "[", "[[", "case", "cd", "echo", "eval", "exit", "export", "false", "for",
+        "if", "printf", "read", "set", "source", "test", "true", "until", "while", "{", "(",
+    ];
+    if program.is_empty()
+        || program.contains("{{")
+        || program.contains('$')
+        || program.starts_with('(')
+        || SHELL_BUILTINS.contains(&program)
+    {
+        return None;
+    }
+    Some(program.to_string())
+}
+
+fn is_env_assignment(word: &str) -> bool {
+    word.split_once('=').is_some_and(|(name, _)| {
+        !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+    })
+}
+
+fn find_on_path(program: &str, path: Option<&OsStr>) -> Option<PathBuf> {
+    std::env::split_paths(path?).find_map(|dir| {
+        let candidate = dir.join(program);
+        if is_executable(&candidate) {
+            return Some(candidate);
+        }
+        if cfg!(windows) {
+            return ["exe", "cmd", "bat"]
+                .iter()
+                .map(|ext| candidate.with_extension(ext))
+                .find(|c| c.is_file());
+        }
+        None
+    })
+}
+
+fn is_executable(path: &Path) -> bool {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        path.metadata()
+            .is_ok_and(|m| m.is_file() && m.permissions().mode() & 0o111 != 0)
+    }
+    #[cfg(not(unix))]
+    {
+        path.is_file()
+    }
+}
+
+fn check_cache(checks: &mut Vec<Check>) {
+    for (name, dir) in [
+        ("cache", &*env::HK_CACHE_DIR),
+        ("state", &*env::HK_STATE_DIR),
+    ] {
+        let display = xx::file::display_path(dir);
+        let writable = xx::file::mkdirp(dir)
+            .map_err(|e| e.to_string())
+            .and_then(|_| tempfile::NamedTempFile::new_in(dir).map_err(|e| e.to_string()));
+        if let Err(err) = writable {
+            checks.push(Check::error(
+                name,
+                format!("{display} is not writable: {err}"),
+            ));
+            continue;
+        }
+        let (files, bytes, corrupt) = dir_stats(dir);
+        let details = serde_json::json!({
+            "path": dir,
+            "files": files,
+            "bytes": bytes,
+            "corrupt": corrupt,
+        });
+        let message = format!("{display} ({files} files, {} KiB)", bytes / 1024);
+        checks.push(
+            if corrupt.is_empty() {
+                Check::ok(name, message)
+            } else {
+                Check::warn(name, format!("{message}, {} unreadable", corrupt.len()))
+                    .hint("run `hk cache clear`")
+            }
+            .details(details),
+        );
+    }
+}
+
+/// File count, total size and unparseable JSON cache files under `dir`
+fn dir_stats(dir: &Path) -> (usize, u64, Vec<PathBuf>) {
+    let (mut files, mut bytes, mut corrupt) = (0, 0, Vec::new());
+    let mut stack = vec![dir.to_path_buf()];
+    while let Some(dir) = stack.pop() {
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let Ok(metadata) = entry.metadata() else {
+                continue;
+            };
+            if metadata.is_dir() {
+                stack.push(path);
+                continue;
+            }
+            files += 1;
+            bytes += metadata.len();
+            if path.extension().is_some_and(|ext| ext == "json")
+                && std::fs::read(&path)
+                    .ok()
+                    .and_then(|raw| serde_json::from_slice::<serde_json::Value>(&raw).ok())
+                    .is_none()
+            {
+                corrupt.push(path);
+            }
+        }
+    }
+    (files, bytes, corrupt)
+}
+
+/// Run git and return its trimmed stdout if it succeeds with output
+fn git<I, S>(args: I) -> Option<String>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<OsStr>,
+{
+    let output = Command::new("git").args(args).output().ok()?;
+    let stdout = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (output.status.success() && !stdout.is_empty()).then_some(stdout)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hook_program_from_config_command() {
+        assert_eq!(
+            hook_program(r#"test "${HK:-1}" = "0" || '/opt/hk bin/hk' run pre-commit --from-hook"#),
+            Some("/opt/hk bin/hk".to_string())
+        );
+        assert_eq!(
+            hook_program(r#"test "${HK:-1}" = "0" || mise x -- hk run pre-push --from-hook"#),
+            Some("mise".to_string())
+        );
+    }
+
+    #[test]
+    fn test_hook_program_from_shim() {
+        let shim =
+            "#!/bin/sh\ntest \"${HK:-1}\" = \"0\" || exec hk run pre-commit --from-hook \"$@\"\n";
+        assert_eq!(hook_program(shim), Some("hk".to_string()));
+    }
+
+    #[test]
+    fn test_hook_target_detects_other_binary() {
+        let tmp = tempfile::tempdir().unwrap();
+        let other = tmp.path().join("hk");
+        std::fs::write(&other, "").unwrap();
+        let command = format!("hk_guard || {} run pre-commit", other.display());
+        let exe = tmp.path().join("current-hk");
+        std::fs::write(&exe, "").unwrap();
+
+        assert_eq!(
+            hook_target(&command, Some(&exe)),
+            Target::Other(other.clone())
+        );
+        assert_eq!(hook_target(&command, Some(&other)), Target::Current);
+        assert_eq!(
+            hook_target("guard || /nonexistent/hk run pre-commit", Some(&exe)),
+            Target::Missing("/nonexistent/hk".to_string())
+        );
+    }
+
+    #[test]
+    fn test_shell_program() {
+        assert_eq!(
+            shell_program("eslint {{files}}"),
+            Some("eslint".to_string())
+        );
+        assert_eq!(
+            shell_program("RUFF_CACHE=.cache exec ruff check {{files}}"),
+            Some("ruff".to_string())
+        );
+        assert_eq!(
+            shell_program("# lint\n./scripts/lint.sh"),
+            Some("./scripts/lint.sh".to_string())
+        );
+        assert_eq!(shell_program("test -f foo && bar"), None);
+        assert_eq!(shell_program("{{ tool }} --check"), None);
+        assert_eq!(shell_program("$LINTER ."), None);
+    }
+
+    #[test]
+    fn test_find_on_path() {
+        let tmp = tempfile::tempdir().unwrap();
+        let bin = tmp.path().join("mytool");
+        std::fs::write(&bin, "#!/bin/sh\n").unwrap();
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(&bin, std::fs::Permissions::from_mode(0o755)).unwrap();
+        }
+        let path = std::env::join_paths([tmp.path()]).unwrap();
+        assert_eq!(find_on_path("mytool", Some(&path)), Some(bin));
+        assert_eq!(find_on_path("othertool", Some(&path)), None);
+    }
+
+    #[test]
+    fn test_dir_stats_finds_corrupt_json() {
+        let tmp = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(tmp.path().join("configs")).unwrap();
+        std::fs::write(tmp.path().join("configs/good.json"), "{}").unwrap();
+        std::fs::write(tmp.path().join("configs/bad.json"), "{").unwrap();
+        let (files, bytes, corrupt) = dir_stats(tmp.path());
+        assert_eq!(files, 2);
+        assert_eq!(bytes, 3);
+        assert_eq!(corrupt, [tmp.path().join("configs/bad.json")]);
+    }
+}
@@ -1,47 +1,154 @@
+use std::collections::BTreeMap;
 use std::path::Path;
 
-use crate::builtins::{BUILTINS_META, BuiltinMeta};
+use crate::builtins::{BUILTINS_META, BuiltinMeta, ProjectIndicator};
+
+use super::tool_configs;
+
+/// How many directory levels below the project root are searched for
+/// subprojects (e.g. `apps/web`, `services/api/v2`)
+const MAX_DEPTH: usize = 4;
 
 /// Detection result for project files
 #[derive(Debug)]
 pub struct Detection {
     pub builtin: &'static BuiltinMeta,
     pub reason: String,
+    /// Subdirectory the builtin should be scoped to, or `None` for the project root
+    pub dir: Option<String>,
+    /// `exclude` patterns translated from the tool's ignore file
+    pub exclude: Vec<String>,
+    /// Environment the step needs to pick up the project's tool config
+    pub env: Vec<(String, String)>,
+}
+
+impl Detection {
+    pub fn new(builtin: &'static BuiltinMeta, reason: String, dir: Option<String>) -> Self {
+        Self {
+            builtin,
+            reason,
+            dir,
+            exclude: vec![],
+            env: vec![],
+        }
+    }
 }
 
-/// Detect relevant builtins for the current project based on project_indicators
+/// Detect relevant builtins for the project based on project_indicators.
+///
+/// The tree is walked (respecting .gitignore and friends) so tools used only by
+/// a subproject are found too. Builtins detected through a project file such as
+/// `apps/web/package.json` are scoped to that directory, since the tool runs
+/// against the subproject's own configuration. Builtins detected through file
+/// extensions (`infra/*.tf`) are not tied to a project file and run from the root.
+///
+/// The result is then tailored to the tool configs found in each directory
+/// (see [`tool_configs::tailor`]).
 pub fn detect_builtins(project_root: &Path) -> Vec<Detection> {
-    let mut detections = Vec::new();
+    let detections = detect_in_tree(project_root, &scan_tree(project_root), BUILTINS_META);
+    tool_configs::tailor(project_root, detections, BUILTINS_META)
+}
 
-    for meta in BUILTINS_META {
-        // Skip builtins without project indicators
-        if meta.project_indicators.is_empty() {
+/// Group detections by subdirectory, root detections first
+pub fn group_by_dir(detections: &[Detection]) -> BTreeMap<Option<&str>, Vec<&Detection>> {
+    let mut groups: BTreeMap<Option<&str>, Vec<&Detection>> = BTreeMap::new();
+    for detection in detections {
+        groups
+            .entry(detection.dir.as_deref())
+            .or_default()
+            .push(detection);
+    }
+    groups
+}
+
+/// Map each non-ignored directory (relative, '/'-separated, "" for the root)
+/// to the names of the files directly inside it.
+fn scan_tree(project_root: &Path) -> BTreeMap<String, Vec<String>> {
+    let mut tree: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    tree.insert(String::new(), vec![]);
+    let walker = ignore::WalkBuilder::new(project_root)
+        .max_depth(Some(MAX_DEPTH + 1))
+        // Detection should also work before `git init`
+        .require_git(false)
+        .filter_entry(|entry| entry.file_name() != "node_modules")
+        .build();
+    for entry in walker.flatten() {
+        let Ok(rel) = entry.path().strip_prefix(project_root) else {
             continue;
+        };
+        let rel = rel.to_string_lossy().replace('\\', "/");
+        let is_dir = entry.file_type().is_some_and(|ft| ft.is_dir());
+        if is_dir {
+            if entry.depth() <= MAX_DEPTH {
+                tree.entry(rel).or_default();
+            }
+        } else {
+            let (dir, name) = rel.rsplit_once('/').unwrap_or(("", &rel));
+            if let Some(files) = tree.get_mut(dir) {
+                files.push(name.to_string());
+            }
         }
+    }
+    tree
+}
 
-        // Check if any indicator matches
-        for indicator in meta.project_indicators {
-            if let Some(reason) = matches_indicator(project_root, indicator) {
-                detections.push(Detection {
-                    builtin: meta,
-                    reason,
-                });
-                break; // Only add each builtin once
+fn detect_in_tree(
+    project_root: &Path,
+    tree: &BTreeMap<String, Vec<String>>,
+    metas: &'static [BuiltinMeta],
+) -> Vec<Detection> {
+    let mut root = Vec::new();
+    let mut hoisted = Vec::new();
+    let mut scoped: Vec<Detection> = Vec::new();
+    // BTreeMap order visits the root first and parents before their children
+    for (dir, files) in tree {
+        for meta in metas {
+            let Some((reason, from_file)) = meta
+                .project_indicators
+                .iter()
+                .find_map(|indicator| matches_indicator(project_root, dir, files, indicator))
+            else {
+                continue;
+            };
+            let at_root = |list: &[Detection]| list.iter().any(|d| d.builtin.name == meta.name);
+            if dir.is_empty() {
+                root.push(Detection::new(meta, reason, None));
+            } else if at_root(&root) || at_root(&hoisted) {
+                continue;
+            } else if !from_file {
+                hoisted.push(Detection::new(meta, format!("{reason} in {dir}"), None));
+            } else if !scoped.iter().any(|d| {
+                d.builtin.name == meta.name
+                    && d.dir
+                        .as_deref()
+                        .is_some_and(|parent| dir.starts_with(&format!("{parent}/")))
+            }) {
+                scoped.push(Detection::new(
+                    meta,
+                    format!("{dir}/{reason}"),
+                    Some(dir.clone()),
+                ));
             }
         }
     }
-
-    detections
+    // A file-extension match deeper in the tree makes a scoped copy redundant
+    scoped.retain(|d| !hoisted.iter().any(|h| h.builtin.name == d.builtin.name));
+    root.extend(hoisted);
+    root.extend(scoped);
+    root
 }
 
-/// Check if a project indicator matches and return the reason if it does
+/// Check if a project indicator matches in `dir` and return the reason if it
+/// does, along with whether it matched a specific project file
 fn matches_indicator(
     project_root: &Path,
-    indicator: &crate::builtins::ProjectIndicator,
-) -> Option<String> {
+    dir: &str,
+    files: &[String],
+    indicator: &ProjectIndicator,
+) -> Option<(String, bool)> {
     // Handle file indicator (exact file or directory match)
     if let Some(file) = indicator.file {
-        let path = project_root.join(file);
+        let path = project_root.join(dir).join(file);
         if !path.exists() {
             return None;
         }
@@ -52,26 +159,22 @@ fn matches_indicator(
                 && let Ok(content) = std::fs::read_to_string(&path)
                 && content.contains(pattern)
             {
-                return Some(format!("{} contains {}", file, pattern));
+                return Some((format!("{} contains {}", file, pattern), true));
             }
             return None;
         }
 
-        return Some(file.to_string());
+        return Some((file.to_string(), true));
     }
 
     // Handle glob indicator
     if let Some(glob_pattern) = indicator.glob
         && let Some(ext) = glob_pattern.strip_prefix("*.")
-        && let Ok(entries) = std::fs::read_dir(project_root)
+        && files
+            .iter()
+            .any(|name| Path::new(name).extension().is_some_and(|e| e == ext))
     {
-        for entry in entries.flatten() {
-            if let Some(file_ext) = entry.path().extension()
-                && file_ext == ext
-            {
-                return Some(format!("{} files", glob_pattern));
-            }
-        }
+        return Some((format!("{} files", glob_pattern), false));
     }
 
     None
@@ -81,6 +184,52 @@ fn matches_indicator(
 mod tests {
     use super::*;
 
+    static TEST_METAS: &[BuiltinMeta] = &[
+        BuiltinMeta {
+            name: "prettier",
+            category: "JavaScript",
+            description: "",
+            project_indicators: &[ProjectIndicator {
+                file: Some("package.json"),
+                glob: None,
+                contains: None,
+            }],
+        },
+        BuiltinMeta {
+            name: "go_fmt",
+            category: "Go",
+            description: "",
+            project_indicators: &[ProjectIndicator {
+                file: Some("go.mod"),
+                glob: None,
+                contains: None,
+            }],
+        },
+        BuiltinMeta {
+            name: "terraform",
+            category: "Terraform",
+            description: "",
+            project_indicators: &[ProjectIndicator {
+                file: None,
+                glob: Some("*.tf"),
+                contains: None,
+            }],
+        },
+    ];
+
+    fn detect(root: &Path) -> Vec<(Option<String>, &'static str)> {
+        detect_in_tree(root, &scan_tree(root), TEST_METAS)
+            .into_iter()
+            .map(|d| (d.dir, d.builtin.name))
+            .collect()
+    }
+
+    fn write(root: &Path, path: &str, content: &str) {
+        let path = root.join(path);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, content).unwrap();
+    }
+
     #[test]
     fn test_detect_builtins_empty_dir() {
         let tmp = tempfile::tempdir().unwrap();
@@ -146,4 +295,53 @@ mod tests {
         let names: Vec<_> = detections.iter().map(|d| d.builtin.name).collect();
         assert!(names.contains(&"shellcheck"));
     }
+
+    #[test]
+    fn test_detect_monorepo_scopes_project_files_to_subdirectories() {
+        let tmp = tempfile::tempdir().unwrap();
+        write(tmp.path(), "apps/web/package.json", "{}");
+        write(tmp.path(), "apps/web/nested/package.json", "{}");
+        write(tmp.path(), "services/api/go.mod", "module api");
+        write(tmp.path(), "infra/main.tf", "");
+
+        assert_eq!(
+            detect(tmp.path()),
+            vec![
+                (None, "terraform"),
+                (Some("apps/web".to_string()), "prettier"),
+                (Some("services/api".to_string()), "go_fmt"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_detect_root_project_file_covers_subdirectories() {
+        let tmp = tempfile::tempdir().unwrap();
+        write(tmp.path(), "package.json", "{}");
+        write(tmp.path(), "packages/ui/package.json", "{}");
+
+        assert_eq!(detect(tmp.path()), vec![(None, "prettier")]);
+    }
+
+    #[test]
+    fn test_detect_respects_ignore_files() {
+        let tmp = tempfile::tempdir().unwrap();
+        write(tmp.path(), ".gitignore", "vendor/\n");
+        write(tmp.path(), "vendor/lib/go.mod", "module lib");
+        write(tmp.path(), "node_modules/pkg/package.json", "{}");
+
+        assert!(detect(tmp.path()).is_empty());
+    }
+
+    #[test]
+    fn test_group_by_dir_puts_root_first() {
+        let tmp = tempfile::tempdir().unwrap();
+        write(tmp.path(), "web/package.json", "{}");
+        write(tmp.path(), "main.tf", "");
+        let detections = detect_in_tree(tmp.path(), &scan_tree(tmp.path()), TEST_METAS);
+
+        let groups = group_by_dir(&detections);
+        let dirs: Vec<_> = groups.keys().copied().collect();
+        assert_eq!(dirs, vec![None, Some("web")]);
+    }
 }
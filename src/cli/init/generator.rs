@@ -1,7 +1,107 @@
 use crate::builtins::BuiltinMeta;
 
-/// Generate hk.pkl content based on selected builtins and hooks
-pub fn generate_pkl(builtins: &[&BuiltinMeta], hooks: &[String], version: &str) -> String {
+use super::detector::Detection;
+
+/// A builtin to add to the generated config
+#[derive(Debug, Clone, Copy)]
+pub struct Selection<'a> {
+    pub builtin: &'static BuiltinMeta,
+    /// Subdirectory to scope the step to with `dir`, or `None` for the project root
+    pub dir: Option<&'a str>,
+    /// Extra `exclude` patterns, e.g. carried over from `.prettierignore`
+    pub exclude: &'a [String],
+    /// Extra environment variables for the step
+    pub env: &'a [(String, String)],
+}
+
+impl<'a> Selection<'a> {
+    pub fn root(builtin: &'static BuiltinMeta) -> Self {
+        Self {
+            builtin,
+            dir: None,
+            exclude: &[],
+            env: &[],
+        }
+    }
+
+    pub fn from_detection(detection: &'a Detection) -> Self {
+        Self {
+            builtin: detection.builtin,
+            dir: detection.dir.as_deref(),
+            exclude: &detection.exclude,
+            env: &detection.env,
+        }
+    }
+
+    /// The step definition, e.g. `Builtins.prettier` or an amended
+    /// `(Builtins.prettier) { ... }` when it needs extra properties
+    fn step(&self) -> String {
+        // Pkl identifiers can't contain '-' (see scripts/gen_builtins.py)
+        let builtin = format!("Builtins.{}", self.builtin.name.replace('-', "_"));
+        let mut props = Vec::new();
+        if let Some(dir) = self.dir {
+            props.push(format!("dir = {}", pkl_string(dir)));
+        }
+        if !self.exclude.is_empty() {
+            let globs = self
+                .exclude
+                .iter()
+                .map(|glob| pkl_string(glob))
+                .collect::<Vec<_>>()
+                .join(", ");
+            props.push(format!("exclude = List({globs})"));
+        }
+        if !self.env.is_empty() {
+            let vars = self
+                .env
+                .iter()
+                .map(|(key, value)| format!("[{}] = {}", pkl_string(key), pkl_string(value)))
+                .collect::<Vec<_>>()
+                .join(" ");
+            props.push(format!("env {{ {vars} }}"));
+        }
+        match props.as_slice() {
+            [] => builtin,
+            [prop] => format!("({builtin}) {{ {prop} }}"),
+            props => {
+                let body: String = props
+                    .iter()
+                    .map(|prop| format!("        {prop}\n"))
+                    .collect();
+                format!("({builtin}) {{\n{body}    }}")
+            }
+        }
+    }
+}
+
+/// Quote `value` as a Pkl string literal, e.g. for paths and globs read
+/// from the project
+fn pkl_string(value: &str) -> String {
+    let mut quoted = String::with_capacity(value.len() + 2);
+    quoted.push('"');
+    for c in value.chars() {
+        match c {
+            '\\' => quoted.push_str("\\\\"),
+            '"' => quoted.push_str("\\\""),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// Generate hk.pkl content based on selected builtins and hooks.
+///
+/// `subprojects` lists directories that carry their own hk.pkl.
+pub fn generate_pkl(
+    builtins: &[Selection],
+    subprojects: &[String],
+    hooks: &[String],
+    version: &str,
+) -> String {
     let mut output = String::new();
 
     // Header with package import
@@ -13,13 +113,28 @@ import "package://github.com/jdx/hk/releases/download/v{version}/hk@{version}#/B
 "#
     ));
 
+    if !subprojects.is_empty() {
+        let dirs = subprojects
+            .iter()
+            .map(|dir| pkl_string(dir))
+            .collect::<Vec<_>>()
+            .join(", ");
+        output.push_str(&format!("subprojects = List({dirs})\n\n"));
+    }
+
     // Generate linters section (always define, even if empty)
     output.push_str("local linters = new Mapping<String, Step> {\n");
-    for meta in builtins {
-        output.push_str(&format!(
-            "    [\"{}\"] = Builtins.{}\n",
-            meta.name, meta.name
-        ));
+    for selection in builtins {
+        let name = selection.builtin.name;
+        let step = selection.step();
+        match selection.dir {
+            // Same "{dir}:{step}" naming that merged subproject steps get
+            Some(dir) => output.push_str(&format!(
+                "    [{}] = {step}\n",
+                pkl_string(&format!("{dir}:{name}"))
+            )),
+            None => output.push_str(&format!("    [\"{name}\"] = {step}\n")),
+        }
     }
     output.push_str("}\n\n");
 
@@ -118,7 +233,7 @@ mod tests {
     #[test]
     fn test_generate_pkl_empty() {
         let hooks = vec!["check".to_string()];
-        let pkl = generate_pkl(&[], &hooks, "1.34.0");
+        let pkl = generate_pkl(&[], &[], &hooks, "1.34.0");
         assert!(pkl.contains("amends"));
         assert!(pkl.contains("hooks"));
     }
@@ -126,15 +241,95 @@ mod tests {
     #[test]
     fn test_generate_pkl_with_builtins() {
         let prettier = BUILTINS_META.iter().find(|b| b.name == "prettier").unwrap();
-        let builtins = vec![prettier];
+        let builtins = vec![Selection::root(prettier)];
         let hooks = vec!["pre-commit".to_string(), "check".to_string()];
-        let pkl = generate_pkl(&builtins, &hooks, "1.34.0");
+        let pkl = generate_pkl(&builtins, &[], &hooks, "1.34.0");
 
         assert!(pkl.contains("Builtins.prettier"));
         assert!(pkl.contains("[\"pre-commit\"]"));
         assert!(pkl.contains("[\"check\"]"));
     }
 
+    #[test]
+    fn test_generate_pkl_with_dir_scoped_builtins_and_subprojects() {
+        static PRETTIER: BuiltinMeta = BuiltinMeta {
+            name: "prettier",
+            category: "JavaScript",
+            description: "",
+            project_indicators: &[],
+        };
+        let builtins = vec![
+            Selection::root(&PRETTIER),
+            Selection {
+                dir: Some("apps/web"),
+                ..Selection::root(&PRETTIER)
+            },
+        ];
+        let pkl = generate_pkl(&builtins, &["services/api".to_string()], &[], "1.34.0");
+
+        assert!(pkl.contains("subprojects = List(\"services/api\")\n"));
+        assert!(pkl.contains("    [\"prettier\"] = Builtins.prettier\n"));
+        assert!(pkl.contains(
+            "    [\"apps/web:prettier\"] = (Builtins.prettier) { dir = \"apps/web\" }\n"
+        ));
+    }
+
+    #[test]
+    fn test_generate_pkl_with_exclude_and_env() {
+        static ESLINT: BuiltinMeta = BuiltinMeta {
+            name: "eslint",
+            category: "JavaScript",
+            description: "",
+            project_indicators: &[],
+        };
+        static CHECKER: BuiltinMeta = BuiltinMeta {
+            name: "editorconfig-checker",
+            category: "",
+            description: "",
+            project_indicators: &[],
+        };
+        let exclude = vec!["**/dist/**".to_string()];
+        let env = vec![("ESLINT_USE_FLAT_CONFIG".to_string(), "false".to_string())];
+        let builtins = vec![
+            Selection {
+                exclude: &exclude,
+                env: &env,
+                ..Selection::root(&ESLINT)
+            },
+            Selection::root(&CHECKER),
+        ];
+        let pkl = generate_pkl(&builtins, &[], &[], "1.34.0");
+
+        assert!(pkl.contains(
+            "    [\"eslint\"] = (Builtins.eslint) {\n        exclude = List(\"**/dist/**\")\n        env { [\"ESLINT_USE_FLAT_CONFIG\"] = \"false\" }\n    }\n"
+        ));
+        assert!(pkl.contains("    [\"editorconfig-checker\"] = Builtins.editorconfig_checker\n"));
+    }
+
+    #[test]
+    fn test_generate_pkl_escapes_strings() {
+        static PRETTIER: BuiltinMeta = BuiltinMeta {
+            name: "prettier",
+            category: "JavaScript",
+            description: "",
+            project_indicators: &[],
+        };
+        let exclude = vec![r"foo\[1\].js".to_string(), r#"say "hi".js"#.to_string()];
+        let env = vec![("PATTERN".to_string(), r"a\(b)".to_string())];
+        let builtins = vec![Selection {
+            dir: Some(r#"we"ird"#),
+            exclude: &exclude,
+            env: &env,
+            ..Selection::root(&PRETTIER)
+        }];
+        let pkl = generate_pkl(&builtins, &[], &[], "1.34.0");
+
+        assert!(pkl.contains(r#"    ["we\"ird:prettier"] = (Builtins.prettier) {"#));
+        assert!(pkl.contains(r#"        dir = "we\"ird""#));
+        assert!(pkl.contains(r#"        exclude = List("foo\\[1\\].js", "say \"hi\".js")"#));
+        assert!(pkl.contains(r#"        env { ["PATTERN"] = "a\\(b)" }"#));
+    }
+
     #[test]
     fn test_default_template() {
         let template = generate_default_template("1.34.0");
@@ -148,7 +343,7 @@ mod tests {
         let expected = format!(
             "import \"package://github.com/jdx/hk/releases/download/v{version}/hk@{version}#/Builtins.pkl\"\n// Using a coding agent? See https://hk.jdx.dev/agents"
         );
-        assert!(generate_pkl(&[], &[], version).contains(&expected));
+        assert!(generate_pkl(&[], &[], &[], version).contains(&expected));
         assert!(generate_default_template(version).contains(&expected));
     }
 }
@@ -1,6 +1,7 @@
 mod detector;
 mod generator;
 mod picker;
+mod tool_configs;
 
 use std::path::PathBuf;
 
@@ -24,6 +25,12 @@ pub struct Init {
     /// Set HK_MISE=1 to make this default behavior.
     #[clap(long, verbatim_doc_comment)]
     mise: bool,
+    /// Write an hk.pkl into each detected subproject and list them in `subprojects`
+    ///
+    /// By default, tools detected in subdirectories are added to the root hk.pkl
+    /// as steps scoped to that directory with `dir`.
+    #[clap(long, verbatim_doc_comment)]
+    subprojects: bool,
 }
 
 impl Init {
@@ -51,7 +58,7 @@ impl Init {
             self.run_interactive(&detections, version)?
         } else {
             // Auto mode (default): use detected builtins or fall back to template
-            self.run_auto(&detections, version)
+            self.run_auto(&detections, version)?
         };
 
         // Write the file
@@ -61,7 +68,10 @@ impl Init {
         if !detections.is_empty() && !self.interactive {
             let summary = detections
                 .iter()
-                .map(|d| format!("{} ({})", d.builtin.name, d.reason))
+                .map(|d| match &d.dir {
+                    Some(dir) => format!("{dir}: {} ({})", d.builtin.name, d.reason),
+                    None => format!("{} ({})", d.builtin.name, d.reason),
+                })
                 .collect::<Vec<_>>()
                 .join(", ");
             info!("Detected: {}", summary);
@@ -75,11 +85,19 @@ impl Init {
         // Print detection info
         if !detections.is_empty() {
             info!("Scanning project...");
-            for detection in detections {
-                info!(
-                    "  Detected: {} ({})",
-                    detection.builtin.name, detection.reason
-                );
+            for (dir, group) in detector::group_by_dir(detections) {
+                let indent = if let Some(dir) = dir {
+                    info!("  {dir}/");
+                    "    "
+                } else {
+                    "  "
+                };
+                for detection in group {
+                    info!(
+                        "{indent}Detected: {} ({})",
+                        detection.builtin.name, detection.reason
+                    );
+                }
             }
         }
 
@@ -100,20 +118,67 @@ impl Init {
             hooks
         };
 
-        Ok(generator::generate_pkl(&builtins, &hooks, version))
+        self.generate(&builtins, &hooks, version)
     }
 
-    fn run_auto(&self, detections: &[detector::Detection], version: &str) -> String {
+    fn run_auto(&self, detections: &[detector::Detection], version: &str) -> Result<String> {
         if detections.is_empty() {
             // No detections - use default template
-            return generator::generate_default_template(version);
+            return Ok(generator::generate_default_template(version));
         }
 
         // Use detected builtins with default hooks
-        let builtins: Vec<_> = detections.iter().map(|d| d.builtin).collect();
+        let builtins: Vec<_> = detections
+            .iter()
+            .map(generator::Selection::from_detection)
+            .collect();
         let hooks: Vec<String> = DEFAULT_HOOKS.iter().map(|s| s.to_string()).collect();
 
-        generator::generate_pkl(&builtins, &hooks, version)
+        self.generate(&builtins, &hooks, version)
+    }
+
+    /// Generate the root hk.pkl. With `--subprojects`, dir-scoped builtins are
+    /// written to an hk.pkl in their own directory instead.
+    fn generate(
+        &self,
+        builtins: &[generator::Selection],
+        hooks: &[String],
+        version: &str,
+    ) -> Result<String> {
+        if !self.subprojects {
+            return Ok(generator::generate_pkl(builtins, &[], hooks, version));
+        }
+        let root = builtins
+            .iter()
+            .filter(|s| s.dir.is_none())
+            .copied()
+            .collect::<Vec<_>>();
+        let mut subprojects: Vec<String> = Vec::new();
+        for selection in builtins {
+            if let Some(dir) = selection.dir
+                && !subprojects.iter().any(|d| d == dir)
+            {
+                subprojects.push(dir.to_string());
+            }
+        }
+        for dir in &subprojects {
+            let hk_file = PathBuf::from(dir).join("hk.pkl");
+            if hk_file.exists() && !self.force {
+                warn!(
+                    "{} already exists, run with --force to overwrite",
+                    hk_file.display()
+                );
+                continue;
+            }
+            let sub = builtins
+                .iter()
+                .filter(|s| s.dir == Some(dir.as_str()))
+                .map(|s| generator::Selection { dir: None, ..*s })
+                .collect::<Vec<_>>();
+            xx::file::write(&hk_file, generator::generate_pkl(&sub, &[], hooks, version))?;
+            info!("Created {}", hk_file.display());
+        }
+        Ok(generator::generate_pkl(&root, &subprojects, hooks, version))
     }
 
     fn write_mise_toml(&self) -> Result<()> {
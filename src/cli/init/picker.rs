@@ -1,28 +1,32 @@
 use demand::DemandOption;
 
 use crate::Result;
-use crate::builtins::{BUILTINS_META, BuiltinMeta};
+use crate::builtins::BUILTINS_META;
 
 use super::DEFAULT_HOOKS;
 use super::detector::Detection;
+use super::generator::Selection;
 
 /// Let user select builtins interactively
-pub fn pick_builtins(detected: &[Detection]) -> Result<Vec<&'static BuiltinMeta>> {
-    let detected_names: std::collections::HashSet<&str> =
-        detected.iter().map(|d| d.builtin.name).collect();
+pub fn pick_builtins(detected: &[Detection]) -> Result<Vec<Selection<'_>>> {
+    let detected_names: std::collections::HashSet<&str> = detected
+        .iter()
+        .filter(|d| d.dir.is_none())
+        .map(|d| d.builtin.name)
+        .collect();
 
     // Build options with detected items pre-selected
-    let mut options: Vec<(&'static BuiltinMeta, bool)> = Vec::new();
+    let mut options: Vec<(Selection, bool)> = Vec::new();
 
     // Add detected builtins first (pre-selected)
     for detection in detected {
-        options.push((detection.builtin, true));
+        options.push((Selection::from_detection(detection), true));
     }
 
     // Add remaining builtins grouped by category
     for meta in BUILTINS_META {
         if !detected_names.contains(meta.name) {
-            options.push((meta, false));
+            options.push((Selection::root(meta), false));
         }
     }
 
@@ -31,22 +35,27 @@ pub fn pick_builtins(detected: &[Detection]) -> Result<Vec<&'static BuiltinMeta>
         .description("Space to toggle, Enter to confirm")
         .filterable(true);
 
-    for (meta, selected) in &options {
-        let label = format!("{}/{}", meta.category, meta.name);
-        let opt = DemandOption::new(meta.name)
+    for (index, (selection, selected)) in options.iter().enumerate() {
+        let meta = selection.builtin;
+        let label = match selection.dir {
+            Some(dir) => format!("{}/{} ({dir})", meta.category, meta.name),
+            None => format!("{}/{}", meta.category, meta.name),
+        };
+        let opt = DemandOption::new(index)
             .label(&label)
             .description(meta.description)
             .selected(*selected);
         ms = ms.option(opt);
     }
 
-    let selected_names: Vec<&str> = ms.run()?;
+    let selected: Vec<usize> = ms.run()?;
 
-    // Map selected names back to BuiltinMeta
-    let result: Vec<&'static BuiltinMeta> = options
+    // Map selected options back to their builtins
+    let result = options
         .iter()
-        .filter(|(meta, _)| selected_names.contains(&meta.name))
-        .map(|(meta, _)| *meta)
+        .enumerate()
+        .filter(|(index, _)| selected.contains(index))
+        .map(|(_, (selection, _))| *selection)
         .collect();
 
     Ok(result)
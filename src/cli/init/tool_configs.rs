@@ -0,0 +1,471 @@
+use std::path::Path;
+
+use crate::builtins::BuiltinMeta;
+
+use super::detector::Detection;
+
+const ESLINT_FLAT_CONFIGS: &[&str] = &[
+    "eslint.config.js",
+    "eslint.config.mjs",
+    "eslint.config.cjs",
+    "eslint.config.ts",
+    "eslint.config.mts",
+    "eslint.config.cts",
+];
+const ESLINT_LEGACY_CONFIGS: &[&str] = &[
+    ".eslintrc",
+    ".eslintrc.js",
+    ".eslintrc.cjs",
+    ".eslintrc.json",
+    ".eslintrc.yaml",
+    ".eslintrc.yml",
+];
+const PRETTIER_CONFIGS: &[&str] = &[
+    ".prettierrc",
+    ".prettierrc.json",
+    ".prettierrc.json5",
+    ".prettierrc.yaml",
+    ".prettierrc.yml",
+    ".prettierrc.toml",
+    ".prettierrc.js",
+    ".prettierrc.cjs",
+    ".prettierrc.mjs",
+    "prettier.config.js",
+    "prettier.config.cjs",
+    "prettier.config.mjs",
+    "prettier.config.ts",
+];
+const BIOME_CONFIGS: &[&str] = &["biome.json", "biome.jsonc"];
+const RUFF_CONFIGS: &[&str] = &["ruff.toml", ".ruff.toml"];
+const RUSTFMT_CONFIGS: &[&str] = &["rustfmt.toml", ".rustfmt.toml"];
+
+/// Which ESLint configuration format a project uses
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EslintConfig {
+    /// `eslint.config.*`
+    Flat,
+    /// `.eslintrc*` or `eslintConfig` in package.json
+    Legacy,
+}
+
+/// Tool configuration found in a single project directory. Each field names
+/// the file that configured the tool so it can be reported as the reason.
+#[derive(Debug, Default)]
+pub struct ToolConfigs {
+    pub eslint: Option<(EslintConfig, String)>,
+    pub prettier: Option<String>,
+    pub biome: Option<String>,
+    pub ruff: Option<String>,
+    pub black: Option<String>,
+    pub isort: Option<String>,
+    pub rustfmt: Option<String>,
+    pub cargo: bool,
+    pub editorconfig: bool,
+    /// Patterns from `.prettierignore`
+    pub prettier_ignore: Vec<String>,
+    /// Patterns from `.eslintignore` (only honored by legacy configs)
+    pub eslint_ignore: Vec<String>,
+}
+
+impl ToolConfigs {
+    pub fn read(dir: &Path) -> Self {
+        let find = |names: &[&str]| {
+            names
+                .iter()
+                .find(|name| dir.join(name).is_file())
+                .map(|name| name.to_string())
+        };
+        let package_json = std::fs::read_to_string(dir.join("package.json"))
+            .ok()
+            .and_then(|content| serde_json::from_str::<serde_json::Value>(&content).ok());
+        let package_key = |key: &str| {
+            package_json
+                .as_ref()
+                .is_some_and(|pkg| pkg.get(key).is_some())
+                .then(|| format!("{key} in package.json"))
+        };
+        let pyproject = std::fs::read_to_string(dir.join("pyproject.toml"))
+            .ok()
+            .and_then(|content| toml::from_str::<toml::Value>(&content).ok());
+        let pyproject_tool = |tool: &str| {
+            pyproject
+                .as_ref()
+                .and_then(|p| p.get("tool")?.get(tool))
+                .map(|_| format!("[tool.{tool}] in pyproject.toml"))
+        };
+        let read_ignore = |name: &str| {
+            std::fs::read_to_string(dir.join(name))
+                .map(|content| ignore_to_globs(&content))
+                .unwrap_or_default()
+        };
+
+        let eslint = find(ESLINT_FLAT_CONFIGS)
+            .map(|file| (EslintConfig::Flat, file))
+            .or_else(|| {
+                find(ESLINT_LEGACY_CONFIGS)
+                    .or_else(|| package_key("eslintConfig"))
+                    .map(|file| (EslintConfig::Legacy, file))
+            });
+        Self {
+            eslint,
+            prettier: find(PRETTIER_CONFIGS).or_else(|| package_key("prettier")),
+            biome: find(BIOME_CONFIGS),
+            ruff: find(RUFF_CONFIGS).or_else(|| pyproject_tool("ruff")),
+            black: pyproject_tool("black"),
+            isort: find(&[".isort.cfg"]).or_else(|| pyproject_tool("isort")),
+            rustfmt: find(RUSTFMT_CONFIGS),
+            cargo: dir.join("Cargo.toml").is_file(),
+            editorconfig: dir.join(".editorconfig").is_file(),
+            prettier_ignore: read_ignore(".prettierignore"),
+            eslint_ignore: read_ignore(".eslintignore"),
+        }
+    }
+}
+
+/// Adjust detections using the tool configuration in each detected directory:
+/// pick between competing builtins, skip formatters that would fight over the
+/// same files, and carry ignore files over as `exclude` patterns.
+pub fn tailor(
+    project_root: &Path,
+    detections: Vec<Detection>,
+    metas: &'static [BuiltinMeta],
+) -> Vec<Detection> {
+    let mut dirs: Vec<Option<String>> = vec![None];
+    for detection in &detections {
+        if !dirs.contains(&detection.dir) {
+            dirs.push(detection.dir.clone());
+        }
+    }
+    let mut out = Vec::new();
+    let mut detections = detections;
+    for dir in dirs {
+        let (group, rest): (Vec<_>, Vec<_>) = detections.into_iter().partition(|d| d.dir == dir);
+        detections = rest;
+        let configs = ToolConfigs::read(&project_root.join(dir.as_deref().unwrap_or("")));
+        let mut group = Group {
+            dir,
+            detections: group,
+            metas,
+        };
+        group.apply(&configs);
+        out.extend(group.detections);
+    }
+    out
+}
+
+/// Detections for one directory while they are being tailored
+struct Group {
+    dir: Option<String>,
+    detections: Vec<Detection>,
+    metas: &'static [BuiltinMeta],
+}
+
+impl Group {
+    fn apply(&mut self, configs: &ToolConfigs) {
+        self.apply_javascript(configs);
+        self.apply_python(configs);
+        if let Some(file) = &configs.rustfmt
+            && !configs.cargo
+        {
+            self.add("rustfmt", file);
+        }
+        if configs.editorconfig && self.dir.is_none() {
+            self.add("editorconfig-checker", ".editorconfig");
+        }
+    }
+
+    fn apply_javascript(&mut self, configs: &ToolConfigs) {
+        if let Some(file) = &configs.biome {
+            self.add("biome", file);
+            // Both format the same files; biome's own config wins
+            self.remove("prettier", file);
+            if configs.eslint.is_none() {
+                self.remove("eslint", file);
+            }
+        } else if configs.prettier.is_some() || configs.eslint.is_some() {
+            let file = configs
+                .prettier
+                .as_ref()
+                .or(configs.eslint.as_ref().map(|e| &e.1));
+            self.remove("biome", file.unwrap());
+        }
+        if let Some((format, file)) = &configs.eslint {
+            self.add("eslint", file);
+            if *format == EslintConfig::Legacy
+                && let Some(eslint) = self.get_mut("eslint")
+            {
+                // ESLint 9 only reads legacy configs with flat config disabled
+                eslint
+                    .env
+                    .push(("ESLINT_USE_FLAT_CONFIG".to_string(), "false".to_string()));
+                eslint.exclude.extend(configs.eslint_ignore.iter().cloned());
+            }
+        }
+        if configs.biome.is_none()
+            && let Some(file) = &configs.prettier
+        {
+            self.add("prettier", file);
+        }
+        if let Some(prettier) = self.get_mut("prettier") {
+            prettier
+                .exclude
+                .extend(configs.prettier_ignore.iter().cloned());
+        }
+    }
+
+    fn apply_python(&mut self, configs: &ToolConfigs) {
+        if let Some(file) = &configs.ruff {
+            self.add("ruff", file);
+            match &configs.black {
+                // Keep the formatter the project configured
+                Some(black) => {
+                    self.add("black", black);
+                    self.remove("ruff_format", black);
+                }
+                None => {
+                    self.add("ruff_format", file);
+                    self.remove("black", file);
+                }
+            }
+        } else if configs.black.is_some() || configs.isort.is_some() {
+            let file = configs.black.as_ref().or(configs.isort.as_ref()).unwrap();
+            self.remove("ruff", file);
+            self.remove("ruff_format", file);
+            if let Some(black) = &configs.black {
+                self.add("black", black);
+            }
+        }
+        if let Some(isort) = &configs.isort {
+            self.add("isort", isort);
+        }
+    }
+
+    fn get_mut(&mut self, name: &str) -> Option<&mut Detection> {
+        self.detections.iter_mut().find(|d| d.builtin.name == name)
+    }
+
+    fn add(&mut self, name: &str, file: &str) {
+        if self.get_mut(name).is_some() {
+            return;
+        }
+        let Some(builtin) = self.metas.iter().find(|meta| meta.name == name) else {
+            return;
+        };
+        let reason = match &self.dir {
+            Some(dir) => format!("{dir}/{file}"),
+            None => file.to_string(),
+        };
+        self.detections
+            .push(Detection::new(builtin, reason, self.dir.clone()));
+    }
+
+    fn remove(&mut self, name: &str, file: &str) {
+        if let Some(index) = self.detections.iter().position(|d| d.builtin.name == name) {
+            self.detections.remove(index);
+            match &self.dir {
+                Some(dir) => info!("Skipping {name} in {dir}: using {file} instead"),
+                None => info!("Skipping {name}: using {file} instead"),
+            }
+        }
+    }
+}
+
+/// Translate gitignore-style patterns into hk `exclude` globs. Negated
+/// patterns cannot be expressed as an exclude and are dropped.
+pub fn ignore_to_globs(content: &str) -> Vec<String> {
+    let mut globs = Vec::new();
+    for line in content.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        if line.starts_with('!') {
+            debug!("init: negated ignore pattern not translated: {line}");
+            continue;
+        }
+        let (pattern, dir_only) = match line.strip_suffix('/') {
+            Some(pattern) => (pattern, true),
+            None => (line, false),
+        };
+        // A slash anywhere but the end anchors the pattern to the ignore file's directory
+        let pattern = match pattern.strip_prefix('/') {
+            Some(anchored) => anchored.to_string(),
+            None if pattern.contains('/') || pattern.starts_with("**") => pattern.to_string(),
+            None => format!("**/{pattern}"),
+        };
+        if pattern.ends_with("/**") {
+            globs.push(pattern);
+            continue;
+        }
+        if !dir_only {
+            globs.push(pattern.clone());
+        }
+        globs.push(format!("{pattern}/**"));
+    }
+    globs.dedup();
+    globs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builtins::ProjectIndicator;
+
+    const NO_INDICATORS: &[ProjectIndicator] = &[];
+
+    static TEST_METAS: &[BuiltinMeta] = &[
+        meta("biome"),
+        meta("black"),
+        meta("eslint"),
+        meta("isort"),
+        meta("prettier"),
+        meta("ruff"),
+        meta("ruff_format"),
+        meta("rustfmt"),
+    ];
+
+    const fn meta(name: &'static str) -> BuiltinMeta {
+        BuiltinMeta {
+            name,
+            category: "",
+            description: "",
+            project_indicators: NO_INDICATORS,
+        }
+    }
+
+    fn detected(name: &str) -> Detection {
+        let builtin = TEST_METAS.iter().find(|m| m.name == name).unwrap();
+        Detection::new(builtin, "package.json".to_string(), None)
+    }
+
+    fn names(detections: &[Detection]) -> Vec<&str> {
+        let mut names: Vec<_> = detections.iter().map(|d| d.builtin.name).collect();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn test_biome_config_replaces_prettier_and_eslint() {
+        let tmp = tempfile::tempdir().unwrap();
+        std::fs::write(tmp.path().join("biome.json"), "{}").unwrap();
+        let detections = tailor(
+            tmp.path(),
+            vec![detected("prettier"), detected("eslint")],
+            TEST_METAS,
+        );
+        assert_eq!(names(&detections), ["biome"]);
+        assert_eq!(detections[0].reason, "biome.json");
+    }
+
+    #[test]
+    fn test_eslint_and_prettier_configs_drop_biome_dependency() {
+        let tmp = tempfile::tempdir().unwrap();
+        std::fs::write(tmp.path().join("eslint.config.mjs"), "").unwrap();
+        std::fs::write(tmp.path().join(".prettierrc"), "{}").unwrap();
+        std::fs::write(tmp.path().join(".prettierignore"), "dist\n/build/\n").unwrap();
+        let detections = tailor(tmp.path(), vec![detected("biome")], TEST_METAS);
+
+        assert_eq!(names(&detections), ["eslint", "prettier"]);
+        let eslint = detections
+            .iter()
+            .find(|d| d.builtin.name == "eslint")
+            .unwrap();
+        assert!(eslint.env.is_empty());
+        let prettier = detections
+            .iter()
+            .find(|d| d.builtin.name == "prettier")
+            .unwrap();
+        assert_eq!(prettier.exclude, ["**/dist", "**/dist/**", "build/**"]);
+    }
+
+    #[test]
+    fn test_legacy_eslint_config_disables_flat_config() {
+        let tmp = tempfile::tempdir().unwrap();
+        std::fs::write(tmp.path().join(".eslintrc.json"), "{}").unwrap();
+        std::fs::write(tmp.path().join(".eslintignore"), "*.min.js\n").unwrap();
+        let detections = tailor(tmp.path(), vec![], TEST_METAS);
+
+        assert_eq!(names(&detections), ["eslint"]);
+        assert_eq!(
+            detections[0].env,
+            [("ESLINT_USE_FLAT_CONFIG".to_string(), "false".to_string())]
+        );
+        assert_eq!(detections[0].exclude, ["**/*.min.js", "**/*.min.js/**"]);
+    }
+
+    #[test]
+    fn test_pyproject_black_and_isort_replace_ruff() {
+        let tmp = tempfile::tempdir().unwrap();
+        std::fs::write(
+            tmp.path().join("pyproject.toml"),
+            "[tool.black]\nline-length = 100\n[tool.isort]\nprofile = \"black\"\n",
+        )
+        .unwrap();
+        let detections = tailor(
+            tmp.path(),
+            vec![detected("ruff"), detected("ruff_format")],
+            TEST_METAS,
+        );
+        assert_eq!(names(&detections), ["black", "isort"]);
+    }
+
+    #[test]
+    fn test_pyproject_ruff_uses_ruff_format_instead_of_black() {
+        let tmp = tempfile::tempdir().unwrap();
+        std::fs::write(
+            tmp.path().join("pyproject.toml"),
+            "[tool.ruff]\nline-length = 100\n# black is mentioned here\n",
+        )
+        .unwrap();
+        let detections = tailor(tmp.path(), vec![detected("black")], TEST_METAS);
+        assert_eq!(names(&detections), ["ruff", "ruff_format"]);
+    }
+
+    #[test]
+    fn test_rustfmt_config_without_cargo_adds_rustfmt() {
+        let tmp = tempfile::tempdir().unwrap();
+        std::fs::write(tmp.path().join("rustfmt.toml"), "").unwrap();
+        let detections = tailor(tmp.path(), vec![], TEST_METAS);
+        assert_eq!(names(&detections), ["rustfmt"]);
+
+        std::fs::write(tmp.path().join("Cargo.toml"), "").unwrap();
+        assert!(tailor(tmp.path(), vec![], TEST_METAS).is_empty());
+    }
+
+    #[test]
+    fn test_subdirectory_configs_apply_to_their_own_detections() {
+        let tmp = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(tmp.path().join("web")).unwrap();
+        std::fs::write(tmp.path().join("web/biome.jsonc"), "{}").unwrap();
+        let mut web = detected("prettier");
+        web.dir = Some("web".to_string());
+        let detections = tailor(tmp.path(), vec![detected("prettier"), web], TEST_METAS);
+
+        let summary: Vec<_> = detections
+            .iter()
+            .map(|d| (d.dir.as_deref(), d.builtin.name, d.reason.as_str()))
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                (None, "prettier", "package.json"),
+                (Some("web"), "biome", "web/biome.jsonc"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_ignore_patterns_become_exclude_globs() {
+        let globs =
+            ignore_to_globs("# comment\n\nnode_modules/\n/coverage\n!keep.js\nsrc/gen/**\n");
+        assert_eq!(
+            globs,
+            [
+                "**/node_modules/**",
+                "coverage",
+                "coverage/**",
+                "src/gen/**",
+            ]
+        );
+    }
+}
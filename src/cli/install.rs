@@ -7,7 +7,8 @@ use std::process::Command;
 
 /// Hook events installed by default for `hk install --global` when no project
 /// config is available to enumerate a more specific set.
-const CORE_GLOBAL_EVENTS: &[&str] = &["commit-msg", "pre-commit", "pre-push", "prepare-commit-msg"];
+pub(crate) const CORE_GLOBAL_EVENTS: &[&str] =
+    &["commit-msg", "pre-commit", "pre-push", "prepare-commit-msg"];
 
 /// Sets up git hooks to run hk.
 ///
@@ -179,7 +180,7 @@ fn global_hook_events() -> Result<Vec<String>> {
         .collect())
 }
 
-fn hook_events(config: &Config) -> Vec<String> {
+pub(crate) fn hook_events(config: &Config) -> Vec<String> {
     config
         .hooks
         .keys()
@@ -471,10 +472,7 @@ pub(crate) fn remove_local_shims() -> Result<usize> {
             Ok(content) => content,
             Err(_) => continue,
         };
-        // Match the HK=0 guard that every hk-written shim has. This is more
-        // specific than `hk run` alone, which could appear in an unrelated
-        // user-written hook.
-        if content.contains(r#"test "${HK:-1}" = "0""#) && content.contains("hk run") {
+        if is_hk_shim(&content) {
             xx::file::remove_file(&p)?;
             info!("removed hook: {}", xx::file::display_path(&p));
             removed += 1;
@@ -483,6 +481,13 @@ pub(crate) fn remove_local_shims() -> Result<usize> {
     Ok(removed)
 }
 
+/// Match the HK=0 guard that every hk-written shim has. This is more
+/// specific than `hk run` alone, which could appear in an unrelated
+/// user-written hook.
+pub(crate) fn is_hk_shim(content: &str) -> bool {
+    content.contains(r#"test "${HK:-1}" = "0""#) && content.contains("hk run")
+}
+
 fn run_git<I, S>(args: I) -> Result<()>
 where
     I: IntoIterator<Item = S>,
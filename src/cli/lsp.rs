@@ -0,0 +1,772 @@
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
+
+use serde_json::{Value, json};
+use tokio::{
+    io::{AsyncBufRead, AsyncBufReadExt, AsyncReadExt, AsyncWrite, AsyncWriteExt, BufReader},
+    process::Command,
+    sync::Mutex,
+};
+
+use crate::{
+    Result,
+    diagnostics::{Diagnostic, Severity},
+};
+
+const DEBOUNCE: Duration = Duration::from_millis(300);
+const FIX_COMMAND: &str = "hk.fix";
+
+/// Runs a Language Server Protocol server for editors over standard input/output
+///
+/// Matching check steps run for a single file when it is opened, edited, or
+/// saved, and their normalized diagnostics are published to the editor.
+/// Unsaved edits are checked on a temporary copy of the buffer. Code actions
+/// apply diagnostic fixes or run the step's fix command.
+#[derive(clap::Args)]
+pub struct Lsp {}
+
+impl Lsp {
+    pub async fn run(self) -> Result<()> {
+        let stdin = BufReader::new(tokio::io::stdin());
+        let stdout = tokio::io::stdout();
+        serve(stdin, stdout).await
+    }
+}
+
+/// An editor buffer and what hk last reported for it.
+#[derive(Debug, Default)]
+struct Document {
+    text: String,
+    /// Bumped on every open/change/save so a debounced run can tell whether it
+    /// has been superseded.
+    generation: u64,
+    diagnostics: Vec<Diagnostic>,
+}
+
+#[derive(Debug, Default)]
+struct LspState {
+    root: Option<PathBuf>,
+    documents: HashMap<String, Document>,
+    shutdown: bool,
+}
+
+struct LspServer<W> {
+    state: Arc<Mutex<LspState>>,
+    writer: Arc<Mutex<W>>,
+}
+
+impl<W> Clone for LspServer<W> {
+    fn clone(&self) -> Self {
+        Self {
+            state: self.state.clone(),
+            writer: self.writer.clone(),
+        }
+    }
+}
+
+async fn serve<R, W>(mut reader: R, writer: W) -> Result<()>
+where
+    R: AsyncBufRead + Unpin,
+    W: AsyncWrite + Unpin + Send + 'static,
+{
+    let server = LspServer {
+        state: Arc::new(Mutex::new(LspState::default())),
+        writer: Arc::new(Mutex::new(writer)),
+    };
+    while let Some(body) = read_body(&mut reader).await? {
+        let message: Value = match serde_json::from_slice(&body) {
+            Ok(message) => message,
+            Err(err) => {
+                // The id is unreadable, so the error cannot be tied to a request.
+                server
+                    .respond(
+                        Some(Value::Null),
+                        Err((-32700, format!("parse error: {err}"))),
+                    )
+                    .await?;
+                continue;
+            }
+        };
+        if message.get("method").and_then(Value::as_str) == Some("exit") {
+            break;
+        }
+        let id = message.get("id").cloned();
+        if let Err(err) = server.handle(message).await {
+            warn!("hk lsp: {err}");
+            server.respond(id, Err((-32603, err.to_string()))).await?;
+        }
+    }
+    Ok(())
+}
+
+impl<W> LspServer<W>
+where
+    W: AsyncWrite + Unpin + Send + 'static,
+{
+    async fn handle(&self, message: Value) -> Result<()> {
+        let Some(method) = message.get("method").and_then(Value::as_str) else {
+            // Responses to server-initiated requests are not used.
+            return Ok(());
+        };
+        let id = message.get("id").cloned();
+        let params = message.get("params").cloned().unwrap_or(Value::Null);
+        let result = match method {
+            "initialize" => Ok(Some(self.initialize(&params).await)),
+            "shutdown" => {
+                self.state.lock().await.shutdown = true;
+                Ok(Some(Value::Null))
+            }
+            "textDocument/didOpen" => {
+                let document = &params["textDocument"];
+                if let (Some(uri), Some(text)) = (
+                    document["uri"].as_str(),
+                    document["text"].as_str().map(str::to_string),
+                ) {
+                    self.update(uri, Some(text)).await;
+                }
+                Ok(None)
+            }
+            "textDocument/didChange" => {
+                // Only full-document sync is advertised, so the last change holds the buffer.
+                let text = params["contentChanges"]
+                    .as_array()
+                    .and_then(|changes| changes.last())
+                    .and_then(|change| change["text"].as_str())
+                    .map(str::to_string);
+                if let Some(uri) = params["textDocument"]["uri"].as_str() {
+                    self.update(uri, text).await;
+                }
+                Ok(None)
+            }
+            "textDocument/didSave" => {
+                if let Some(uri) = params["textDocument"]["uri"].as_str() {
+                    let text = params["text"].as_str().map(str::to_string);
+                    self.update(uri, text).await;
+                }
+                Ok(None)
+            }
+            "textDocument/didClose" => {
+                if let Some(uri) = params["textDocument"]["uri"].as_str() {
+                    self.state.lock().await.documents.remove(uri);
+                    self.publish(uri, &[]).await?;
+                }
+                Ok(None)
+            }
+            "textDocument/codeAction" => Ok(Some(self.code_actions(&params).await)),
+            "workspace/executeCommand" => {
+                // Fixers can take a while; keep reading messages meanwhile.
+                let server = self.clone();
+                tokio::spawn(async move {
+                    let result = server.execute_command(&params).await.map(Some);
+                    if let Err(err) = server.respond(id, result).await {
+                        warn!("hk lsp: failed to respond to {FIX_COMMAND}: {err}");
+                    }
+                });
+                return Ok(());
+            }
+            _ if id.is_some() => Err((-32601, format!("method not found: {method}"))),
+            _ => Ok(None),
+        };
+        self.respond(id, result).await
+    }
+
+    /// Reply to a request; notifications (no id) get no reply.
+    async fn respond(
+        &self,
+        id: Option<Value>,
+        result: std::result::Result<Option<Value>, (i64, String)>,
+    ) -> Result<()> {
+        let Some(id) = id else {
+            return Ok(());
+        };
+        let response = match result {
+            Ok(result) => {
+                json!({"jsonrpc": "2.0", "id": id, "result": result.unwrap_or(Value::Null)})
+            }
+            Err((code, message)) => {
+                json!({"jsonrpc": "2.0", "id": id, "error": {"code": code, "message": message}})
+            }
+        };
+        self.send(&response).await
+    }
+
+    async fn initialize(&self, params: &Value) -> Value {
+        let root = params["rootUri"]
+            .as_str()
+            .and_then(uri_to_path)
+            .or_else(|| params["rootPath"].as_str().map(PathBuf::from))
+            .or_else(|| std::env::current_dir().ok());
+        self.state.lock().await.root = root;
+        json!({
+            "capabilities": {
+                "textDocumentSync": {
+                    "openClose": true,
+                    "change": 1,
+                    "save": {"includeText": false},
+                },
+                "codeActionProvider": {
+                    "codeActionKinds": ["quickfix", "source.fixAll.hk"],
+                },
+                "executeCommandProvider": {"commands": [FIX_COMMAND]},
+            },
+            "serverInfo": {"name": "hk", "version": env!("CARGO_PKG_VERSION")},
+        })
+    }
+
+    /// Record the latest buffer contents and schedule a debounced check.
+    async fn update(&self, uri: &str, text: Option<String>) {
+        let generation = {
+            let mut state = self.state.lock().await;
+            if state.shutdown {
+                return;
+            }
+            let document = state.documents.entry(uri.to_string()).or_default();
+            if let Some(text) = text {
+                document.text = text;
+            }
+            document.generation += 1;
+            document.generation
+        };
+        let server = self.clone();
+        let uri = uri.to_string();
+        tokio::spawn(async move {
+            tokio::time::sleep(DEBOUNCE).await;
+            if let Err(err) = server.check(&uri, generation).await {
+                warn!("hk lsp: failed to check {uri}: {err}");
+            }
+        });
+    }
+
+    async fn check(&self, uri: &str, generation: u64) -> Result<()> {
+        let Some(path) = uri_to_path(uri) else {
+            return Ok(());
+        };
+        let (root, text) = {
+            let state = self.state.lock().await;
+            let Some(document) = state.documents.get(uri) else {
+                return Ok(());
+            };
+            if document.generation != generation {
+                return Ok(());
+            }
+            (state.root.clone(), document.text.clone())
+        };
+        let root = root.unwrap_or_else(|| path.parent().unwrap_or(Path::new(".")).to_path_buf());
+        // Unsaved edits are checked on a copy so diagnostics match the buffer
+        let unsaved = std::fs::read_to_string(&path).is_ok_and(|saved| saved != text);
+        let diagnostics = run_check(&root, &path, unsaved.then_some(text)).await?;
+        {
+            let mut state = self.state.lock().await;
+            let Some(document) = state.documents.get_mut(uri) else {
+                return Ok(());
+            };
+            if document.generation != generation {
+                return Ok(());
+            }
+            document.diagnostics = diagnostics.clone();
+        }
+        self.publish(uri, &diagnostics).await
+    }
+
+    async fn publish(&self, uri: &str, diagnostics: &[Diagnostic]) -> Result<()> {
+        let diagnostics = diagnostics
+            .iter()
+            .map(to_lsp_diagnostic)
+            .collect::<Vec<_>>();
+        self.send(&json!({
+            "jsonrpc": "2.0",
+            "method": "textDocument/publishDiagnostics",
+            "params": {"uri": uri, "diagnostics": diagnostics},
+        }))
+        .await
+    }
+
+    async fn code_actions(&self, params: &Value) -> Value {
+        let Some(uri) = params["textDocument"]["uri"].as_str() else {
+            return json!([]);
+        };
+        let requested = params["context"]["diagnostics"]
+            .as_array()
+            .cloned()
+            .unwrap_or_default();
+        let state = self.state.lock().await;
+        let Some(document) = state.documents.get(uri) else {
+            return json!([]);
+        };
+        let mut actions = Vec::new();
+        let mut steps = Vec::new();
+        for diagnostic in &document.diagnostics {
+            let lsp_diagnostic = to_lsp_diagnostic(diagnostic);
+            if !requested.is_empty()
+                && !requested.iter().any(|r| {
+                    r["range"] == lsp_diagnostic["range"]
+                        && r["message"] == lsp_diagnostic["message"]
+                })
+            {
+                continue;
+            }
+            if let Some(edit) = fix_edit(uri, diagnostic) {
+                actions.push(json!({
+                    "title": format!("Apply {} fix", diagnostic.tool),
+                    "kind": "quickfix",
+                    "diagnostics": [lsp_diagnostic],
+                    "isPreferred": true,
+                    "edit": edit,
+                }));
+            }
+            if !steps.contains(&diagnostic.step) {
+                steps.push(diagnostic.step.clone());
+            }
+        }
+        for step in steps {
+            actions.push(json!({
+                "title": format!("Run hk fix for {step}"),
+                "kind": "quickfix",
+                "command": {
+                    "title": format!("Run hk fix for {step}"),
+                    "command": FIX_COMMAND,
+                    "arguments": [uri, step],
+                },
+            }));
+        }
+        if !document.diagnostics.is_empty() {
+            actions.push(json!({
+                "title": "Run hk fix",
+                "kind": "source.fixAll.hk",
+                "command": {
+                    "title": "Run hk fix",
+                    "command": FIX_COMMAND,
+                    "arguments": [uri],
+                },
+            }));
+        }
+        Value::Array(actions)
+    }
+
+    async fn execute_command(&self, params: &Value) -> std::result::Result<Value, (i64, String)> {
+        if params["command"].as_str() != Some(FIX_COMMAND) {
+            return Err((-32602, "unknown command".to_string()));
+        }
+        let arguments = params["arguments"].as_array().cloned().unwrap_or_default();
+        let Some(uri) = arguments.first().and_then(Value::as_str) else {
+            return Err((-32602, format!("{FIX_COMMAND} requires a document URI")));
+        };
+        let Some(path) = uri_to_path(uri) else {
+            return Err((-32602, format!("not a file URI: {uri}")));
+        };
+        let step = arguments.get(1).and_then(Value::as_str);
+        let root = self
+            .state
+            .lock()
+            .await
+            .root
+            .clone()
+            .unwrap_or_else(|| path.parent().unwrap_or(Path::new(".")).to_path_buf());
+        run_fix(&root, &path, step)
+            .await
+            .map_err(|err| (-32603, err.to_string()))?;
+        // The fixer rewrote the file on disk; the editor reloads it and sends
+        // didChange, which re-checks once the buffer matches.
+        Ok(Value::Null)
+    }
+
+    async fn send(&self, message: &Value) -> Result<()> {
+        let mut writer = self.writer.lock().await;
+        write_message(&mut *writer, message).await
+    }
+}
+
+#[cfg(test)]
+async fn read_message<R>(reader: &mut R) -> Result<Option<Value>>
+where
+    R: AsyncBufRead + Unpin,
+{
+    match read_body(reader).await? {
+        Some(body) => Ok(Some(serde_json::from_slice(&body)?)),
+        None => Ok(None),
+    }
+}
+
+/// Read the body of the next `Content-Length` framed message.
+async fn read_body<R>(reader: &mut R) -> Result<Option<Vec<u8>>>
+where
+    R: AsyncBufRead + Unpin,
+{
+    let mut content_length = None;
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line).await? == 0 {
+            return Ok(None);
+        }
+        let line = line.trim_end();
+        if line.is_empty() {
+            if content_length.is_some() {
+                break;
+            }
+            continue;
+        }
+        if let Some((name, value)) = line.split_once(':')
+            && name.eq_ignore_ascii_case("content-length")
+        {
+            match value.trim().parse::<usize>() {
+                Ok(length) => content_length = Some(length),
+                // Without a length the body cannot be framed; its lines are
+                // skipped like any other unknown header until the next message.
+                Err(err) => warn!("hk lsp: ignoring invalid header {line:?}: {err}"),
+            }
+        }
+    }
+    let mut body = vec![0; content_length.unwrap_or_default()];
+    reader.read_exact(&mut body).await?;
+    Ok(Some(body))
+}
+
+async fn write_message<W>(writer: &mut W, message: &Value) -> Result<()>
+where
+    W: AsyncWrite + Unpin,
+{
+    let body = serde_json::to_vec(message)?;
+    writer
+        .write_all(format!("Content-Length: {}\r\n\r\n", body.len()).as_bytes())
+        .await?;
+    writer.write_all(&body).await?;
+    writer.flush().await?;
+    Ok(())
+}
+
+fn uri_to_path(uri: &str) -> Option<PathBuf> {
+    url::Url::parse(uri).ok()?.to_file_path().ok()
+}
+
+/// Run the check steps matching `path` and return the diagnostics that
+/// belong to it. With `unsaved`, the steps check that buffer instead of the
+/// file on disk.
+async fn run_check(root: &Path, path: &Path, unsaved: Option<String>) -> Result<Vec<Diagnostic>> {
+    let mut command = hk_command(root);
+    command.args(["check", "--format", "json", "--no-progress"]);
+    match &unsaved {
+        Some(_) => command
+            .arg("--stdin-filepath")
+            .arg(path)
+            .stdin(std::process::Stdio::piped()),
+        None => command.arg("--").arg(path),
+    };
+    let mut child = command
+        .spawn()
+        .map_err(|err| eyre::eyre!("failed to start hk: {err}"))?;
+    if let (Some(text), Some(mut stdin)) = (unsaved, child.stdin.take()) {
+        stdin.write_all(text.as_bytes()).await?;
+    }
+    let output = child.wait_with_output().await?;
+    let result: Value = serde_json::from_slice(&output.stdout).map_err(|err| {
+        eyre::eyre!(
+            "hk returned an invalid result: {err}\n{}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )
+    })?;
+    Ok(diagnostics_for_path(&result, root, path))
+}
+
+async fn run_fix(root: &Path, path: &Path, step: Option<&str>) -> Result<()> {
+    let mut command = hk_command(root);
+    command.args(["fix", "--no-stage", "--no-progress"]);
+    if let Some(step) = step {
+        command.args(["--step", step]);
+    }
+    let output = command
+        .arg("--")
+        .arg(path)
+        .output()
+        .await
+        .map_err(|err| eyre::eyre!("failed to start hk: {err}"))?;
+    if !output.status.success() {
+        eyre::bail!(
+            "hk fix failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(())
+}
+
+fn hk_command(root: &Path) -> Command {
+    let executable = std::env::current_exe().unwrap_or_else(|_| PathBuf::from("hk"));
+    let mut command = Command::new(executable);
+    command
+        .current_dir(root)
+        .stdin(std::process::Stdio::null())
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped())
+        .kill_on_drop(true);
+    command
+}
+
+/// Collect the normalized diagnostics in a run result that refer to `path`.
+/// A failed step that produced no parseable diagnostics is reported as one
+/// file-level diagnostic carrying the step's output.
+fn diagnostics_for_path(result: &Value, root: &Path, path: &Path) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    for step in result["steps"].as_array().into_iter().flatten() {
+        let name = step["name"].as_str().unwrap_or_default();
+        let parsed: Vec<Diagnostic> =
+            serde_json::from_value(step["diagnostics"].clone()).unwrap_or_default();
+        let before = diagnostics.len();
+        diagnostics.extend(parsed.into_iter().filter(|diagnostic| {
+            diagnostic
+                .path
+                .as_deref()
+                .is_none_or(|p| same_file(root, Path::new(p), path))
+        }));
+        if diagnostics.len() == before && step["status"].as_str() == Some("failed") {
+            let output = step["output"].as_str().unwrap_or_default().trim();
+            diagnostics.push(Diagnostic {
+                step: name.to_string(),
+                tool: name.to_string(),
+                severity: Severity::Error,
+                message: if output.is_empty() {
+                    format!("{name} failed")
+                } else {
+                    output.to_string()
+                },
+                path: Some(path.display().to_string()),
+                range: None,
+                rule: None,
+                help_url: None,
+                fix: None,
+            });
+        }
+    }
+    diagnostics
+}
+
+fn same_file(root: &Path, candidate: &Path, path: &Path) -> bool {
+    let candidate = if candidate.is_absolute() {
+        candidate.to_path_buf()
+    } else {
+        root.join(candidate)
+    };
+    candidate == path
+        || candidate
+            .canonicalize()
+            .ok()
+            .zip(path.canonicalize().ok())
+            .is_some_and(|(a, b)| a == b)
+}
+
+/// hk positions are 1-based; LSP positions are 0-based.
+fn to_lsp_position(position: &crate::diagnostics::Position) -> Value {
+    json!({
+        "line": position.line.saturating_sub(1),
+        "character": position.column.saturating_sub(1),
+    })
+}
+
+fn to_lsp_range(range: Option<&crate::diagnostics::Range>) -> Value {
+    match range {
+        Some(range) => {
+            let start = to_lsp_position(&range.start);
+            let end = range.end.as_ref().map(to_lsp_position).unwrap_or_else(|| {
+                json!({"line": start["line"], "character": start["character"].as_u64().unwrap_or(0) + 1})
+            });
+            json!({"start": start, "end": end})
+        }
+        None => json!({
+            "start": {"line": 0, "character": 0},
+            "end": {"line": 0, "character": 0},
+        }),
+    }
+}
+
+fn to_lsp_diagnostic(diagnostic: &Diagnostic) -> Value {
+    let mut value = json!({
+        "range": to_lsp_range(diagnostic.range.as_ref()),
+        "severity": match diagnostic.severity {
+            Severity::Error => 1,
+            Severity::Warning => 2,
+            Severity::Note => 3,
+            Severity::Help => 4,
+        },
+        "source": format!("hk/{}", diagnostic.step),
+        "message": diagnostic.message,
+    });
+    if let Some(rule) = &diagnostic.rule {
+        value["code"] = json!(rule);
+    }
+    if let Some(help_url) = &diagnostic.help_url {
+        value["codeDescription"] = json!({"href": help_url});
+    }
+    value
+}
+
+/// A workspace edit for a diagnostic fix. Fixes without a range cannot be
+/// placed and are only reachable through the step's fix command.
+fn fix_edit(uri: &str, diagnostic: &Diagnostic) -> Option<Value> {
+    let fix = diagnostic.fix.as_ref()?;
+    let range = fix.range.as_ref()?;
+    Some(json!({
+        "changes": {
+            uri: [{
+                "range": to_lsp_range(Some(range)),
+                "newText": fix.replacement,
+            }],
+        },
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::diagnostics::{DiagnosticFix, Position, Range};
+
+    fn diagnostic(path: Option<&str>) -> Diagnostic {
+        Diagnostic {
+            step: "lint".into(),
+            tool: "linter".into(),
+            severity: Severity::Warning,
+            message: "problem".into(),
+            path: path.map(str::to_string),
+            range: Some(Range {
+                start: Position { line: 3, column: 5 },
+                end: Some(Position { line: 3, column: 9 }),
+            }),
+            rule: Some("R1".into()),
+            help_url: None,
+            fix: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn messages_round_trip_with_content_length_framing() {
+        let mut buffer = Vec::new();
+        write_message(&mut buffer, &json!({"jsonrpc": "2.0", "id": 1}))
+            .await
+            .unwrap();
+        assert!(buffer.starts_with(b"Content-Length: "));
+        let mut reader = BufReader::new(buffer.as_slice());
+        let message = read_message(&mut reader).await.unwrap().unwrap();
+        assert_eq!(message["id"], 1);
+        assert!(read_message(&mut reader).await.unwrap().is_none());
+    }
+
+    #[test]
+    fn diagnostics_are_converted_to_zero_based_ranges() {
+        let value = to_lsp_diagnostic(&diagnostic(None));
+        assert_eq!(value["range"]["start"], json!({"line": 2, "character": 4}));
+        assert_eq!(value["range"]["end"], json!({"line": 2, "character": 8}));
+        assert_eq!(value["severity"], 2);
+        assert_eq!(value["code"], "R1");
+        assert_eq!(value["source"], "hk/lint");
+    }
+
+    #[test]
+    fn run_results_are_filtered_to_the_checked_file() {
+        let root = tempfile::tempdir().unwrap();
+        let path = root.path().join("src/a.rs");
+        let result = json!({
+            "steps": [
+                {
+                    "name": "lint",
+                    "status": "failed",
+                    "diagnostics": [
+                        serde_json::to_value(diagnostic(Some("src/a.rs"))).unwrap(),
+                        serde_json::to_value(diagnostic(Some("src/b.rs"))).unwrap(),
+                    ],
+                },
+                {"name": "fmt", "status": "failed", "diagnostics": [], "output": "needs formatting"},
+                {"name": "ok", "status": "passed", "diagnostics": []},
+            ],
+        });
+        let diagnostics = diagnostics_for_path(&result, root.path(), &path);
+        assert_eq!(diagnostics.len(), 2);
+        assert_eq!(diagnostics[0].path.as_deref(), Some("src/a.rs"));
+        assert_eq!(diagnostics[1].step, "fmt");
+        assert_eq!(diagnostics[1].message, "needs formatting");
+        assert!(diagnostics[1].range.is_none());
+    }
+
+    #[test]
+    fn diagnostic_fixes_with_ranges_become_workspace_edits() {
+        let mut with_fix = diagnostic(None);
+        with_fix.fix = Some(DiagnosticFix {
+            replacement: "ok".into(),
+            path: None,
+            range: with_fix.range.clone(),
+        });
+        let edit = fix_edit("file:///a.rs", &with_fix).unwrap();
+        assert_eq!(edit["changes"]["file:///a.rs"][0]["newText"], "ok");
+
+        let mut without_range = with_fix.clone();
+        without_range.fix.as_mut().unwrap().range = None;
+        assert!(fix_edit("file:///a.rs", &without_range).is_none());
+    }
+
+    #[tokio::test]
+    async fn protocol_initializes_and_shuts_down() {
+        let (server_io, client_io) = tokio::io::duplex(64 * 1024);
+        let (server_read, server_write) = tokio::io::split(server_io);
+        let server_task = tokio::spawn(serve(BufReader::new(server_read), server_write));
+        let (client_read, mut client_write) = tokio::io::split(client_io);
+        let mut client_read = BufReader::new(client_read);
+
+        for message in [
+            json!({"jsonrpc": "2.0", "id": 1, "method": "initialize", "params": {"rootUri": null, "capabilities": {}}}),
+            json!({"jsonrpc": "2.0", "method": "initialized", "params": {}}),
+            json!({"jsonrpc": "2.0", "id": 2, "method": "textDocument/hover", "params": {}}),
+            json!({"jsonrpc": "2.0", "id": 3, "method": "shutdown"}),
+            json!({"jsonrpc": "2.0", "method": "exit"}),
+        ] {
+            write_message(&mut client_write, &message).await.unwrap();
+        }
+
+        let initialize = read_message(&mut client_read).await.unwrap().unwrap();
+        assert_eq!(initialize["id"], 1);
+        assert_eq!(initialize["result"]["serverInfo"]["name"], "hk");
+        assert_eq!(
+            initialize["result"]["capabilities"]["executeCommandProvider"]["commands"][0],
+            FIX_COMMAND
+        );
+        let unknown = read_message(&mut client_read).await.unwrap().unwrap();
+        assert_eq!(unknown["id"], 2);
+        assert_eq!(unknown["error"]["code"], -32601);
+        let shutdown = read_message(&mut client_read).await.unwrap().unwrap();
+        assert_eq!(shutdown["id"], 3);
+        assert!(shutdown["result"].is_null());
+
+        server_task.await.unwrap().unwrap();
+    }
+
+    #[tokio::test]
+    async fn malformed_messages_do_not_stop_the_server() {
+        let (server_io, client_io) = tokio::io::duplex(64 * 1024);
+        let (server_read, server_write) = tokio::io::split(server_io);
+        let server_task = tokio::spawn(serve(BufReader::new(server_read), server_write));
+        let (client_read, mut client_write) = tokio::io::split(client_io);
+        let mut client_read = BufReader::new(client_read);
+
+        client_write
+            .write_all(b"Content-Length: nope\r\n\r\n{\"id\": 9}\r\n")
+            .await
+            .unwrap();
+        client_write
+            .write_all(b"Content-Length: 5\r\n\r\n{bad}")
+            .await
+            .unwrap();
+        for message in [
+            json!({"jsonrpc": "2.0", "id": 1, "method": "shutdown"}),
+            json!({"jsonrpc": "2.0", "method": "exit"}),
+        ] {
+            write_message(&mut client_write, &message).await.unwrap();
+        }
+
+        let parse_error = read_message(&mut client_read).await.unwrap().unwrap();
+        assert!(parse_error["id"].is_null());
+        assert_eq!(parse_error["error"]["code"], -32700);
+        let shutdown = read_message(&mut client_read).await.unwrap().unwrap();
+        assert_eq!(shutdown["id"], 1);
+
+        server_task.await.unwrap().unwrap();
+    }
+}
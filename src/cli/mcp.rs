@@ -1,5 +1,5 @@
 use std::{
-    collections::{BTreeSet, VecDeque},
+    collections::{BTreeMap, BTreeSet, VecDeque},
     path::{Path, PathBuf},
     sync::{
         Arc,
@@ -12,9 +12,11 @@ use rmcp::{
     ErrorData, Peer, RoleServer, ServerHandler, ServiceExt,
     handler::server::{router::tool::ToolRouter, wrapper::Parameters},
     model::{
-        CallToolResult, ContentBlock, Implementation, ListResourcesResult, MetaObject,
-        PaginatedRequestParams, ReadResourceRequestParams, ReadResourceResponse,
-        ReadResourceResult, Resource, ResourceContents, ServerCapabilities, ServerInfo,
+        CallToolResult, ContentBlock, Implementation, ListResourceTemplatesResult,
+        ListResourcesResult, MetaObject, PaginatedRequestParams, ProgressNotificationParam,
+        ProgressToken, ReadResourceRequestParams, ReadResourceResponse, ReadResourceResult,
+        Resource, ResourceContents, ResourceTemplate, ResourceUpdatedNotificationParam,
+        ServerCapabilities, ServerInfo, SubscribeRequestParams, UnsubscribeRequestParams,
     },
     service::RequestContext,
     tool, tool_handler, tool_router,
@@ -24,9 +26,10 @@ use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_json::{Value, json};
 use tokio::{
-    io::{AsyncRead, AsyncReadExt},
+    io::{AsyncRead, AsyncReadExt, AsyncWriteExt},
     process::Command,
     sync::Mutex,
+    task::JoinHandle,
 };
 use tokio_util::sync::CancellationToken;
 
@@ -37,9 +40,11 @@ const MAX_RUN_OUTPUT_BYTES: usize = 16 * 1024 * 1024;
 const MAX_RUN_DIFF_BYTES: usize = 16 * 1024 * 1024;
 const COMPLETED_RUN_LIMIT: usize = 32;
 const COMPLETED_RUN_TTL: Duration = Duration::from_secs(30 * 60);
+const QUICK_RUN_WAIT: Duration = Duration::from_secs(60);
 const DASHBOARD_URI: &str = "ui://hk/run-dashboard";
 const MCP_APP_MIME: &str = "text/html;profile=mcp-app";
 const DASHBOARD_HTML: &str = include_str!("mcp_dashboard.html");
+const RUN_URI_PREFIX: &str = "hk://runs/";
 
 fn dashboard_tool_meta() -> MetaObject {
     serde_json::from_value(json!({
@@ -113,11 +118,88 @@ impl RunKind {
     }
 }
 
+/// An explicit file list (and optional step filter) supplied by a client
+/// instead of the hook's default file selection.
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+struct FileSelection {
+    files: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    steps: Vec<String>,
+}
+
+/// Per-run MCP resources, addressed as `hk://runs/{id}[/diagnostics|/diff]`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RunResource {
+    Run,
+    Diagnostics,
+    Diff,
+}
+
+impl RunResource {
+    const ALL: [Self; 3] = [Self::Run, Self::Diagnostics, Self::Diff];
+
+    fn uri(self, id: &str) -> String {
+        match self {
+            Self::Run => format!("{RUN_URI_PREFIX}{id}"),
+            Self::Diagnostics => format!("{RUN_URI_PREFIX}{id}/diagnostics"),
+            Self::Diff => format!("{RUN_URI_PREFIX}{id}/diff"),
+        }
+    }
+
+    fn parse(uri: &str) -> Option<(&str, Self)> {
+        let path = uri.strip_prefix(RUN_URI_PREFIX)?;
+        let (id, resource) = match path.split_once('/') {
+            None => (path, Self::Run),
+            Some((id, "diagnostics")) => (id, Self::Diagnostics),
+            Some((id, "diff")) => (id, Self::Diff),
+            Some(_) => return None,
+        };
+        (!id.is_empty()).then_some((id, resource))
+    }
+
+    fn mime_type(self) -> &'static str {
+        match self {
+            Self::Run | Self::Diagnostics => "application/json",
+            Self::Diff => "text/x-diff",
+        }
+    }
+}
+
+/// The client and token to report step progress to while the request that
+/// started a run with a `progressToken` is still waiting for it.
+#[derive(Debug, Clone)]
+struct ProgressTarget {
+    peer: Peer<RoleServer>,
+    token: ProgressToken,
+}
+
+/// A client notification collected under the state lock and sent after it is
+/// released.
+#[derive(Debug)]
+enum Notice {
+    Progress(Peer<RoleServer>, ProgressNotificationParam),
+    ResourceUpdated(Peer<RoleServer>, String),
+}
+
+async fn send_notices(notices: Vec<Notice>) {
+    for notice in notices {
+        let _ = match notice {
+            Notice::Progress(peer, param) => peer.notify_progress(param).await,
+            Notice::ResourceUpdated(peer, uri) => {
+                peer.notify_resource_updated(ResourceUpdatedNotificationParam::new(uri))
+                    .await
+            }
+        };
+    }
+}
+
 #[derive(Debug)]
 struct RunRecord {
     id: String,
     root: PathBuf,
     kind: RunKind,
+    selection: Option<FileSelection>,
+    progress: Option<ProgressTarget>,
     status: String,
     started_at: String,
     finished_at: Option<String>,
@@ -141,12 +223,48 @@ impl RunRecord {
         matches!(self.status.as_str(), "starting" | "running" | "cancelling")
     }
 
+    /// Builds a progress notification for a step event. Each step counts twice
+    /// toward the total, once when it starts and once when it completes, so
+    /// progress increases with every event.
+    fn step_progress(&self, name: &str, status: &str, counts: (usize, usize)) -> Option<Notice> {
+        let target = self.progress.as_ref()?;
+        let (progress, total) = counts;
+        let message = match status {
+            "running" => format!("{name} started"),
+            status => format!("{name} {status}"),
+        };
+        Some(Notice::Progress(
+            target.peer.clone(),
+            ProgressNotificationParam::new(target.token.clone(), progress as f64)
+                .with_total(total as f64)
+                .with_message(message),
+        ))
+    }
+
+    fn read_resource(&self, resource: RunResource) -> Result<String, String> {
+        match resource {
+            RunResource::Run => {
+                serde_json::to_string_pretty(&self.snapshot()).map_err(|error| error.to_string())
+            }
+            RunResource::Diagnostics => serde_json::to_string_pretty(&json!({
+                "schema_version": 1,
+                "run_id": self.id,
+                "status": self.status,
+                "complete": !self.active(),
+                "diagnostics": run_diagnostics(self.result.as_ref()),
+            }))
+            .map_err(|error| error.to_string()),
+            RunResource::Diff => Ok(self.diff.clone()),
+        }
+    }
+
     fn snapshot(&self) -> RunSnapshot {
         RunSnapshot {
             schema_version: 1,
             id: self.id.clone(),
             root: self.root.display().to_string(),
             kind: self.kind.label().to_string(),
+            selection: self.selection.clone(),
             status: self.status.clone(),
             started_at: self.started_at.clone(),
             finished_at: self.finished_at.clone(),
@@ -168,6 +286,8 @@ struct RunSnapshot {
     id: String,
     root: String,
     kind: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    selection: Option<FileSelection>,
     status: String,
     started_at: String,
     finished_at: Option<String>,
@@ -186,6 +306,7 @@ struct McpState {
     startup_roots: BTreeSet<PathBuf>,
     roots: BTreeSet<PathBuf>,
     runs: VecDeque<RunRecord>,
+    subscriptions: BTreeMap<String, Peer<RoleServer>>,
 }
 
 impl McpState {
@@ -208,6 +329,21 @@ impl McpState {
                 break;
             }
         }
+        let runs = &self.runs;
+        self.subscriptions.retain(|uri, _| {
+            RunResource::parse(uri).is_none_or(|(id, _)| runs.iter().any(|run| run.id == id))
+        });
+    }
+
+    fn resource_updates(&self, id: &str, resources: &[RunResource]) -> Vec<Notice> {
+        resources
+            .iter()
+            .filter_map(|resource| {
+                let uri = resource.uri(id);
+                let peer = self.subscriptions.get(&uri)?.clone();
+                Some(Notice::ResourceUpdated(peer, uri))
+            })
+            .collect()
     }
 }
 
@@ -217,6 +353,17 @@ struct RootRequest {
     root: Option<String>,
 }
 
+#[derive(Debug, Deserialize, JsonSchema)]
+struct FilesRequest {
+    /// An allowed root returned by inspect_project; omit when only one root is available.
+    root: Option<String>,
+    /// Files to run on, relative to the root or absolute within it.
+    files: Vec<String>,
+    /// Step names to run; omit to run every step that matches the files.
+    #[serde(default)]
+    steps: Vec<String>,
+}
+
 #[derive(Debug, Deserialize, JsonSchema)]
 struct RunRequest {
     /// Run identifier returned by a start tool.
@@ -262,6 +409,7 @@ impl HkMcpServer {
                 startup_roots: roots.clone(),
                 roots,
                 runs: VecDeque::new(),
+                subscriptions: BTreeMap::new(),
             })),
             next_id: Arc::new(AtomicU64::new(1)),
             tool_router: Self::combined_tool_router(),
@@ -324,7 +472,13 @@ impl HkMcpServer {
         }
     }
 
-    async fn start(&self, root: PathBuf, kind: RunKind) -> Result<RunSnapshot, String> {
+    async fn start(
+        &self,
+        root: PathBuf,
+        kind: RunKind,
+        selection: Option<FileSelection>,
+        progress: Option<ProgressTarget>,
+    ) -> Result<(RunSnapshot, JoinHandle<()>), String> {
         let id = format!("hk-{}", self.next_id.fetch_add(1, Ordering::Relaxed));
         let cancel = CancellationToken::new();
         let snapshot = {
@@ -341,6 +495,8 @@ impl HkMcpServer {
                 id: id.clone(),
                 root: root.clone(),
                 kind,
+                selection: selection.clone(),
+                progress,
                 status: "starting".into(),
                 started_at: chrono::Utc::now().to_rfc3339(),
                 finished_at: None,
@@ -363,11 +519,19 @@ impl HkMcpServer {
             snapshot
         };
         let server = self.clone();
-        tokio::spawn(async move { server.execute(id, root, kind, cancel).await });
-        Ok(snapshot)
+        let handle =
+            tokio::spawn(async move { server.execute(id, root, kind, selection, cancel).await });
+        Ok((snapshot, handle))
     }
 
-    async fn execute(&self, id: String, root: PathBuf, kind: RunKind, cancel: CancellationToken) {
+    async fn execute(
+        &self,
+        id: String,
+        root: PathBuf,
+        kind: RunKind,
+        selection: Option<FileSelection>,
+        cancel: CancellationToken,
+    ) {
         let diff_baseline = prepare_diff_baseline(&root).await;
         let executable = match std::env::current_exe() {
             Ok(path) => path,
@@ -383,11 +547,20 @@ impl HkMcpServer {
             .arg(&root)
             .args(["--format", "jsonl"])
             .arg(kind.command())
-            .arg("--all")
-            .stdin(std::process::Stdio::null())
             .stdout(std::process::Stdio::piped())
             .stderr(std::process::Stdio::piped())
             .kill_on_drop(true);
+        match &selection {
+            Some(selection) => {
+                command
+                    .args(["--files0-from", "-"])
+                    .args(selection.steps.iter().map(|step| format!("--step={step}")))
+                    .stdin(std::process::Stdio::piped());
+            }
+            None => {
+                command.arg("--all").stdin(std::process::Stdio::null());
+            }
+        }
         if kind.safe() {
             command.arg("--safe");
         }
@@ -403,6 +576,16 @@ impl HkMcpServer {
             }
         };
         self.set_status(&id, "running").await;
+        if let (Some(selection), Some(mut stdin)) = (selection, child.stdin.take()) {
+            tokio::spawn(async move {
+                let mut files0 = Vec::new();
+                for file in &selection.files {
+                    files0.extend_from_slice(file.as_bytes());
+                    files0.push(0);
+                }
+                let _ = stdin.write_all(&files0).await;
+            });
+        }
         let stdout = child.stdout.take().unwrap();
         let stderr = child.stderr.take().unwrap();
         let stdout_task = tokio::spawn(read_output(self.state.clone(), id.clone(), stdout, true));
@@ -424,6 +607,7 @@ impl HkMcpServer {
         let Some(run) = state.runs.iter_mut().find(|run| run.id == id) else {
             return;
         };
+        run.progress = None;
         run.finished_at = Some(chrono::Utc::now().to_rfc3339());
         run.completed_at = Some(Instant::now());
         run.diff = diff.text;
@@ -448,7 +632,10 @@ impl HkMcpServer {
                 run.error = Some(format!("failed to wait for hk: {error}"));
             }
         }
+        let notices = state.resource_updates(&id, &RunResource::ALL);
         state.cleanup();
+        drop(state);
+        send_notices(notices).await;
     }
 
     async fn set_status(&self, id: &str, status: &str) {
@@ -456,6 +643,9 @@ impl HkMcpServer {
         if let Some(run) = state.runs.iter_mut().find(|run| run.id == id) {
             run.status = status.into();
         }
+        let notices = state.resource_updates(id, &[RunResource::Run]);
+        drop(state);
+        send_notices(notices).await;
     }
 
     async fn finish_error(&self, id: &str, error: String) {
@@ -463,9 +653,13 @@ impl HkMcpServer {
         if let Some(run) = state.runs.iter_mut().find(|run| run.id == id) {
             run.status = "failed".into();
             run.error = Some(error);
+            run.progress = None;
             run.finished_at = Some(chrono::Utc::now().to_rfc3339());
             run.completed_at = Some(Instant::now());
         }
+        let notices = state.resource_updates(id, &RunResource::ALL);
+        drop(state);
+        send_notices(notices).await;
     }
 
     async fn snapshot(&self, id: &str) -> Result<RunSnapshot, String> {
@@ -574,9 +768,9 @@ impl HkMcpServer {
     async fn start_check(
         &self,
         Parameters(request): Parameters<RootRequest>,
-        peer: Peer<RoleServer>,
+        context: RequestContext<RoleServer>,
     ) -> Result<CallToolResult, String> {
-        self.start_tool(request, peer, RunKind::Check).await
+        self.start_tool(request, context, RunKind::Check).await
     }
 
     #[tool(
@@ -591,9 +785,9 @@ impl HkMcpServer {
     async fn start_safe_check(
         &self,
         Parameters(request): Parameters<RootRequest>,
-        peer: Peer<RoleServer>,
+        context: RequestContext<RoleServer>,
     ) -> Result<CallToolResult, String> {
-        self.start_tool(request, peer, RunKind::SafeCheck).await
+        self.start_tool(request, context, RunKind::SafeCheck).await
     }
 
     #[tool(
@@ -608,9 +802,43 @@ impl HkMcpServer {
     async fn start_safe_fix(
         &self,
         Parameters(request): Parameters<RootRequest>,
-        peer: Peer<RoleServer>,
+        context: RequestContext<RoleServer>,
     ) -> Result<CallToolResult, String> {
-        self.start_tool(request, peer, RunKind::SafeFix).await
+        self.start_tool(request, context, RunKind::SafeFix).await
+    }
+
+    #[tool(
+        description = "Run an all-or-nothing safe hk check on an explicit file list, optionally limited to named steps, and return normalized diagnostics",
+        annotations(
+            read_only_hint = false,
+            destructive_hint = false,
+            idempotent_hint = true,
+            open_world_hint = false
+        )
+    )]
+    async fn safe_check_files(
+        &self,
+        Parameters(request): Parameters<FilesRequest>,
+        context: RequestContext<RoleServer>,
+    ) -> Result<CallToolResult, String> {
+        self.files_tool(request, context, RunKind::SafeCheck).await
+    }
+
+    #[tool(
+        description = "Run a confirmed safe hk fix on an explicit file list, optionally limited to named steps, and return normalized diagnostics; never stages changes",
+        annotations(
+            read_only_hint = false,
+            destructive_hint = false,
+            idempotent_hint = false,
+            open_world_hint = false
+        )
+    )]
+    async fn safe_fix_files(
+        &self,
+        Parameters(request): Parameters<FilesRequest>,
+        context: RequestContext<RoleServer>,
+    ) -> Result<CallToolResult, String> {
+        self.files_tool(request, context, RunKind::SafeFix).await
     }
 
     #[tool(
@@ -752,6 +980,9 @@ impl HkMcpServer {
             run.cancel.cancel();
         }
         let snapshot = run.snapshot();
+        let notices = state.resource_updates(&snapshot.id, &[RunResource::Run]);
+        drop(state);
+        send_notices(notices).await;
         let value = serde_json::to_value(&snapshot).map_err(|error| error.to_string())?;
         Ok(tool_success(
             format!("Run {} is {}", snapshot.id, snapshot.status),
@@ -787,6 +1018,81 @@ impl HkMcpServer {
     }
 }
 
+/// Resolves client-supplied paths to unique, `/`-separated paths relative to
+/// `root`, rejecting anything that does not resolve to a file inside it.
+fn select_files(root: &Path, files: &[String]) -> Result<Vec<String>, String> {
+    if files.is_empty() {
+        return Err("files must not be empty".into());
+    }
+    let mut selected = indexmap::IndexSet::new();
+    for file in files {
+        if file.is_empty() || file.contains('\0') {
+            return Err(format!("invalid file path: {file:?}"));
+        }
+        let path = root.join(file);
+        let path = path
+            .canonicalize()
+            .map_err(|error| format!("invalid file {file}: {error}"))?;
+        let Ok(relative) = path.strip_prefix(root) else {
+            return Err(format!("file is outside the project root: {file}"));
+        };
+        if !path.is_file() {
+            return Err(format!("not a file: {file}"));
+        }
+        let relative = relative
+            .components()
+            .map(|component| component.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+        selected.insert(relative);
+    }
+    Ok(selected.into_iter().collect())
+}
+
+fn select_steps(steps: &[String]) -> Result<Vec<String>, String> {
+    steps
+        .iter()
+        .map(|step| {
+            if step.is_empty() || step.starts_with('-') || step.contains('\0') {
+                Err(format!("invalid step name: {step:?}"))
+            } else {
+                Ok(step.clone())
+            }
+        })
+        .collect()
+}
+
+fn step_progress_counts(result: Option<&Value>) -> Option<(usize, usize)> {
+    let steps = result?.get("steps")?.as_array()?;
+    let progress = steps
+        .iter()
+        .map(|step| {
+            match step
+                .get("status")
+                .and_then(Value::as_str)
+                .unwrap_or("pending")
+            {
+                "pending" => 0,
+                "running" => 1,
+                _ => 2,
+            }
+        })
+        .sum();
+    Some((progress, steps.len() * 2))
+}
+
+fn run_diagnostics(result: Option<&Value>) -> Vec<Value> {
+    result
+        .and_then(|result| result.get("steps"))
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(|step| step.get("diagnostics").and_then(Value::as_array))
+        .flatten()
+        .cloned()
+        .collect()
+}
+
 fn utf8_page_bounds(bytes: &[u8], requested_offset: usize, limit: usize) -> (usize, usize) {
     let mut offset = requested_offset.min(bytes.len());
     while offset < bytes.len() && bytes[offset] & 0b1100_0000 == 0b1000_0000 {
@@ -821,15 +1127,72 @@ impl HkMcpServer {
     async fn start_tool(
         &self,
         request: RootRequest,
-        peer: Peer<RoleServer>,
+        context: RequestContext<RoleServer>,
         kind: RunKind,
     ) -> Result<CallToolResult, String> {
-        self.refresh_client_roots(&peer).await;
+        self.refresh_client_roots(&context.peer).await;
         let root = self.select_root(request.root.as_deref()).await?;
-        let snapshot = self.start(root, kind).await?;
+        // The reply comes before the run finishes and a progress token is only
+        // valid until then, so progress is reported through updates to the
+        // subscribed hk://runs/{id} resource instead.
+        let (snapshot, _) = self.start(root, kind, None, None).await?;
         let value = serde_json::to_value(&snapshot).map_err(|error| error.to_string())?;
         Ok(tool_success(format!("Started run {}", snapshot.id), value))
     }
+
+    async fn files_tool(
+        &self,
+        request: FilesRequest,
+        context: RequestContext<RoleServer>,
+        kind: RunKind,
+    ) -> Result<CallToolResult, String> {
+        self.refresh_client_roots(&context.peer).await;
+        let root = self.select_root(request.root.as_deref()).await?;
+        let selection = FileSelection {
+            files: select_files(&root, &request.files)?,
+            steps: select_steps(&request.steps)?,
+        };
+        let (snapshot, handle) = self
+            .start(root, kind, Some(selection), progress_target(&context))
+            .await?;
+        let completed = tokio::time::timeout(QUICK_RUN_WAIT, handle).await.is_ok();
+        if !completed {
+            // The run outlives this request and with it the progress token
+            let mut state = self.state.lock().await;
+            if let Some(run) = state.runs.iter_mut().find(|run| run.id == snapshot.id) {
+                run.progress = None;
+            }
+        }
+        let snapshot = self.snapshot(&snapshot.id).await?;
+        let diagnostics = run_diagnostics(snapshot.result.as_ref());
+        let summary = if completed {
+            format!(
+                "Run {} {} with {} diagnostic(s)",
+                snapshot.id,
+                snapshot.status,
+                diagnostics.len()
+            )
+        } else {
+            format!(
+                "Run {} is still {}; poll get_run for its result",
+                snapshot.id, snapshot.status
+            )
+        };
+        let value = json!({
+            "schema_version": 1,
+            "completed": completed,
+            "diagnostics": diagnostics,
+            "run": snapshot,
+        });
+        Ok(tool_success(summary, value))
+    }
+}
+
+fn progress_target(context: &RequestContext<RoleServer>) -> Option<ProgressTarget> {
+    Some(ProgressTarget {
+        peer: context.peer.clone(),
+        token: context.meta.get_progress_token()?,
+    })
 }
 
 #[cfg(debug_assertions)]
@@ -881,11 +1244,12 @@ impl ServerHandler for HkMcpServer {
             ServerCapabilities::builder()
                 .enable_tools()
                 .enable_resources()
+                .enable_resources_subscribe()
                 .build(),
         )
             .with_server_info(Implementation::new("hk", env!("CARGO_PKG_VERSION")))
             .with_instructions(
-                "Inspect and run hk within authorized project roots. Prefer plan and safe tools; review diffs after fixes.",
+                "Inspect and run hk within authorized project roots. Prefer plan and safe tools; use the *_files tools for files you edited; subscribe to hk://runs/{id} resources instead of polling; review diffs after fixes.",
             )
     }
 
@@ -910,7 +1274,55 @@ impl ServerHandler for HkMcpServer {
                 }))
                 .expect("dashboard resource metadata is an object"),
             );
-        Ok(ListResourcesResult::with_all_items(vec![resource]))
+        let mut resources = vec![resource];
+        let mut state = self.state.lock().await;
+        state.cleanup();
+        for run in &state.runs {
+            resources.extend(RunResource::ALL.map(|kind| {
+                let (name, description) = match kind {
+                    RunResource::Run => ("", "Run state and structured result"),
+                    RunResource::Diagnostics => ("-diagnostics", "Normalized diagnostics"),
+                    RunResource::Diff => ("-diff", "Git patch captured when the run completed"),
+                };
+                Resource::new(kind.uri(&run.id), format!("{}{name}", run.id))
+                    .with_description(format!(
+                        "{description} for {} run {}",
+                        run.kind.label(),
+                        run.id
+                    ))
+                    .with_mime_type(kind.mime_type())
+            }));
+        }
+        Ok(ListResourcesResult::with_all_items(resources))
+    }
+
+    async fn list_resource_templates(
+        &self,
+        _request: Option<PaginatedRequestParams>,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ListResourceTemplatesResult, ErrorData> {
+        let template = |kind: RunResource, name: &str, description: &str| {
+            ResourceTemplate::new(kind.uri("{run_id}"), name)
+                .with_description(description)
+                .with_mime_type(kind.mime_type())
+        };
+        Ok(ListResourceTemplatesResult::with_all_items(vec![
+            template(
+                RunResource::Run,
+                "hk-run",
+                "Run state and structured result; subscribe for live updates",
+            ),
+            template(
+                RunResource::Diagnostics,
+                "hk-run-diagnostics",
+                "Normalized diagnostics for a run",
+            ),
+            template(
+                RunResource::Diff,
+                "hk-run-diff",
+                "Git patch captured when a run completed",
+            ),
+        ]))
     }
 
     async fn read_resource(
@@ -918,14 +1330,64 @@ impl ServerHandler for HkMcpServer {
         request: ReadResourceRequestParams,
         _context: RequestContext<RoleServer>,
     ) -> Result<ReadResourceResponse, ErrorData> {
-        if request.uri != DASHBOARD_URI {
-            return Err(ErrorData::resource_not_found("resource not found", None));
+        if request.uri == DASHBOARD_URI {
+            return Ok(ReadResourceResult::new(vec![
+                ResourceContents::text(DASHBOARD_HTML, DASHBOARD_URI).with_mime_type(MCP_APP_MIME),
+            ])
+            .into());
         }
+        let Some((id, kind)) = RunResource::parse(&request.uri) else {
+            return Err(ErrorData::resource_not_found("resource not found", None));
+        };
+        let mut state = self.state.lock().await;
+        state.cleanup();
+        let run = state
+            .runs
+            .iter()
+            .find(|run| run.id == id)
+            .ok_or_else(|| ErrorData::resource_not_found("run not found or expired", None))?;
+        let text = run
+            .read_resource(kind)
+            .map_err(|error| ErrorData::internal_error(error, None))?;
         Ok(ReadResourceResult::new(vec![
-            ResourceContents::text(DASHBOARD_HTML, DASHBOARD_URI).with_mime_type(MCP_APP_MIME),
+            ResourceContents::text(text, request.uri).with_mime_type(kind.mime_type()),
         ])
         .into())
     }
+
+    #[allow(deprecated)]
+    async fn subscribe(
+        &self,
+        request: SubscribeRequestParams,
+        context: RequestContext<RoleServer>,
+    ) -> Result<(), ErrorData> {
+        let Some((id, _)) = RunResource::parse(&request.uri) else {
+            return Err(ErrorData::invalid_params(
+                "only hk run resources support subscriptions",
+                None,
+            ));
+        };
+        let mut state = self.state.lock().await;
+        state.cleanup();
+        if !state.runs.iter().any(|run| run.id == id) {
+            return Err(ErrorData::resource_not_found(
+                "run not found or expired",
+                None,
+            ));
+        }
+        state.subscriptions.insert(request.uri, context.peer);
+        Ok(())
+    }
+
+    #[allow(deprecated)]
+    async fn unsubscribe(
+        &self,
+        request: UnsubscribeRequestParams,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<(), ErrorData> {
+        self.state.lock().await.subscriptions.remove(&request.uri);
+        Ok(())
+    }
 }
 
 fn tool_success(summary: String, value: Value) -> CallToolResult {
@@ -952,21 +1414,34 @@ fn parse_run_result(run: &mut RunRecord) -> bool {
     true
 }
 
-fn apply_jsonl_event(run: &mut RunRecord, line: &[u8]) {
+/// What a structured output event changed, for client notifications. Step
+/// events carry the progress counts as of that event.
+#[derive(Debug, PartialEq)]
+enum RunEvent {
+    Step {
+        name: String,
+        status: String,
+        progress: Option<(usize, usize)>,
+    },
+    Completed,
+    Other,
+}
+
+fn apply_jsonl_event(run: &mut RunRecord, line: &[u8]) -> Option<RunEvent> {
     let event: Value = match serde_json::from_slice(line) {
         Ok(event) => event,
         Err(error) => {
             run.error = Some(format!("failed to parse hk structured result: {error}"));
-            return;
+            return None;
         }
     };
     let Some(kind) = event.get("event").and_then(Value::as_str) else {
         run.error = Some("failed to parse hk structured result: event name is missing".into());
-        return;
+        return None;
     };
     let Some(data) = event.get("data").cloned() else {
         run.error = Some("failed to parse hk structured result: event data is missing".into());
-        return;
+        return None;
     };
     match kind {
         "run_started" => {
@@ -979,23 +1454,31 @@ fn apply_jsonl_event(run: &mut RunRecord, line: &[u8]) {
                 "duration_ms": 0,
                 "steps": [],
             }));
+            Some(RunEvent::Other)
         }
         "run_planned" => {
             let Some(steps) = data.get("steps").and_then(Value::as_array) else {
                 run.error =
                     Some("failed to parse hk structured result: planned steps are invalid".into());
-                return;
+                return None;
             };
             if let Some(result) = run.result.as_mut() {
                 result["steps"] = Value::Array(steps.clone());
             }
+            Some(RunEvent::Other)
         }
         "step_started" | "step_completed" => {
             let Some(name) = data.get("name").and_then(Value::as_str) else {
                 run.error =
                     Some("failed to parse hk structured result: step name is missing".into());
-                return;
+                return None;
             };
+            let name = name.to_string();
+            let status = data
+                .get("status")
+                .and_then(Value::as_str)
+                .unwrap_or("running")
+                .to_string();
             let fallback = || {
                 json!({
                     "schema_version": 1,
@@ -1010,26 +1493,33 @@ fn apply_jsonl_event(run: &mut RunRecord, line: &[u8]) {
             let result = run.result.get_or_insert_with(fallback);
             let Some(steps) = result.get_mut("steps").and_then(Value::as_array_mut) else {
                 run.error = Some("failed to parse hk structured result: steps are invalid".into());
-                return;
+                return None;
             };
             if let Some(existing) = steps
                 .iter_mut()
-                .find(|step| step.get("name").and_then(Value::as_str) == Some(name))
+                .find(|step| step.get("name").and_then(Value::as_str) == Some(&name))
             {
                 *existing = data;
             } else {
                 steps.push(data);
             }
+            Some(RunEvent::Step {
+                progress: step_progress_counts(run.result.as_ref()),
+                name,
+                status,
+            })
         }
         "run_completed" => {
             run.result = Some(data);
             run.saw_run_completed = true;
+            Some(RunEvent::Completed)
         }
-        _ => {}
+        _ => Some(RunEvent::Other),
     }
 }
 
-fn consume_jsonl_events(run: &mut RunRecord, bytes: &[u8]) {
+fn consume_jsonl_events(run: &mut RunRecord, bytes: &[u8]) -> Vec<RunEvent> {
+    let mut events = Vec::new();
     run.stdout_event_buffer.extend_from_slice(bytes);
     while let Some(newline) = run
         .stdout_event_buffer
@@ -1042,9 +1532,10 @@ fn consume_jsonl_events(run: &mut RunRecord, bytes: &[u8]) {
             .collect::<Vec<_>>();
         line.pop();
         if !line.iter().all(u8::is_ascii_whitespace) {
-            apply_jsonl_event(run, &line);
+            events.extend(apply_jsonl_event(run, &line));
         }
     }
+    events
 }
 
 async fn read_output<R>(state: Arc<Mutex<McpState>>, id: String, mut reader: R, stdout: bool)
@@ -1067,7 +1558,29 @@ where
                 run.stdout_truncated = true;
             }
             let appended = run.stdout[previous_len..].to_vec();
-            consume_jsonl_events(run, &appended);
+            let events = consume_jsonl_events(run, &appended);
+            if events.is_empty() {
+                continue;
+            }
+            let mut notices = events
+                .iter()
+                .filter_map(|event| match event {
+                    RunEvent::Step {
+                        name,
+                        status,
+                        progress,
+                    } => run.step_progress(name, status, (*progress)?),
+                    RunEvent::Completed | RunEvent::Other => None,
+                })
+                .collect::<Vec<_>>();
+            let resources = if events.contains(&RunEvent::Completed) {
+                &[RunResource::Run, RunResource::Diagnostics][..]
+            } else {
+                &[RunResource::Run][..]
+            };
+            notices.extend(state.resource_updates(&id, resources));
+            drop(state);
+            send_notices(notices).await;
         } else if append_capped(&mut run.output, &buffer[..count]) {
             run.output_truncated = true;
         }
@@ -1225,6 +1738,8 @@ mod tests {
             id: id.into(),
             root: PathBuf::from("/project"),
             kind: RunKind::Check,
+            selection: None,
+            progress: None,
             status: status.into(),
             started_at: String::new(),
             finished_at: (!matches!(status, "starting" | "running" | "cancelling"))
@@ -1269,7 +1784,7 @@ mod tests {
         let state = McpState {
             startup_roots: BTreeSet::from([startup.clone()]),
             roots: BTreeSet::from([startup]),
-            runs: VecDeque::new(),
+            ..Default::default()
         };
 
         assert!(is_within_startup_roots(&state.startup_roots, &child));
@@ -1285,7 +1800,7 @@ mod tests {
         let mut state = McpState {
             startup_roots: BTreeSet::from([startup.clone()]),
             roots: BTreeSet::from([startup.clone(), stale]),
-            runs: VecDeque::new(),
+            ..Default::default()
         };
 
         state.replace_client_roots(BTreeSet::from([current.clone()]));
@@ -1466,6 +1981,121 @@ mod tests {
         assert!(!run.saw_run_completed);
     }
 
+    #[test]
+    fn explicit_files_are_normalized_relative_to_the_root() {
+        let directory = tempfile::tempdir().unwrap();
+        let root = directory.path().canonicalize().unwrap();
+        std::fs::create_dir(root.join("src")).unwrap();
+        std::fs::write(root.join("src/main.rs"), "").unwrap();
+        std::fs::write(root.join("README.md"), "").unwrap();
+
+        let files = select_files(
+            &root,
+            &[
+                "src/../src/main.rs".into(),
+                root.join("README.md").display().to_string(),
+                "src/main.rs".into(),
+            ],
+        )
+        .unwrap();
+
+        assert_eq!(files, vec!["src/main.rs", "README.md"]);
+    }
+
+    #[test]
+    fn explicit_files_must_be_existing_files_inside_the_root() {
+        let directory = tempfile::tempdir().unwrap();
+        let root = directory.path().canonicalize().unwrap();
+        std::fs::create_dir(root.join("src")).unwrap();
+        let outside = tempfile::NamedTempFile::new().unwrap();
+
+        assert!(select_files(&root, &[]).is_err());
+        assert!(select_files(&root, &["missing.rs".into()]).is_err());
+        assert!(select_files(&root, &["src".into()]).is_err());
+        assert!(select_files(&root, &["../outside.rs".into()]).is_err());
+        assert!(select_files(&root, &[outside.path().display().to_string()]).is_err());
+        #[cfg(unix)]
+        {
+            std::os::unix::fs::symlink(outside.path(), root.join("link.rs")).unwrap();
+            assert!(select_files(&root, &["link.rs".into()]).is_err());
+        }
+    }
+
+    #[test]
+    fn step_names_cannot_be_options() {
+        assert_eq!(
+            select_steps(&["cargo-check".into()]).unwrap(),
+            vec!["cargo-check"]
+        );
+        assert!(select_steps(&["--all".into()]).is_err());
+        assert!(select_steps(&[String::new()]).is_err());
+    }
+
+    #[test]
+    fn diagnostics_are_flattened_across_steps() {
+        let result = json!({
+            "steps": [
+                {"name": "a", "diagnostics": [{"step": "a", "message": "one"}]},
+                {"name": "b"},
+                {"name": "c", "diagnostics": [{"step": "c", "message": "two"}]},
+            ]
+        });
+
+        let diagnostics = run_diagnostics(Some(&result));
+
+        assert_eq!(diagnostics.len(), 2);
+        assert_eq!(diagnostics[1]["message"], "two");
+        assert!(run_diagnostics(None).is_empty());
+    }
+
+    #[test]
+    fn run_resource_uris_round_trip() {
+        for resource in RunResource::ALL {
+            assert_eq!(
+                RunResource::parse(&resource.uri("hk-3")),
+                Some(("hk-3", resource))
+            );
+        }
+        assert_eq!(RunResource::parse("hk://runs/"), None);
+        assert_eq!(RunResource::parse("hk://runs/hk-3/output"), None);
+        assert_eq!(RunResource::parse(DASHBOARD_URI), None);
+    }
+
+    #[test]
+    fn step_events_advance_progress() {
+        let mut run = test_run("progress", "running", Vec::new());
+        let events = consume_jsonl_events(
+            &mut run,
+            br#"{"schema_version":1,"event":"run_started","sequence":0,"data":{"hook":"check","started_at":"now"}}
+{"schema_version":1,"event":"run_planned","sequence":1,"data":{"steps":[{"name":"a","status":"pending"},{"name":"b","status":"pending"}]}}
+{"schema_version":1,"event":"step_started","sequence":2,"data":{"name":"a","status":"running","duration_ms":0,"effects":[],"diagnostics":[]}}
+"#,
+        );
+        assert_eq!(
+            events.last(),
+            Some(&RunEvent::Step {
+                name: "a".into(),
+                status: "running".into(),
+                progress: Some((1, 4)),
+            })
+        );
+
+        let events = consume_jsonl_events(
+            &mut run,
+            br#"{"schema_version":1,"event":"step_completed","sequence":3,"data":{"name":"a","status":"failed","duration_ms":0,"effects":[],"diagnostics":[]}}
+{"schema_version":1,"event":"step_started","sequence":4,"data":{"name":"b","status":"running","duration_ms":0,"effects":[],"diagnostics":[]}}
+"#,
+        );
+        let progress = events
+            .iter()
+            .map(|event| match event {
+                RunEvent::Step { progress, .. } => *progress,
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(progress, vec![Some((2, 4)), Some((3, 4))]);
+    }
+
     #[test]
     fn cleanup_expires_old_completed_runs_but_not_active_runs() {
         let mut state = McpState::default();
@@ -1619,11 +2249,16 @@ mod tests {
         let root = tempfile::tempdir().unwrap();
         let root = root.path().canonicalize().unwrap();
         let server = HkMcpServer::new(root.clone());
-        let first = server
-            .start(root.clone(), RunKind::SafeCheck)
+        let (first, _) = server
+            .start(root.clone(), RunKind::SafeCheck, None, None)
             .await
             .unwrap();
-        assert!(server.start(root, RunKind::SafeCheck).await.is_err());
+        assert!(
+            server
+                .start(root, RunKind::SafeCheck, None, None)
+                .await
+                .is_err()
+        );
         {
             let mut state = server.state.lock().await;
             let run = state
@@ -1706,7 +2341,7 @@ mod tests {
         read.read_line(&mut line).await.unwrap();
         let response: Value = serde_json::from_str(&line).unwrap();
         let tools = response["result"]["tools"].as_array().unwrap();
-        let expected_tool_count = if cfg!(debug_assertions) { 11 } else { 10 };
+        let expected_tool_count = if cfg!(debug_assertions) { 13 } else { 12 };
         assert_eq!(tools.len(), expected_tool_count);
         let tools = tools
             .iter()
@@ -1720,6 +2355,8 @@ mod tests {
             "inspect_project",
             "plan",
             "render_run",
+            "safe_check_files",
+            "safe_fix_files",
             "start_check",
             "start_safe_check",
             "start_safe_fix",
@@ -1734,6 +2371,10 @@ mod tests {
             tools["start_safe_fix"]["annotations"]["destructiveHint"],
             false
         );
+        assert_eq!(
+            tools["safe_check_files"]["inputSchema"]["required"],
+            json!(["files"])
+        );
         assert_eq!(
             tools["inspect_project"]["annotations"]["readOnlyHint"],
             true
@@ -1773,4 +2414,82 @@ mod tests {
         server_task.abort();
         let _ = server_task.await;
     }
+
+    #[tokio::test]
+    async fn run_resources_can_be_read_and_subscribed() {
+        let root = tempfile::tempdir().unwrap();
+        let server = HkMcpServer::new(root.path().canonicalize().unwrap());
+        let mut run = test_run("hk-7", "running", Vec::new());
+        run.result = Some(json!({
+            "steps": [{"name": "lint", "diagnostics": [{"step": "lint", "message": "bad"}]}]
+        }));
+        server.state.lock().await.runs.push_back(run);
+        let (server_io, client_io) = tokio::io::duplex(64 * 1024);
+        let service = server.clone();
+        let server_task = tokio::spawn(async move {
+            service
+                .serve(server_io)
+                .await
+                .unwrap()
+                .waiting()
+                .await
+                .unwrap();
+        });
+        let (read, mut write) = tokio::io::split(client_io);
+        let mut read = BufReader::new(read);
+        let mut line = String::new();
+        write
+            .write_all(
+                br#"{"jsonrpc":"2.0","id":1,"method":"initialize","params":{"protocolVersion":"2025-06-18","capabilities":{},"clientInfo":{"name":"hk-test","version":"1"}}}
+"#,
+            )
+            .await
+            .unwrap();
+        read.read_line(&mut line).await.unwrap();
+        let response: Value = serde_json::from_str(&line).unwrap();
+        assert_eq!(
+            response["result"]["capabilities"]["resources"]["subscribe"],
+            true
+        );
+
+        write
+            .write_all(
+                br#"{"jsonrpc":"2.0","method":"notifications/initialized"}
+{"jsonrpc":"2.0","id":2,"method":"resources/read","params":{"uri":"hk://runs/hk-7/diagnostics"}}
+{"jsonrpc":"2.0","id":3,"method":"resources/subscribe","params":{"uri":"hk://runs/hk-404"}}
+{"jsonrpc":"2.0","id":4,"method":"resources/subscribe","params":{"uri":"hk://runs/hk-7"}}
+"#,
+            )
+            .await
+            .unwrap();
+        line.clear();
+        read.read_line(&mut line).await.unwrap();
+        let response: Value = serde_json::from_str(&line).unwrap();
+        let contents = &response["result"]["contents"][0];
+        assert_eq!(contents["mimeType"], "application/json");
+        let diagnostics: Value = serde_json::from_str(contents["text"].as_str().unwrap()).unwrap();
+        assert_eq!(diagnostics["complete"], false);
+        assert_eq!(diagnostics["diagnostics"][0]["message"], "bad");
+        line.clear();
+        read.read_line(&mut line).await.unwrap();
+        let response: Value = serde_json::from_str(&line).unwrap();
+        assert_eq!(response["id"], 3);
+        assert!(response["error"].is_object());
+        line.clear();
+        read.read_line(&mut line).await.unwrap();
+        let response: Value = serde_json::from_str(&line).unwrap();
+        assert_eq!(response["id"], 4);
+        assert!(response["result"].is_object());
+
+        server.finish_error("hk-7", "boom".into()).await;
+        line.clear();
+        read.read_line(&mut line).await.unwrap();
+        let notification: Value = serde_json::from_str(&line).unwrap();
+        assert_eq!(notification["method"], "notifications/resources/updated");
+        assert_eq!(notification["params"]["uri"], "hk://runs/hk-7");
+
+        drop(write);
+        server_task.abort();
+        let _ = server_task.await;
+    }
 }
@@ -1153,12 +1153,14 @@ impl PreCommit {
         // pre-commit-hooks utilities
         map.insert("trailing-whitespace", "trailing_whitespace");
         map.insert("end-of-file-fixer", "newlines");
-        map.insert("check-yaml", "yamllint");
-        map.insert("check-json", "jq");
-        map.insert("check-toml", "taplo");
+        map.insert("check-yaml", "check_yaml");
+        map.insert("check-json", "check_json");
+        map.insert("check-toml", "check_toml");
+        map.insert("check-xml", "check_xml");
         map.insert("check-merge-conflict", "check_merge_conflict");
         map.insert("check-case-conflict", "check_case_conflict");
         map.insert("mixed-line-ending", "mixed_line_ending");
+        map.insert("pretty-format-json", "pretty_format_json");
         map.insert(
             "check-executables-have-shebangs",
             "check_executables_have_shebangs",
@@ -15,9 +15,11 @@ mod check;
 mod command_effects;
 mod completion;
 mod config;
+mod doctor;
 mod fix;
 mod init;
 mod install;
+mod lsp;
 mod mcp;
 mod migrate;
 mod run;
@@ -125,9 +127,11 @@ enum Commands {
     Check(Box<check::Check>),
     Completion(Box<completion::Completion>),
     Config(Box<config::Config>),
+    Doctor(Box<doctor::Doctor>),
     Fix(Box<fix::Fix>),
     Init(Box<init::Init>),
     Install(Box<install::Install>),
+    Lsp(Box<lsp::Lsp>),
     Mcp(Box<mcp::Mcp>),
     Migrate(Box<migrate::Migrate>),
     Run(Box<run::Run>),
@@ -229,11 +233,14 @@ pub async fn run() -> Result<Option<std::process::ExitStatus>> {
     // - Version: just prints version info
     // - Builtins: just lists compiled-in builtin names, no project config needed
     // - Util: standalone file utilities must not recursively load hk config
+    // - Doctor: reports a broken config instead of failing on it
     let settings = if matches!(
         args.command,
         Commands::Agent(_)
             | Commands::Builtins(_)
+            | Commands::Doctor(_)
             | Commands::Init(_)
+            | Commands::Lsp(_)
             | Commands::Mcp(_)
             | Commands::Migrate(_)
             | Commands::Completion(_)
@@ -258,9 +265,11 @@ pub async fn run() -> Result<Option<std::process::ExitStatus>> {
         Commands::Check(cmd) => cmd.hook.run("check").await,
         Commands::Completion(cmd) => cmd.run().await,
         Commands::Config(cmd) => cmd.run().await,
+        Commands::Doctor(cmd) => cmd.run().await,
         Commands::Fix(cmd) => cmd.hook.run("fix").await,
         Commands::Init(cmd) => cmd.run().await,
         Commands::Install(cmd) => cmd.run().await,
+        Commands::Lsp(cmd) => cmd.run().await,
         Commands::Mcp(cmd) => cmd.run().await,
         Commands::Migrate(cmd) => cmd.run().await,
         Commands::Run(cmd) => cmd.run().await,
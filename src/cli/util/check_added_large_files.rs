@@ -1,11 +1,29 @@
 use crate::Result;
+use git2::{AttrCheckFlags, Index, Repository};
+use globset::{Glob, GlobMatcher};
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 const DEFAULT_MAX_SIZE_KB: u64 = 500;
 
+/// Check for large files being added to the repository
+///
+/// The size of the staged blob is checked when a file is in the index, so
+/// files stored as Git LFS pointers pass regardless of their work-tree size.
+/// Files that were already over their limit in HEAD are skipped unless
+/// `--enforce-all` is set.
 #[derive(Debug, clap::Args)]
 pub struct CheckAddedLargeFiles {
+    /// Check every file, including files that were already over their limit
+    /// in HEAD
+    #[clap(long)]
+    pub enforce_all: bool,
+
+    /// Limit in kilobytes for files matching a glob, as `GLOB=KB`, e.g.
+    /// `assets/**=2048`. Can be repeated; the last matching limit wins.
+    #[clap(long, value_name = "GLOB=KB", value_parser = parse_limit)]
+    pub limit: Vec<(String, u64)>,
+
     /// Maximum file size in kilobytes (default: 500)
     #[clap(long, default_value_t = DEFAULT_MAX_SIZE_KB)]
     pub maxkb: u64,
@@ -17,14 +35,40 @@ pub struct CheckAddedLargeFiles {
 
 impl CheckAddedLargeFiles {
     pub async fn run(&self) -> Result<()> {
-        let max_size_bytes = self.maxkb * 1024;
+        let limits = self
+            .limit
+            .iter()
+            .map(|(glob, kb)| Ok((Glob::new(glob)?.compile_matcher(), *kb)))
+            .collect::<Result<Vec<_>>>()?;
+        let repo = StagedRepo::discover();
         let mut found_large = false;
 
         for file_path in &self.files {
-            if is_too_large(file_path, max_size_bytes)? {
-                println!("{}", file_path.display());
-                found_large = true;
-            }
+            let path = repo
+                .as_ref()
+                .and_then(|repo| repo.relative(file_path))
+                .unwrap_or_else(|| file_path.clone());
+            let max_kb = limit_for(&limits, &path).unwrap_or(self.maxkb);
+            let Some(size) = self.oversized(repo.as_ref(), file_path, &path, max_kb)? else {
+                continue;
+            };
+            let lfs = repo.as_ref().is_some_and(|repo| repo.is_lfs(&path));
+            let hint = if lfs {
+                "it matches filter=lfs in .gitattributes but was not staged as an LFS pointer, \
+                 run `git lfs install` and add it again"
+                    .to_string()
+            } else {
+                format!(
+                    "consider tracking it with `git lfs track \"{}\"`",
+                    lfs_pattern(&path)
+                )
+            };
+            println!(
+                "{}: {} KB exceeds the {max_kb} KB limit; {hint}",
+                file_path.display(),
+                size.div_ceil(1024),
+            );
+            found_large = true;
         }
 
         if found_large {
@@ -33,20 +77,138 @@ impl CheckAddedLargeFiles {
 
         Ok(())
     }
+
+    /// The size of a file that is over `max_kb`, or None if it passes.
+    /// `path` is `file_path` relative to the repository.
+    fn oversized(
+        &self,
+        repo: Option<&StagedRepo>,
+        file_path: &Path,
+        path: &Path,
+        max_kb: u64,
+    ) -> Result<Option<u64>> {
+        let size = match repo.and_then(|repo| repo.staged_size(path)) {
+            Some(size) => size,
+            // LFS files are converted to pointers when they are added
+            None if repo.is_some_and(|repo| repo.is_lfs(path)) => return Ok(None),
+            None if is_too_large(file_path, max_kb * 1024)? => {
+                work_tree_size(file_path).unwrap_or_default()
+            }
+            None => return Ok(None),
+        };
+        if !exceeds(size, max_kb) {
+            return Ok(None);
+        }
+        if !self.enforce_all
+            && repo
+                .and_then(|repo| repo.head_size(path))
+                .is_some_and(|head_size| exceeds(head_size, max_kb))
+        {
+            debug!("{}: already over the limit in HEAD", path.display());
+            return Ok(None);
+        }
+        Ok(Some(size))
+    }
+}
+
+fn is_too_large(path: &Path, max_size: u64) -> Result<bool> {
+    Ok(work_tree_size(path).is_some_and(|size| size > max_size))
+}
+
+fn exceeds(size: u64, max_kb: u64) -> bool {
+    size > max_kb * 1024
+}
+
+fn parse_limit(value: &str) -> std::result::Result<(String, u64), String> {
+    let (glob, kb) = value
+        .rsplit_once('=')
+        .ok_or_else(|| format!("expected GLOB=KB, got `{value}`"))?;
+    Glob::new(glob).map_err(|err| err.to_string())?;
+    let kb = kb
+        .trim()
+        .parse()
+        .map_err(|_| format!("expected a size in kilobytes, got `{kb}`"))?;
+    Ok((glob.to_string(), kb))
+}
+
+fn limit_for(limits: &[(GlobMatcher, u64)], path: &Path) -> Option<u64> {
+    limits
+        .iter()
+        .rev()
+        .find(|(glob, _)| glob.is_match(path))
+        .map(|(_, kb)| *kb)
+}
+
+/// The `git lfs track` pattern suggested for a file: its extension, or the
+/// path itself for files without one.
+fn lfs_pattern(path: &Path) -> String {
+    match path.extension() {
+        Some(ext) => format!("*.{}", ext.to_string_lossy()),
+        None => path.to_string_lossy().to_string(),
+    }
 }
 
-fn is_too_large(path: &PathBuf, max_size: u64) -> Result<bool> {
-    let metadata = match fs::metadata(path) {
-        Ok(m) => m,
-        Err(_) => return Ok(false), // File doesn't exist or can't be accessed
-    };
+/// Size of a regular file in the work tree, or None for directories and
+/// files that don't exist or can't be accessed.
+fn work_tree_size(path: &Path) -> Option<u64> {
+    fs::metadata(path)
+        .ok()
+        .filter(|metadata| !metadata.is_dir())
+        .map(|metadata| metadata.len())
+}
+
+/// Blob sizes and attributes from the repository containing the checked files.
+struct StagedRepo {
+    repo: Repository,
+    index: Option<Index>,
+    workdir: PathBuf,
+}
+
+impl StagedRepo {
+    fn discover() -> Option<Self> {
+        Self::open(Path::new("."))
+    }
+
+    fn open(dir: &Path) -> Option<Self> {
+        let repo = Repository::discover(dir).ok()?;
+        let workdir = fs::canonicalize(repo.workdir()?).ok()?;
+        let index = repo.index().ok();
+        Some(Self {
+            repo,
+            index,
+            workdir,
+        })
+    }
 
-    // Skip directories
-    if metadata.is_dir() {
-        return Ok(false);
+    /// The path relative to the work tree, or None if it is outside of it.
+    fn relative(&self, path: &Path) -> Option<PathBuf> {
+        let path = fs::canonicalize(path).ok()?;
+        path.strip_prefix(&self.workdir).ok().map(Path::to_path_buf)
     }
 
-    Ok(metadata.len() > max_size)
+    fn blob_size(&self, id: git2::Oid) -> Option<u64> {
+        let (size, _) = self.repo.odb().ok()?.read_header(id).ok()?;
+        Some(size as u64)
+    }
+
+    fn staged_size(&self, path: &Path) -> Option<u64> {
+        let entry = self.index.as_ref()?.get_path(path, 0)?;
+        self.blob_size(entry.id)
+    }
+
+    fn head_size(&self, path: &Path) -> Option<u64> {
+        let tree = self.repo.head().ok()?.peel_to_tree().ok()?;
+        let entry = tree.get_path(path).ok()?;
+        self.blob_size(entry.id())
+    }
+
+    fn is_lfs(&self, path: &Path) -> bool {
+        self.repo
+            .get_attr(path, "filter", AttrCheckFlags::default())
+            .ok()
+            .flatten()
+            == Some("lfs")
+    }
 }
 
 #[cfg(test)]
@@ -122,4 +284,131 @@ mod tests {
         let result = is_too_large(&dir.path().to_path_buf(), 1024).unwrap();
         assert!(!result); // Directories should be skipped
     }
+
+    #[test]
+    fn test_parse_limit() {
+        assert_eq!(
+            parse_limit("assets/**=2048").unwrap(),
+            ("assets/**".to_string(), 2048)
+        );
+        assert!(parse_limit("assets/**").is_err());
+        assert!(parse_limit("*.bin=big").is_err());
+    }
+
+    #[test]
+    fn test_last_matching_limit_wins() {
+        let limits = ["**/*.png=100", "assets/**=2048"]
+            .iter()
+            .map(|limit| {
+                let (glob, kb) = parse_limit(limit).unwrap();
+                (Glob::new(&glob).unwrap().compile_matcher(), kb)
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(limit_for(&limits, Path::new("assets/logo.png")), Some(2048));
+        assert_eq!(limit_for(&limits, Path::new("docs/logo.png")), Some(100));
+        assert_eq!(limit_for(&limits, Path::new("src/main.rs")), None);
+    }
+
+    #[test]
+    fn test_lfs_pattern() {
+        assert_eq!(lfs_pattern(Path::new("assets/video.mp4")), "*.mp4");
+        assert_eq!(lfs_pattern(Path::new("bin/tool")), "bin/tool");
+    }
+
+    /// A repository with `.gitattributes` sending `*.bin` to LFS
+    fn repo() -> (tempfile::TempDir, StagedRepo) {
+        let dir = tempfile::TempDir::new().unwrap();
+        Repository::init(dir.path()).unwrap();
+        fs::write(dir.path().join(".gitattributes"), "*.bin filter=lfs\n").unwrap();
+        let repo = StagedRepo::open(dir.path()).unwrap();
+        (dir, repo)
+    }
+
+    fn stage(repo: &StagedRepo, path: &str, commit: bool) {
+        let mut index = repo.repo.index().unwrap();
+        index.add_path(Path::new(path)).unwrap();
+        index.write().unwrap();
+        if commit {
+            let tree = repo.repo.find_tree(index.write_tree().unwrap()).unwrap();
+            let sig = git2::Signature::now("test", "test@example.com").unwrap();
+            repo.repo
+                .commit(Some("HEAD"), &sig, &sig, "add", &tree, &[])
+                .unwrap();
+        }
+    }
+
+    fn check(enforce_all: bool) -> CheckAddedLargeFiles {
+        CheckAddedLargeFiles {
+            enforce_all,
+            limit: vec![],
+            maxkb: 1,
+            files: vec![],
+        }
+    }
+
+    #[test]
+    fn test_lfs_pointer_passes() {
+        let (dir, _) = repo();
+        let file = dir.path().join("video.bin");
+        fs::write(&file, "version https://git-lfs.github.com/spec/v1\n").unwrap();
+        let repo = StagedRepo::open(dir.path()).unwrap();
+        stage(&repo, "video.bin", false);
+        fs::write(&file, vec![b'x'; 2048]).unwrap();
+
+        let repo = StagedRepo::open(dir.path()).unwrap();
+        let path = Path::new("video.bin");
+        assert_eq!(
+            check(false).oversized(Some(&repo), &file, path, 1).unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_unstaged_lfs_file_passes() {
+        let (dir, repo) = repo();
+        let file = dir.path().join("video.bin");
+        fs::write(&file, vec![b'x'; 2048]).unwrap();
+
+        let path = Path::new("video.bin");
+        assert_eq!(
+            check(false).oversized(Some(&repo), &file, path, 1).unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_staged_blob_is_checked() {
+        let (dir, _) = repo();
+        let file = dir.path().join("data.txt");
+        fs::write(&file, vec![b'x'; 2048]).unwrap();
+        let repo = StagedRepo::open(dir.path()).unwrap();
+        stage(&repo, "data.txt", false);
+
+        let repo = StagedRepo::open(dir.path()).unwrap();
+        let path = Path::new("data.txt");
+        assert_eq!(
+            check(false).oversized(Some(&repo), &file, path, 1).unwrap(),
+            Some(2048)
+        );
+    }
+
+    #[test]
+    fn test_enforce_all_checks_files_over_the_limit_in_head() {
+        let (dir, _) = repo();
+        let file = dir.path().join("data.txt");
+        fs::write(&file, vec![b'x'; 2048]).unwrap();
+        let repo = StagedRepo::open(dir.path()).unwrap();
+        stage(&repo, "data.txt", true);
+
+        let repo = StagedRepo::open(dir.path()).unwrap();
+        let path = Path::new("data.txt");
+        assert_eq!(
+            check(false).oversized(Some(&repo), &file, path, 1).unwrap(),
+            None
+        );
+        assert_eq!(
+            check(true).oversized(Some(&repo), &file, path, 1).unwrap(),
+            Some(2048)
+        );
+    }
 }
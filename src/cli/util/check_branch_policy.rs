@@ -0,0 +1,295 @@
+use crate::Result;
+use crate::git::is_zero_sha;
+use eyre::WrapErr;
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use regex::Regex;
+use std::io::Read;
+
+use super::check_commit_msg::parse_regex;
+use super::no_commit_to_branch::get_current_branch;
+
+/// Enforce branch naming and protected-ref rules
+///
+/// Without `--stdin`, the current branch name is checked against
+/// `--branch-pattern`, e.g. from a `post-checkout` hook. With `--stdin`,
+/// the ref updates git passes to `pre-push` are read from stdin and every
+/// rule is applied to each pushed ref.
+#[derive(Debug, clap::Args)]
+pub struct CheckBranchPolicy {
+    /// Regexes that new branch names must match in full; a name is valid if
+    /// it matches any of them
+    #[clap(long, value_name = "REGEX", value_parser = parse_regex)]
+    pub branch_pattern: Vec<Regex>,
+
+    /// Reject merge commits in the pushed commits
+    #[clap(long)]
+    pub linear: bool,
+
+    /// Refs that cannot be deleted, as globs matched against the branch
+    /// name or the full ref, e.g. `main,release/*`
+    #[clap(long, value_name = "GLOB", value_delimiter = ',')]
+    pub no_delete: Vec<String>,
+
+    /// Refs that cannot be force-pushed, as globs matched against the branch
+    /// name or the full ref
+    #[clap(long, value_name = "GLOB", value_delimiter = ',')]
+    pub no_force: Vec<String>,
+
+    /// Refs that cannot be pushed to at all, as globs matched against the
+    /// branch name or the full ref
+    #[clap(long, value_name = "GLOB", value_delimiter = ',')]
+    pub protect: Vec<String>,
+
+    /// Read pre-push ref updates (`<local ref> <local sha> <remote ref>
+    /// <remote sha>` lines) from stdin
+    #[clap(long)]
+    pub stdin: bool,
+}
+
+impl CheckBranchPolicy {
+    pub async fn run(&self) -> Result<()> {
+        let violations = if self.stdin {
+            let mut input = String::new();
+            std::io::stdin().read_to_string(&mut input)?;
+            self.check_push(&input)?
+        } else {
+            match get_current_branch()? {
+                Some(branch) => self.check_name(&branch).into_iter().collect(),
+                None => vec![],
+            }
+        };
+        for violation in &violations {
+            println!("{violation}");
+        }
+        if !violations.is_empty() {
+            return Err(eyre::eyre!("Branch policy violations found"));
+        }
+        Ok(())
+    }
+
+    fn check_name(&self, branch: &str) -> Option<String> {
+        if self.branch_pattern.is_empty()
+            || self
+                .branch_pattern
+                .iter()
+                .any(|pattern| is_full_match(pattern, branch))
+        {
+            return None;
+        }
+        let patterns = self
+            .branch_pattern
+            .iter()
+            .map(Regex::as_str)
+            .collect::<Vec<_>>();
+        Some(format!(
+            "branch name '{branch}' does not match any of: {}",
+            patterns.join(", ")
+        ))
+    }
+
+    fn check_push(&self, input: &str) -> Result<Vec<String>> {
+        let protect = build_globs(&self.protect)?;
+        let no_delete = build_globs(&self.no_delete)?;
+        let no_force = build_globs(&self.no_force)?;
+        let mut violations = vec![];
+        for update in input.lines().filter_map(RefUpdate::parse) {
+            let remote_ref = &update.remote_ref;
+            let name = short_name(remote_ref);
+            if ref_matches(&protect, remote_ref) {
+                violations.push(format!("{remote_ref}: '{name}' is protected"));
+                continue;
+            }
+            if update.is_delete() {
+                if ref_matches(&no_delete, remote_ref) {
+                    violations.push(format!("{remote_ref}: deleting '{name}' is not allowed"));
+                }
+                continue;
+            }
+            if update.is_create() {
+                if remote_ref.starts_with("refs/heads/")
+                    && let Some(violation) = self.check_name(name)
+                {
+                    violations.push(format!("{remote_ref}: {violation}"));
+                }
+            } else if ref_matches(&no_force, remote_ref)
+                && !is_ancestor(&update.remote_sha, &update.local_sha)?
+            {
+                violations.push(format!(
+                    "{remote_ref}: force-pushing '{name}' is not allowed"
+                ));
+            }
+            if self.linear {
+                for (sha, subject) in merge_commits(&update)? {
+                    violations.push(format!(
+                        "{remote_ref}: merge commit {} \"{subject}\" breaks linear history",
+                        &sha[..sha.len().min(12)]
+                    ));
+                }
+            }
+        }
+        Ok(violations)
+    }
+}
+
+/// One line of `pre-push` stdin.
+#[derive(Debug, PartialEq, Eq)]
+struct RefUpdate {
+    local_sha: String,
+    remote_ref: String,
+    remote_sha: String,
+}
+
+impl RefUpdate {
+    fn parse(line: &str) -> Option<Self> {
+        let mut parts = line.split_whitespace();
+        let (_local_ref, local_sha, remote_ref, remote_sha) =
+            (parts.next()?, parts.next()?, parts.next()?, parts.next()?);
+        Some(Self {
+            local_sha: local_sha.to_string(),
+            remote_ref: remote_ref.to_string(),
+            remote_sha: remote_sha.to_string(),
+        })
+    }
+
+    fn is_delete(&self) -> bool {
+        is_zero_sha(&self.local_sha)
+    }
+
+    fn is_create(&self) -> bool {
+        is_zero_sha(&self.remote_sha)
+    }
+}
+
+fn is_full_match(pattern: &Regex, text: &str) -> bool {
+    pattern
+        .find(text)
+        .is_some_and(|m| m.start() == 0 && m.end() == text.len())
+}
+
+fn build_globs(globs: &[String]) -> Result<GlobSet> {
+    let mut builder = GlobSetBuilder::new();
+    for glob in globs {
+        builder.add(Glob::new(glob)?);
+    }
+    Ok(builder.build()?)
+}
+
+fn short_name(git_ref: &str) -> &str {
+    git_ref.strip_prefix("refs/heads/").unwrap_or(git_ref)
+}
+
+fn ref_matches(globs: &GlobSet, git_ref: &str) -> bool {
+    globs.is_match(git_ref) || globs.is_match(short_name(git_ref))
+}
+
+/// Whether the push is a fast-forward. A remote sha that doesn't exist
+/// locally can't be an ancestor of the pushed commit.
+fn is_ancestor(ancestor: &str, descendant: &str) -> Result<bool> {
+    let output = xx::process::cmd("git", ["merge-base", "--is-ancestor", ancestor, descendant])
+        .stdout_capture()
+        .stderr_capture()
+        .unchecked()
+        .run()?;
+    match output.status.code() {
+        Some(0) => Ok(true),
+        Some(1) | Some(128) => Ok(false),
+        _ => Err(eyre::eyre!(
+            "Failed to compare {ancestor} and {descendant}: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )),
+    }
+}
+
+/// Merge commits being pushed: those after the remote sha, or for a new
+/// branch, those not on any remote-tracking branch.
+fn merge_commits(update: &RefUpdate) -> Result<Vec<(String, String)>> {
+    let mut args = vec!["log", "--merges", "--format=%H %s"];
+    let range = format!("{}..{}", update.remote_sha, update.local_sha);
+    if update.is_create() || !is_ancestor(&update.remote_sha, &update.local_sha)? {
+        args.extend([update.local_sha.as_str(), "--not", "--remotes"]);
+    } else {
+        args.push(&range);
+    }
+    let output = xx::process::cmd("git", args)
+        .read()
+        .wrap_err("Failed to list merge commits")?;
+    Ok(output
+        .lines()
+        .filter_map(|line| line.split_once(' '))
+        .map(|(sha, subject)| (sha.to_string(), subject.to_string()))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ZERO: &str = "0000000000000000000000000000000000000000";
+
+    fn policy(branch_pattern: &[&str], protect: &[&str]) -> CheckBranchPolicy {
+        CheckBranchPolicy {
+            branch_pattern: branch_pattern
+                .iter()
+                .map(|pattern| Regex::new(pattern).unwrap())
+                .collect(),
+            linear: false,
+            no_delete: vec!["main".to_string()],
+            no_force: vec![],
+            protect: protect.iter().map(|glob| glob.to_string()).collect(),
+            stdin: false,
+        }
+    }
+
+    #[test]
+    fn test_parse_ref_update() {
+        let update = RefUpdate::parse(&format!("refs/heads/x abc refs/heads/x {ZERO}")).unwrap();
+        assert!(update.is_create());
+        assert!(!update.is_delete());
+        assert_eq!(update.remote_ref, "refs/heads/x");
+        assert!(RefUpdate::parse("refs/heads/x abc").is_none());
+    }
+
+    #[test]
+    fn test_check_name_requires_full_match() {
+        assert_eq!(policy(&[], &[]).check_name("anything"), None);
+
+        let policy = policy(&["(feat|fix)/[a-z0-9-]+", "main"], &[]);
+        assert_eq!(policy.check_name("feat/add-login"), None);
+        assert_eq!(policy.check_name("main"), None);
+        assert_eq!(
+            policy.check_name("feat/Add_Login"),
+            Some(
+                "branch name 'feat/Add_Login' does not match any of: (feat|fix)/[a-z0-9-]+, main"
+                    .to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn test_check_push_protected_and_deleted_refs() {
+        let policy = policy(&["feat/.+"], &["release/*"]);
+        let input = format!(
+            "refs/heads/r abc refs/heads/release/1.0 def\n\
+             (delete) {ZERO} refs/heads/main abc\n\
+             (delete) {ZERO} refs/heads/old abc\n\
+             refs/heads/wip abc refs/heads/wip {ZERO}\n\
+             refs/tags/v1 abc refs/tags/v1 {ZERO}\n"
+        );
+        assert_eq!(
+            policy.check_push(&input).unwrap(),
+            vec![
+                "refs/heads/release/1.0: 'release/1.0' is protected",
+                "refs/heads/main: deleting 'main' is not allowed",
+                "refs/heads/wip: branch name 'wip' does not match any of: feat/.+",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_ref_matches_short_and_full_names() {
+        let globs = build_globs(&["main".to_string(), "refs/tags/*".to_string()]).unwrap();
+        assert!(ref_matches(&globs, "refs/heads/main"));
+        assert!(ref_matches(&globs, "refs/tags/v1.0"));
+        assert!(!ref_matches(&globs, "refs/heads/feature"));
+    }
+}
@@ -0,0 +1,96 @@
+use crate::Result;
+use crate::codeowners::CodeOwners;
+use crate::git_util::find_work_tree_root;
+use std::path::PathBuf;
+use std::process::Command;
+
+/// Validate CODEOWNERS files
+///
+/// Reports owners that aren't `@user`, `@org/team` or an email address,
+/// patterns GitHub doesn't support, and patterns that don't match any file
+/// in the repository.
+#[derive(Debug, clap::Args)]
+pub struct CheckCodeowners {
+    /// CODEOWNERS files to check (default: the first of
+    /// `.github/CODEOWNERS`, `CODEOWNERS` and `docs/CODEOWNERS`)
+    #[clap(value_hint = clap::ValueHint::FilePath)]
+    pub files: Vec<PathBuf>,
+}
+
+impl CheckCodeowners {
+    pub async fn run(&self) -> Result<()> {
+        let root = find_work_tree_root();
+        // Each file with the path to report it under
+        let files = if self.files.is_empty() {
+            match CodeOwners::discover(&root)? {
+                Some(codeowners) => {
+                    let path = codeowners
+                        .path
+                        .strip_prefix(&root)
+                        .unwrap_or(&codeowners.path)
+                        .to_path_buf();
+                    vec![(codeowners, path)]
+                }
+                None => return Err(eyre::eyre!("No CODEOWNERS file found")),
+            }
+        } else {
+            self.files
+                .iter()
+                .map(|path| Ok((CodeOwners::from_path(path)?, path.clone())))
+                .collect::<Result<Vec<_>>>()?
+        };
+        let repo_files = repo_files(&root)?;
+
+        let mut found_errors = false;
+        for (codeowners, path) in &files {
+            let mut errors = codeowners
+                .errors
+                .iter()
+                .map(|error| (error.line, error.message.clone()))
+                .collect::<Vec<_>>();
+            for rule in &codeowners.rules {
+                if !repo_files.iter().any(|file| rule.is_match(file)) {
+                    errors.push((
+                        rule.line,
+                        format!("pattern `{}` does not match any files", rule.pattern),
+                    ));
+                }
+            }
+            errors.sort();
+            for (line, message) in errors {
+                println!("{}:{line}:1: error: {message}", path.display());
+                found_errors = true;
+            }
+        }
+
+        if found_errors {
+            return Err(eyre::eyre!("Invalid CODEOWNERS entries found"));
+        }
+        Ok(())
+    }
+}
+
+/// Tracked and untracked, non-ignored files relative to the repository root.
+fn repo_files(root: &std::path::Path) -> Result<Vec<PathBuf>> {
+    let output = Command::new("git")
+        .args([
+            "ls-files",
+            "-z",
+            "--cached",
+            "--others",
+            "--exclude-standard",
+        ])
+        .current_dir(root)
+        .output()?;
+    if !output.status.success() {
+        return Err(eyre::eyre!(
+            "Failed to list repository files: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(String::from_utf8(output.stdout)?
+        .split('\0')
+        .filter(|file| !file.is_empty())
+        .map(PathBuf::from)
+        .collect())
+}
@@ -0,0 +1,637 @@
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+
+use regex::Regex;
+
+use super::check_conventional_commit::{default_allowed_types, parse_commit_title};
+use crate::Result;
+
+/// Lint a commit message against a configurable policy
+///
+/// Comment lines and everything below the `git commit --verbose` scissors
+/// line are ignored. Problems are printed as
+/// `file:line:col: error: message [rule]`.
+#[derive(Debug, clap::Args)]
+pub struct CheckCommitMsg {
+    /// Scopes allowed with `--conventional`; any scope is allowed if empty
+    #[clap(long, value_delimiter = ',')]
+    pub allowed_scopes: Vec<String>,
+
+    /// Commit types allowed with `--conventional`
+    #[clap(long, default_value = default_allowed_types(), value_delimiter = ',')]
+    pub allowed_types: Vec<String>,
+
+    /// Require the subject to follow the conventional commits format
+    #[clap(long)]
+    pub conventional: bool,
+
+    /// Wrap long body lines, add the blank line after the subject and add a
+    /// missing `Signed-off-by` trailer, then report anything left over
+    #[clap(long)]
+    pub fix: bool,
+
+    /// Regex that must match somewhere in the message, e.g. `#\d+` or
+    /// `[A-Z]+-\d+` for issue references
+    #[clap(long, value_parser = parse_regex)]
+    pub issue_pattern: Option<Regex>,
+
+    /// Maximum number of characters in body lines. Indented lines and lines
+    /// without spaces (e.g. URLs) are exempt.
+    #[clap(long)]
+    pub max_body_line_length: Option<usize>,
+
+    /// Maximum number of characters in the subject line
+    #[clap(long)]
+    pub max_subject_length: Option<usize>,
+
+    /// Require a scope with `--conventional`
+    #[clap(long)]
+    pub require_scope: bool,
+
+    /// Trailers that must be present, e.g. `Signed-off-by` or `Change-Id`
+    #[clap(long, value_delimiter = ',')]
+    pub require_trailer: Vec<String>,
+
+    /// Commit message file to check
+    #[clap(required = true)]
+    pub commit_msg_file: PathBuf,
+}
+
+impl CheckCommitMsg {
+    pub async fn run(&self) -> Result<()> {
+        let policy = CommitPolicy {
+            conventional: self.conventional,
+            allowed_types: self.allowed_types.clone(),
+            allowed_scopes: self.allowed_scopes.clone(),
+            require_scope: self.require_scope,
+            max_subject_length: self.max_subject_length,
+            max_body_line_length: self.max_body_line_length,
+            require_trailer: self.require_trailer.clone(),
+            issue_pattern: self.issue_pattern.clone(),
+        };
+        let path = &self.commit_msg_file;
+        let original = std::fs::read_to_string(path)?;
+        let content = if self.fix {
+            let fixed = policy.fix(&original)?;
+            if fixed != original {
+                std::fs::write(path, &fixed)?;
+            }
+            fixed
+        } else {
+            original
+        };
+
+        let violations = policy.lint(&content);
+        for violation in &violations {
+            println!("{}", violation.display(path));
+        }
+        if !violations.is_empty() {
+            return Err(eyre::eyre!(
+                "Commit message does not follow the commit policy"
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+/// The rules a commit message must follow. Every check is opt-in except the
+/// blank line after the subject.
+#[derive(Debug, Clone, Default)]
+pub struct CommitPolicy {
+    pub conventional: bool,
+    pub allowed_types: Vec<String>,
+    /// Any scope is allowed if empty.
+    pub allowed_scopes: Vec<String>,
+    pub require_scope: bool,
+    pub max_subject_length: Option<usize>,
+    pub max_body_line_length: Option<usize>,
+    pub require_trailer: Vec<String>,
+    pub issue_pattern: Option<Regex>,
+}
+
+pub(super) fn parse_regex(pattern: &str) -> std::result::Result<Regex, String> {
+    Regex::new(pattern).map_err(|err| err.to_string())
+}
+
+/// The scissors line written by `git commit --verbose`; everything below it
+/// is discarded by git.
+const SCISSORS: &str = "# ------------------------ >8 ------------------------";
+
+/// Trailer lines like `Signed-off-by: Name <email>` and `BREAKING CHANGE: ...`.
+static TRAILER: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^(?<token>[A-Za-z][A-Za-z0-9-]*|(?i:breaking change)): ?(?<value>.*)$").unwrap()
+});
+
+/// A policy violation on a 1-based line of the message file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Violation {
+    pub line: usize,
+    pub message: String,
+    pub rule: &'static str,
+}
+
+impl Violation {
+    fn new(line: usize, message: impl Into<String>, rule: &'static str) -> Self {
+        Self {
+            line,
+            message: message.into(),
+            rule,
+        }
+    }
+
+    pub fn display(&self, path: &Path) -> String {
+        format!(
+            "{}:{}:1: error: {} [{}]",
+            path.display(),
+            self.line,
+            self.message,
+            self.rule
+        )
+    }
+}
+
+/// The lines git keeps from a commit message file, with their line numbers.
+struct Message<'a> {
+    lines: Vec<(usize, &'a str)>,
+}
+
+impl<'a> Message<'a> {
+    fn parse(content: &'a str) -> Self {
+        let mut lines = content
+            .lines()
+            .enumerate()
+            .take_while(|(_, line)| *line != SCISSORS)
+            .filter(|(_, line)| !line.starts_with('#'))
+            .map(|(index, line)| (index + 1, line.trim_end()))
+            .collect::<Vec<_>>();
+        while lines.last().is_some_and(|(_, line)| line.is_empty()) {
+            lines.pop();
+        }
+        let leading_blank = lines.iter().take_while(|(_, line)| line.is_empty()).count();
+        lines.drain(..leading_blank);
+        Self { lines }
+    }
+
+    fn subject(&self) -> Option<(usize, &'a str)> {
+        self.lines.first().copied()
+    }
+
+    /// Index into `lines` where the trailer block starts, if the last
+    /// paragraph (after the subject) consists only of trailers.
+    fn trailers_start(&self) -> Option<usize> {
+        let start = self
+            .lines
+            .iter()
+            .rposition(|(_, line)| line.is_empty())
+            .map_or(1, |blank| blank + 1);
+        let block = self.lines.get(start..)?;
+        let is_trailer_block = !block.is_empty()
+            && TRAILER.is_match(block[0].1)
+            && block
+                .iter()
+                .all(|(_, line)| TRAILER.is_match(line) || line.starts_with([' ', '\t']));
+        is_trailer_block.then_some(start)
+    }
+
+    fn trailers(&self) -> Vec<(usize, &'a str, &'a str)> {
+        let Some(start) = self.trailers_start() else {
+            return vec![];
+        };
+        self.lines[start..]
+            .iter()
+            .filter_map(|(number, line)| {
+                let captures = TRAILER.captures(line)?;
+                Some((
+                    *number,
+                    captures.name("token").unwrap().as_str(),
+                    captures.name("value").unwrap().as_str(),
+                ))
+            })
+            .collect()
+    }
+
+    /// Body lines, excluding the subject, its blank line and the trailers.
+    fn body(&self) -> &[(usize, &'a str)] {
+        let end = self.trailers_start().unwrap_or(self.lines.len());
+        self.lines.get(1..end).unwrap_or_default()
+    }
+}
+
+/// Titles generated by git that are never expected to follow the
+/// conventional format.
+fn is_generated_title(title: &str) -> bool {
+    ["fixup! ", "squash! ", "amend! ", "Merge ", "Revert \""]
+        .iter()
+        .any(|prefix| title.starts_with(prefix))
+}
+
+impl CommitPolicy {
+    /// Checks a commit message file's contents against the policy.
+    pub fn lint(&self, content: &str) -> Vec<Violation> {
+        let message = Message::parse(content);
+        let Some((subject_line, subject)) = message.subject() else {
+            return vec![Violation::new(1, "empty commit message", "empty")];
+        };
+        let mut violations = vec![];
+
+        if let Some(max) = self.max_subject_length {
+            let length = subject.chars().count();
+            if length > max {
+                violations.push(Violation::new(
+                    subject_line,
+                    format!("subject is {length} characters, the maximum is {max}"),
+                    "subject-length",
+                ));
+            }
+        }
+
+        if let Some((line, text)) = message.lines.get(1)
+            && !text.is_empty()
+        {
+            violations.push(Violation::new(
+                *line,
+                "the subject must be followed by a blank line",
+                "blank-line",
+            ));
+        }
+
+        if self.conventional && !is_generated_title(subject) {
+            violations.extend(self.lint_conventional(subject_line, subject, &message));
+        }
+
+        if let Some(max) = self.max_body_line_length {
+            for (line, text) in message.body() {
+                let length = text.chars().count();
+                if length > max && is_wrappable(text) {
+                    violations.push(Violation::new(
+                        *line,
+                        format!("body line is {length} characters, the maximum is {max}"),
+                        "body-line-length",
+                    ));
+                }
+            }
+        }
+
+        let trailers = message.trailers();
+        for required in &self.require_trailer {
+            if !trailers
+                .iter()
+                .any(|(_, token, _)| token.eq_ignore_ascii_case(required))
+            {
+                let line = message.lines.last().map_or(subject_line, |(line, _)| *line);
+                violations.push(Violation::new(
+                    line,
+                    format!("missing `{required}` trailer"),
+                    "trailer",
+                ));
+            }
+        }
+
+        if let Some(pattern) = &self.issue_pattern
+            && !message.lines.iter().any(|(_, line)| pattern.is_match(line))
+        {
+            violations.push(Violation::new(
+                subject_line,
+                format!("no issue reference matching `{pattern}`"),
+                "issue-ref",
+            ));
+        }
+
+        violations.sort_by_key(|violation| violation.line);
+        violations
+    }
+
+    fn lint_conventional(
+        &self,
+        subject_line: usize,
+        subject: &str,
+        message: &Message,
+    ) -> Vec<Violation> {
+        let mut violations = vec![];
+        if let Err(err) = parse_commit_title(subject, &self.allowed_types) {
+            violations.push(Violation::new(
+                subject_line,
+                err.to_string(),
+                "conventional",
+            ));
+            return violations;
+        }
+
+        let prefix = subject
+            .split_once(':')
+            .map_or(subject, |(prefix, _)| prefix);
+        let scope = prefix
+            .trim_end_matches('!')
+            .split_once('(')
+            .map(|(_, scope)| scope.trim_end_matches(')'));
+        match scope {
+            None if self.require_scope => {
+                violations.push(Violation::new(subject_line, "missing scope", "scope"));
+            }
+            Some(scope)
+                if !self.allowed_scopes.is_empty()
+                    && !self.allowed_scopes.iter().any(|allowed| allowed == scope) =>
+            {
+                violations.push(Violation::new(
+                    subject_line,
+                    format!(
+                        "scope '{scope}' is not one of: {}",
+                        self.allowed_scopes.join(", ")
+                    ),
+                    "scope",
+                ));
+            }
+            _ => {}
+        }
+
+        for (line, token, value) in message.trailers() {
+            let normalized = token.to_ascii_uppercase().replace('-', " ");
+            if normalized != "BREAKING CHANGE" {
+                continue;
+            }
+            if token != "BREAKING CHANGE" && token != "BREAKING-CHANGE" {
+                violations.push(Violation::new(
+                    line,
+                    format!("`{token}` must be written as `BREAKING CHANGE`"),
+                    "breaking-change",
+                ));
+            }
+            if value.trim().is_empty() {
+                violations.push(Violation::new(
+                    line,
+                    "`BREAKING CHANGE` must describe the change",
+                    "breaking-change",
+                ));
+            }
+        }
+
+        violations
+    }
+
+    /// Applies the fixable parts of the policy to a commit message file's
+    /// contents, keeping comment lines.
+    pub fn fix(&self, content: &str) -> Result<String> {
+        let message = Message::parse(content);
+        let Some((_, subject)) = message.subject() else {
+            return Ok(content.to_string());
+        };
+        let trailers_start = message.trailers_start();
+
+        let mut lines = vec![subject.to_string()];
+        let rest = &message.lines[1..];
+        if rest.first().is_some_and(|(_, line)| !line.is_empty()) {
+            lines.push(String::new());
+        }
+        for (index, (_, line)) in rest.iter().enumerate() {
+            let in_trailers = trailers_start.is_some_and(|start| index + 1 >= start);
+            match self.max_body_line_length {
+                Some(max) if !in_trailers && line.chars().count() > max && is_wrappable(line) => {
+                    lines.extend(wrap(line, max));
+                }
+                _ => lines.push(line.to_string()),
+            }
+        }
+
+        let has_sign_off = message
+            .trailers()
+            .iter()
+            .any(|(_, token, _)| token.eq_ignore_ascii_case("Signed-off-by"));
+        if !has_sign_off
+            && self
+                .require_trailer
+                .iter()
+                .any(|trailer| trailer.eq_ignore_ascii_case("Signed-off-by"))
+        {
+            if trailers_start.is_none() {
+                lines.push(String::new());
+            }
+            lines.push(format!("Signed-off-by: {}", git_identity()?));
+        }
+
+        let mut fixed = lines.join("\n");
+        fixed.push('\n');
+        // git ignores the comments, but `git commit --verbose` needs the
+        // scissors line and diff to stay in place
+        let comments = content
+            .lines()
+            .skip_while(|line| *line != SCISSORS)
+            .collect::<Vec<_>>();
+        let comments = content
+            .lines()
+            .take_while(|line| *line != SCISSORS)
+            .filter(|line| line.starts_with('#'))
+            .chain(comments)
+            .collect::<Vec<_>>();
+        if !comments.is_empty() {
+            fixed.push('\n');
+            fixed.push_str(&comments.join("\n"));
+            fixed.push('\n');
+        }
+        Ok(fixed)
+    }
+}
+
+/// Indented lines are usually code or quotes, and lines without spaces
+/// (URLs, paths) can't be wrapped.
+fn is_wrappable(line: &str) -> bool {
+    !line.starts_with([' ', '\t']) && line.trim().contains(' ')
+}
+
+/// Greedily wraps `line` to `width` characters, indenting continuation
+/// lines of list items to line up with the item's text.
+fn wrap(line: &str, width: usize) -> Vec<String> {
+    static LIST_ITEM: LazyLock<Regex> =
+        LazyLock::new(|| Regex::new(r"^(?:[-*+]|\d+[.)])\s+").unwrap());
+    let hanging = LIST_ITEM.find(line).map_or(String::new(), |item| {
+        " ".repeat(item.as_str().chars().count())
+    });
+
+    let mut lines = vec![];
+    let mut current = String::new();
+    for word in line.split(' ').filter(|word| !word.is_empty()) {
+        let indent = if lines.is_empty() { "" } else { &hanging };
+        if current.is_empty() {
+            current = format!("{indent}{word}");
+        } else if current.chars().count() + 1 + word.chars().count() <= width {
+            current.push(' ');
+            current.push_str(word);
+        } else {
+            lines.push(std::mem::take(&mut current));
+            current = format!("{hanging}{word}");
+        }
+    }
+    if !current.is_empty() {
+        lines.push(current);
+    }
+    lines
+}
+
+/// `Name <email>` of the committer, as git would use for `--signoff`.
+fn git_identity() -> Result<String> {
+    let output = std::process::Command::new("git")
+        .args(["var", "GIT_COMMITTER_IDENT"])
+        .output()?;
+    if !output.status.success() {
+        return Err(eyre::eyre!(
+            "failed to read the committer identity: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    let ident = String::from_utf8_lossy(&output.stdout);
+    // "Name <email> 1700000000 +0000"
+    let end = ident.rfind('>').map_or(ident.len(), |index| index + 1);
+    Ok(ident[..end].trim().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy() -> CommitPolicy {
+        CommitPolicy {
+            allowed_types: default_allowed_types()
+                .split(',')
+                .map(String::from)
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    fn rules(policy: &CommitPolicy, content: &str) -> Vec<&'static str> {
+        policy
+            .lint(content)
+            .into_iter()
+            .map(|violation| violation.rule)
+            .collect()
+    }
+
+    #[test]
+    fn test_blank_line_after_subject() {
+        let policy = policy();
+        assert_eq!(
+            rules(&policy, "fix things\nmore detail\n"),
+            vec!["blank-line"]
+        );
+        assert!(rules(&policy, "fix things\n\nmore detail\n").is_empty());
+        assert!(rules(&policy, "fix things\n# comment\n\nbody\n").is_empty());
+        assert_eq!(rules(&policy, "# only comments\n\n"), vec!["empty"]);
+    }
+
+    #[test]
+    fn test_subject_and_body_length() {
+        let policy = CommitPolicy {
+            max_subject_length: Some(10),
+            max_body_line_length: Some(20),
+            ..policy()
+        };
+        let violations = policy
+            .lint("a subject that is long\n\nshort body\nthis body line is far too long\n    indented code that is far too long\nhttps://example.com/a/very/long/url/that/cannot/wrap\n")
+            ;
+        assert_eq!(
+            violations,
+            vec![
+                Violation::new(
+                    1,
+                    "subject is 22 characters, the maximum is 10",
+                    "subject-length"
+                ),
+                Violation::new(
+                    4,
+                    "body line is 30 characters, the maximum is 20",
+                    "body-line-length"
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_required_trailers_and_issue_refs() {
+        let policy = CommitPolicy {
+            require_trailer: vec!["Signed-off-by".into(), "Change-Id".into()],
+            issue_pattern: Some(Regex::new(r"#\d+").unwrap()),
+            ..policy()
+        };
+        assert_eq!(
+            rules(&policy, "fix things\n\nSigned-off-by: A <a@example.com>\n"),
+            vec!["issue-ref", "trailer"]
+        );
+        assert!(
+            rules(
+                &policy,
+                "fix things\n\nCloses #12\n\nSigned-off-by: A <a@example.com>\nChange-Id: I123\n"
+            )
+            .is_empty()
+        );
+        // trailers must be in the last paragraph
+        assert_eq!(
+            rules(
+                &policy,
+                "fix #1\n\nChange-Id: I123\nSigned-off-by: A <a@example.com>\n\nmore text\n"
+            ),
+            vec!["trailer", "trailer"]
+        );
+    }
+
+    #[test]
+    fn test_conventional_scopes_and_breaking_changes() {
+        let policy = CommitPolicy {
+            conventional: true,
+            allowed_scopes: vec!["cli".into(), "core".into()],
+            ..policy()
+        };
+        assert!(rules(&policy, "feat(cli): add flag\n").is_empty());
+        assert_eq!(rules(&policy, "feat(web): add page\n"), vec!["scope"]);
+        assert_eq!(rules(&policy, "wip: stuff\n"), vec!["conventional"]);
+        assert!(rules(&policy, "Merge branch 'main'\n").is_empty());
+        assert!(
+            rules(
+                &policy,
+                "feat!: drop flag\n\nBREAKING CHANGE: the flag is gone\n"
+            )
+            .is_empty()
+        );
+        assert_eq!(
+            rules(
+                &policy,
+                "feat!: drop flag\n\nbreaking change: the flag is gone\n"
+            ),
+            vec!["breaking-change"]
+        );
+        assert_eq!(
+            rules(&policy, "feat!: drop flag\n\nBREAKING-CHANGE:\n"),
+            vec!["breaking-change"]
+        );
+
+        let policy = CommitPolicy {
+            require_scope: true,
+            ..policy
+        };
+        assert_eq!(rules(&policy, "feat: add flag\n"), vec!["scope"]);
+    }
+
+    #[test]
+    fn test_fix_wraps_body_and_keeps_comments() {
+        let policy = CommitPolicy {
+            max_body_line_length: Some(20),
+            ..policy()
+        };
+        let fixed = policy
+            .fix("subject\nthis body line is far too long to fit\n- a list item that is long\n    code stays as it is, however long\n\nCo-authored-by: A Very Long Name <a@example.com>\n# Please enter the commit message\n")
+            .unwrap();
+        assert_eq!(
+            fixed,
+            "subject\n\nthis body line is\nfar too long to fit\n- a list item that\n  is long\n    code stays as it is, however long\n\nCo-authored-by: A Very Long Name <a@example.com>\n\n# Please enter the commit message\n"
+        );
+        assert!(policy.lint(&fixed).is_empty());
+    }
+
+    #[test]
+    fn test_wrap() {
+        assert_eq!(wrap("one two three", 7), vec!["one two", "three"]);
+        assert_eq!(
+            wrap("1. one two three", 8),
+            vec!["1. one", "   two", "   three"]
+        );
+    }
+}
@@ -38,7 +38,7 @@ fn check_conventional_commit(path: &PathBuf, allowed_types: &[String]) -> Result
     Ok(())
 }
 
-fn parse_commit_title(title: &str, allowed_types: &[String]) -> Result<bool> {
+pub(super) fn parse_commit_title(title: &str, allowed_types: &[String]) -> Result<bool> {
     // `git commit --fixup`/`--squash` and `rebase --autosquash` produce commits titled
     // `fixup! <msg>` / `squash! <msg>` / `amend! <msg>`. These are meant to be squashed
     // away by `git rebase --autosquash` and never carry a conventional format themselves,
@@ -110,7 +110,7 @@ fn check_commit_type(commit_type: &str, allowed_types: &[String]) -> bool {
     allowed_types.contains(&commit_type.to_string())
 }
 
-fn default_allowed_types() -> String {
+pub(super) fn default_allowed_types() -> String {
     [
         "build", "chore", "ci", "docs", "feat", "fix", "perf", "refactor", "revert", "style",
         "test",
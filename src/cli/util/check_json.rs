@@ -0,0 +1,82 @@
+use super::structured_data::{self, Problem, Validate};
+use crate::Result;
+use serde::de::DeserializeSeed;
+use std::path::PathBuf;
+
+#[derive(Debug, clap::Args)]
+pub struct CheckJson {
+    /// Files to check
+    #[clap(required = true)]
+    pub files: Vec<PathBuf>,
+}
+
+impl CheckJson {
+    pub async fn run(&self) -> Result<()> {
+        structured_data::check_files(&self.files, "JSON", check_json)
+    }
+}
+
+fn check_json(text: &str) -> Vec<Problem> {
+    let mut deserializer = serde_json::Deserializer::from_str(text);
+    let result = Validate {
+        allow_custom_tags: false,
+    }
+    .deserialize(&mut deserializer)
+    .and_then(|()| deserializer.end());
+    match result {
+        Ok(()) => vec![],
+        Err(error) => {
+            let message = error.to_string();
+            // serde_json appends " at line X column Y"; report that position instead.
+            let message = message
+                .rsplit_once(" at line ")
+                .map_or(message.as_str(), |(message, _)| message);
+            // Duplicate keys are reported at the key's closing quote; point at
+            // its opening quote instead.
+            let column = match message.strip_prefix("duplicate key ") {
+                Some(key) => error.column().saturating_sub(key.chars().count() - 1),
+                None => error.column(),
+            };
+            vec![Problem::new(
+                error.line(),
+                column,
+                message,
+                structured_data::rule_for(message),
+            )]
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_valid_json() {
+        assert!(check_json(r#"{"a": [1, 2, {"b": null}], "c": "d"}"#).is_empty());
+    }
+
+    #[test]
+    fn test_syntax_error_position() {
+        let problems = check_json("{\n  \"a\": 1,\n}\n");
+        assert_eq!(problems.len(), 1);
+        assert_eq!((problems[0].line, problems[0].column), (3, 1));
+        assert_eq!(problems[0].rule, "syntax");
+        assert_eq!(problems[0].message, "trailing comma");
+    }
+
+    #[test]
+    fn test_duplicate_keys() {
+        let problems = check_json("{\n  \"a\": 1,\n  \"b\": {\"c\": 1, \"c\": 2}\n}\n");
+        assert_eq!(problems.len(), 1);
+        assert_eq!((problems[0].line, problems[0].column), (3, 17));
+        assert_eq!(problems[0].rule, "duplicate-key");
+        assert_eq!(problems[0].message, "duplicate key \"c\"");
+    }
+
+    #[test]
+    fn test_trailing_content() {
+        let problems = check_json("{}\n{}\n");
+        assert_eq!(problems[0].line, 2);
+    }
+}
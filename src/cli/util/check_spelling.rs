@@ -0,0 +1,543 @@
+use crate::Result;
+use crate::file_type::get_file_types;
+use crate::git_util::find_work_tree_root;
+use regex::Regex;
+use std::collections::{HashMap, HashSet};
+use std::ops::Range;
+use std::path::PathBuf;
+use std::sync::LazyLock;
+
+const BUNDLED: &str = include_str!("check_spelling/misspellings.txt");
+
+/// Project dictionary read from the repository root when `--dictionary`
+/// isn't given.
+const PROJECT_DICTIONARY: &str = ".hk-words.txt";
+
+/// Check for common misspellings in identifiers, comments and prose
+///
+/// Identifiers are split into words at `snake_case` and `camelCase`
+/// boundaries. In source files, string literals are skipped. Words are
+/// looked up in a bundled list of misspellings, which the project dictionary
+/// extends: each line of it is either a word to accept or a
+/// `misspelling->correction` pair.
+#[derive(Debug, clap::Args)]
+pub struct CheckSpelling {
+    /// Words to accept, in addition to the project dictionary
+    #[clap(long, value_name = "WORD", value_delimiter = ',')]
+    pub allow: Vec<String>,
+
+    /// Project dictionary files (default: `.hk-words.txt` in the repository
+    /// root, if it exists)
+    #[clap(long, value_name = "FILE", value_hint = clap::ValueHint::FilePath)]
+    pub dictionary: Vec<PathBuf>,
+
+    /// Replace misspellings that have a single correction
+    #[clap(short, long)]
+    pub fix: bool,
+
+    /// Files to check
+    #[clap(required = true)]
+    pub files: Vec<PathBuf>,
+}
+
+impl CheckSpelling {
+    pub async fn run(&self) -> Result<()> {
+        let mut dictionary = Dictionary::default();
+        dictionary.extend(BUNDLED);
+        let dictionary_files = if self.dictionary.is_empty() {
+            let path = find_work_tree_root().join(PROJECT_DICTIONARY);
+            path.is_file().then_some(path).into_iter().collect()
+        } else {
+            self.dictionary.clone()
+        };
+        for path in &dictionary_files {
+            dictionary.extend(&xx::file::read_to_string(path)?);
+        }
+        for word in &self.allow {
+            dictionary.allow(word);
+        }
+
+        let mut found_issues = false;
+        for path in &self.files {
+            if path.is_dir() {
+                continue;
+            }
+            let types = get_file_types(path);
+            if types.contains("binary") {
+                continue;
+            }
+            let Ok(text) = std::fs::read_to_string(path) else {
+                continue;
+            };
+            let mut typos = dictionary.check(&text, Syntax::for_types(&types));
+            if self.fix {
+                let fixed = apply_fixes(&text, &typos);
+                if fixed != text {
+                    std::fs::write(path, &fixed)?;
+                }
+                typos.retain(|typo| typo.corrections.len() > 1);
+            }
+            for typo in &typos {
+                let (line, column) = line_column(&text, typo.range.start);
+                println!("{}:{line}:{column}: {typo}", path.display());
+                found_issues = true;
+            }
+        }
+
+        if found_issues {
+            std::process::exit(1);
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Default)]
+struct Dictionary {
+    allowed: HashSet<String>,
+    corrections: HashMap<String, Vec<String>>,
+}
+
+impl Dictionary {
+    /// Adds the entries of a dictionary file: words to accept and
+    /// `misspelling->correction` pairs. `#` starts a comment.
+    fn extend(&mut self, text: &str) {
+        for line in text.lines() {
+            let line = line.split('#').next().unwrap_or_default().trim();
+            if line.is_empty() {
+                continue;
+            }
+            match line.split_once("->") {
+                Some((wrong, right)) => {
+                    let corrections = right
+                        .split(',')
+                        .map(|c| c.trim().to_string())
+                        .filter(|c| !c.is_empty())
+                        .collect();
+                    self.corrections
+                        .insert(wrong.trim().to_lowercase(), corrections);
+                }
+                None => self.allow(line),
+            }
+        }
+    }
+
+    fn allow(&mut self, word: &str) {
+        self.allowed.insert(word.trim().to_lowercase());
+    }
+
+    fn corrections(&self, word: &str) -> Option<&[String]> {
+        let word = word.to_lowercase();
+        if self.allowed.contains(&word) {
+            return None;
+        }
+        self.corrections.get(&word).map(Vec::as_slice)
+    }
+
+    fn check(&self, text: &str, syntax: Option<&Syntax>) -> Vec<Typo> {
+        let mut typos = vec![];
+        for range in checked_ranges(text, syntax) {
+            for token in tokens(text, range) {
+                if self.allowed.contains(&text[token.clone()].to_lowercase()) {
+                    continue;
+                }
+                for word in subwords(&text[token.clone()]) {
+                    let range = token.start + word.start..token.start + word.end;
+                    if let Some(corrections) = self.corrections(&text[range.clone()]) {
+                        typos.push(Typo {
+                            word: text[range.clone()].to_string(),
+                            corrections: corrections.to_vec(),
+                            range,
+                        });
+                    }
+                }
+            }
+        }
+        typos
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct Typo {
+    word: String,
+    corrections: Vec<String>,
+    range: Range<usize>,
+}
+
+impl std::fmt::Display for Typo {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let corrections = self
+            .corrections
+            .iter()
+            .map(|c| format!("`{}`", match_case(&self.word, c)))
+            .collect::<Vec<_>>();
+        match corrections.as_slice() {
+            [correction] => write!(f, "`{}` should be {correction}", self.word),
+            _ => write!(
+                f,
+                "`{}` should be one of {}",
+                self.word,
+                corrections.join(", ")
+            ),
+        }
+    }
+}
+
+/// Comment and string syntax of a source language. String literals are
+/// skipped; comments are checked, and recognised so that quotes inside them
+/// don't start a string.
+#[derive(Debug)]
+struct Syntax {
+    line_comments: &'static [&'static str],
+    block_comments: &'static [(&'static str, &'static str)],
+    quotes: &'static [char],
+}
+
+const C_LIKE: Syntax = Syntax {
+    line_comments: &["//"],
+    block_comments: &[("/*", "*/")],
+    quotes: &['"', '\''],
+};
+const SCRIPT: Syntax = Syntax {
+    line_comments: &["#"],
+    block_comments: &[],
+    quotes: &['"', '\''],
+};
+
+/// Source syntax by file type. Files of other types, such as markdown or
+/// config files, are checked in full.
+const SYNTAXES: &[(&str, Syntax)] = &[
+    (
+        "rust",
+        // `'` also starts lifetimes, so only `"` strings are skipped
+        Syntax {
+            line_comments: &["//"],
+            block_comments: &[("/*", "*/")],
+            quotes: &['"'],
+        },
+    ),
+    (
+        "go",
+        Syntax {
+            line_comments: &["//"],
+            block_comments: &[("/*", "*/")],
+            quotes: &['"', '\'', '`'],
+        },
+    ),
+    (
+        "typescript",
+        Syntax {
+            line_comments: &["//"],
+            block_comments: &[("/*", "*/")],
+            quotes: &['"', '\'', '`'],
+        },
+    ),
+    (
+        "javascript",
+        Syntax {
+            line_comments: &["//"],
+            block_comments: &[("/*", "*/")],
+            quotes: &['"', '\'', '`'],
+        },
+    ),
+    ("java", C_LIKE),
+    ("kotlin", C_LIKE),
+    ("swift", C_LIKE),
+    ("c", C_LIKE),
+    ("c++", C_LIKE),
+    ("csharp", C_LIKE),
+    (
+        "php",
+        Syntax {
+            line_comments: &["//", "#"],
+            block_comments: &[("/*", "*/")],
+            quotes: &['"', '\''],
+        },
+    ),
+    (
+        "python",
+        // docstrings are checked like comments
+        Syntax {
+            line_comments: &["#"],
+            block_comments: &[("\"\"\"", "\"\"\""), ("'''", "'''")],
+            quotes: &['"', '\''],
+        },
+    ),
+    ("ruby", SCRIPT),
+    ("perl", SCRIPT),
+    ("shell", SCRIPT),
+    (
+        "lua",
+        Syntax {
+            line_comments: &["--"],
+            block_comments: &[],
+            quotes: &['"', '\''],
+        },
+    ),
+];
+
+impl Syntax {
+    fn for_types(types: &HashSet<String>) -> Option<&'static Self> {
+        SYNTAXES
+            .iter()
+            .find(|(name, _)| types.contains(*name))
+            .map(|(_, syntax)| syntax)
+    }
+}
+
+/// Byte ranges of `text` to check: everything but string literals.
+fn checked_ranges(text: &str, syntax: Option<&Syntax>) -> Vec<Range<usize>> {
+    let Some(syntax) = syntax else {
+        #[allow(clippy::single_range_in_vec_init)]
+        return vec![0..text.len()];
+    };
+    let mut ranges = vec![];
+    let mut keep_from = 0;
+    let mut i = 0;
+    while i < text.len() {
+        let rest = &text[i..];
+        if syntax.line_comments.iter().any(|p| rest.starts_with(p)) {
+            i = rest.find('\n').map_or(text.len(), |n| i + n);
+            continue;
+        }
+        if let Some((open, close)) = syntax
+            .block_comments
+            .iter()
+            .find(|(open, _)| rest.starts_with(open))
+        {
+            let after = i + open.len();
+            i = text[after..]
+                .find(close)
+                .map_or(text.len(), |n| after + n + close.len());
+            continue;
+        }
+        let c = rest.chars().next().unwrap_or_default();
+        // `'"'` is a character literal, not the start of a string
+        if c == '\'' && rest.starts_with("'\"'") {
+            i += 3;
+            continue;
+        }
+        if syntax.quotes.contains(&c) {
+            ranges.push(keep_from..i);
+            i = string_end(text, i + 1, c as u8);
+            keep_from = i;
+            continue;
+        }
+        i += c.len_utf8();
+    }
+    ranges.push(keep_from..text.len());
+    ranges
+}
+
+/// The end (exclusive) of a string literal whose contents start at `start`.
+fn string_end(text: &str, start: usize, quote: u8) -> usize {
+    let bytes = text.as_bytes();
+    let mut i = start;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' => i += 2,
+            b if b == quote => return i + 1,
+            _ => i += 1,
+        }
+    }
+    text.len()
+}
+
+static TOKEN: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"[A-Za-z0-9_]+").unwrap());
+
+/// URLs and email addresses, which aren't spell-checked.
+static SKIPPED: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?:[A-Za-z][A-Za-z0-9+.-]*://|www\.)\S+|[\w.+-]+@[\w-]+\.[\w.]+").unwrap()
+});
+
+/// Identifier-like tokens in `text[range]`, as byte ranges of `text`.
+fn tokens(text: &str, range: Range<usize>) -> Vec<Range<usize>> {
+    let region = &text[range.clone()];
+    let skipped = SKIPPED
+        .find_iter(region)
+        .map(|m| m.range())
+        .collect::<Vec<_>>();
+    TOKEN
+        .find_iter(region)
+        .filter(|m| !skipped.iter().any(|s| s.contains(&m.start())))
+        .filter(|m| !looks_like_hash(m.as_str()))
+        .map(|m| {
+            let mut start = m.start();
+            // drop the letter of escapes such as `\n` or `\t`
+            if region[..start].ends_with('\\')
+                && m.len() > 1
+                && m.as_str()
+                    .starts_with(['n', 'r', 't', 'b', 'f', 'v', 'a', 'e', 'u', 'x'])
+            {
+                start += 1;
+            }
+            range.start + start..range.start + m.end()
+        })
+        .collect()
+}
+
+/// Long runs of letters and digits without underscores are hashes, keys or
+/// base64 rather than identifiers.
+fn looks_like_hash(token: &str) -> bool {
+    token.len() >= 16
+        && !token.contains('_')
+        && token.bytes().any(|c| c.is_ascii_digit())
+        && token.bytes().any(|c| c.is_ascii_alphabetic())
+}
+
+/// Splits an identifier into words at underscores, digits and case changes:
+/// `parseHTTPResponse_v2` becomes `parse`, `HTTP`, `Response` and `v`.
+fn subwords(token: &str) -> Vec<Range<usize>> {
+    let chars = token.as_bytes();
+    let mut words = vec![];
+    let mut start = None;
+    for (i, &c) in chars.iter().enumerate() {
+        if !c.is_ascii_alphabetic() {
+            if let Some(s) = start.take() {
+                words.push(s..i);
+            }
+            continue;
+        }
+        match start {
+            Some(s) => {
+                let prev = chars[i - 1];
+                let next_lower = chars.get(i + 1).is_some_and(u8::is_ascii_lowercase);
+                if c.is_ascii_uppercase()
+                    && (prev.is_ascii_lowercase() || (prev.is_ascii_uppercase() && next_lower))
+                {
+                    words.push(s..i);
+                    start = Some(i);
+                }
+            }
+            None => start = Some(i),
+        }
+    }
+    if let Some(s) = start {
+        words.push(s..token.len());
+    }
+    words
+}
+
+/// `correction` in the case of `word`: `TEH` becomes `THE`, `Teh` `The`.
+fn match_case(word: &str, correction: &str) -> String {
+    if word.len() > 1 && !word.bytes().any(|c| c.is_ascii_lowercase()) {
+        correction.to_uppercase()
+    } else if word.starts_with(|c: char| c.is_ascii_uppercase()) {
+        let mut chars = correction.chars();
+        chars
+            .next()
+            .map(|first| first.to_uppercase().chain(chars).collect())
+            .unwrap_or_default()
+    } else {
+        correction.to_string()
+    }
+}
+
+/// Replaces the misspellings that have a single correction.
+fn apply_fixes(text: &str, typos: &[Typo]) -> String {
+    let mut fixed = String::with_capacity(text.len());
+    let mut last = 0;
+    for typo in typos {
+        let [correction] = typo.corrections.as_slice() else {
+            continue;
+        };
+        fixed.push_str(&text[last..typo.range.start]);
+        fixed.push_str(&match_case(&typo.word, correction));
+        last = typo.range.end;
+    }
+    fixed.push_str(&text[last..]);
+    fixed
+}
+
+/// 1-based line and column of the byte `offset` in `text`.
+fn line_column(text: &str, offset: usize) -> (usize, usize) {
+    let before = &text[..offset];
+    let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+    (
+        before.matches('\n').count() + 1,
+        before[line_start..].chars().count() + 1,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dictionary(extra: &str) -> Dictionary {
+        let mut dictionary = Dictionary::default();
+        dictionary.extend(BUNDLED);
+        dictionary.extend(extra);
+        dictionary
+    }
+
+    fn words(text: &str, syntax: Option<&Syntax>) -> Vec<String> {
+        dictionary("")
+            .check(text, syntax)
+            .into_iter()
+            .map(|typo| typo.word)
+            .collect()
+    }
+
+    fn rust() -> Option<&'static Syntax> {
+        Syntax::for_types(&HashSet::from(["text".to_string(), "rust".to_string()]))
+    }
+
+    #[test]
+    fn test_subwords() {
+        let split = |token: &str| {
+            subwords(token)
+                .into_iter()
+                .map(|r| token[r].to_string())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            split("parseHTTPResponse_v2"),
+            vec!["parse", "HTTP", "Response", "v"]
+        );
+        assert_eq!(split("recieve_DATA"), vec!["recieve", "DATA"]);
+        assert_eq!(split("__init__"), vec!["init"]);
+    }
+
+    #[test]
+    fn test_checks_identifiers_and_comments_but_not_strings() {
+        let text = "// teh loader\nfn recieveData() { let s = \"teh\"; }\n/* seperate */\n";
+        assert_eq!(words(text, rust()), vec!["teh", "recieve", "seperate"]);
+        // without a known syntax, strings are prose too
+        assert_eq!(words(text, None), vec!["teh", "recieve", "teh", "seperate"]);
+    }
+
+    #[test]
+    fn test_skips_escapes_urls_and_char_literals() {
+        assert_eq!(words("print(\"\\nteh\\tok\")", None), vec!["teh"]);
+        let text =
+            "see https://example.com/teh and teh@example.com\nsha512-sxtK3teh71OQvVFHaTDzS\n";
+        assert!(words(text, None).is_empty());
+        assert_eq!(words("let q = '\"'; // teh\n", rust()), vec!["teh"]);
+    }
+
+    #[test]
+    fn test_project_dictionary() {
+        let dictionary = dictionary("# ours\nteh\nrecieveData\nfoobr->foobar\n");
+        let typos = dictionary.check("teh recieveData recieve foobr", None);
+        assert_eq!(
+            typos.iter().map(|t| t.word.as_str()).collect::<Vec<_>>(),
+            vec!["recieve", "foobr"]
+        );
+        assert_eq!(typos[1].corrections, vec!["foobar"]);
+    }
+
+    #[test]
+    fn test_fix_preserves_case_and_skips_ambiguous() {
+        let text = "Teh TEH teh wether recieveData\n";
+        let typos = dictionary("").check(text, None);
+        assert_eq!(
+            apply_fixes(text, &typos),
+            "The THE the wether receiveData\n"
+        );
+        assert_eq!(
+            typos[3].to_string(),
+            "`wether` should be one of `whether`, `weather`"
+        );
+        assert_eq!(line_column("a\nbc teh", 5), (2, 4));
+    }
+}
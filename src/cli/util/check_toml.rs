@@ -0,0 +1,60 @@
+use super::structured_data::{self, Problem};
+use crate::Result;
+use std::path::PathBuf;
+
+#[derive(Debug, clap::Args)]
+pub struct CheckToml {
+    /// Files to check
+    #[clap(required = true)]
+    pub files: Vec<PathBuf>,
+}
+
+impl CheckToml {
+    pub async fn run(&self) -> Result<()> {
+        structured_data::check_files(&self.files, "TOML", check_toml)
+    }
+}
+
+fn check_toml(text: &str) -> Vec<Problem> {
+    match toml::from_str::<toml::Table>(text) {
+        Ok(_) => vec![],
+        Err(error) => {
+            let offset = error.span().map_or(0, |span| span.start);
+            let message = error.message().trim_end();
+            let rule = if message.starts_with("duplicate key") {
+                "duplicate-key"
+            } else {
+                "syntax"
+            };
+            vec![Problem::at_offset(text, offset, message, rule)]
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_valid_toml() {
+        assert!(
+            check_toml("[package]\nname = \"hk\"\n\n[dependencies]\nserde = \"1\"\n").is_empty()
+        );
+    }
+
+    #[test]
+    fn test_syntax_error_position() {
+        let problems = check_toml("[package]\nname = \n");
+        assert_eq!(problems.len(), 1);
+        assert_eq!(problems[0].line, 2);
+        assert_eq!(problems[0].rule, "syntax");
+    }
+
+    #[test]
+    fn test_duplicate_keys() {
+        let problems = check_toml("a = 1\nb = 2\na = 3\n");
+        assert_eq!(problems.len(), 1);
+        assert_eq!((problems[0].line, problems[0].column), (3, 1));
+        assert_eq!(problems[0].rule, "duplicate-key");
+    }
+}
@@ -0,0 +1,63 @@
+use super::structured_data::{self, Problem};
+use crate::Result;
+use std::path::PathBuf;
+
+#[derive(Debug, clap::Args)]
+pub struct CheckXml {
+    /// Files to check
+    #[clap(required = true)]
+    pub files: Vec<PathBuf>,
+}
+
+impl CheckXml {
+    pub async fn run(&self) -> Result<()> {
+        structured_data::check_files(&self.files, "XML", check_xml)
+    }
+}
+
+fn check_xml(text: &str) -> Vec<Problem> {
+    let options = roxmltree::ParsingOptions {
+        allow_dtd: true,
+        ..Default::default()
+    };
+    match roxmltree::Document::parse_with_options(text, options) {
+        Ok(_) => vec![],
+        Err(error) => {
+            let pos = error.pos();
+            let message = error.to_string();
+            let message = message
+                .strip_suffix(&format!(" at {pos}"))
+                .unwrap_or(&message);
+            vec![Problem::new(
+                pos.row as usize,
+                pos.col as usize,
+                message,
+                "syntax",
+            )]
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_valid_xml() {
+        assert!(
+            check_xml(
+                "<?xml version=\"1.0\"?>\n<!DOCTYPE note>\n<note a=\"1\"><to>x</to></note>\n"
+            )
+            .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_mismatched_tag_position() {
+        let problems = check_xml("<note>\n  <to>x</from>\n</note>\n");
+        assert_eq!(problems.len(), 1);
+        assert_eq!(problems[0].line, 2);
+        assert_eq!(problems[0].rule, "syntax");
+        assert!(!problems[0].message.contains(" at 2:"));
+    }
+}
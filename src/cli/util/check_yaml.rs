@@ -0,0 +1,183 @@
+use super::structured_data::{self, Problem, Validate};
+use crate::Result;
+use serde::de::DeserializeSeed;
+use std::path::PathBuf;
+
+#[derive(Debug, clap::Args)]
+pub struct CheckYaml {
+    /// Allow multiple documents separated by `---` in one file
+    #[clap(short = 'm', long)]
+    pub allow_multiple_documents: bool,
+    /// Allow tags outside the YAML core schema, such as CloudFormation's `!Ref`
+    #[clap(long, alias = "unsafe")]
+    pub allow_custom_tags: bool,
+    /// Files to check
+    #[clap(required = true)]
+    pub files: Vec<PathBuf>,
+}
+
+impl CheckYaml {
+    pub async fn run(&self) -> Result<()> {
+        structured_data::check_files(&self.files, "YAML", |text| {
+            check_yaml(text, self.allow_multiple_documents, self.allow_custom_tags)
+        })
+    }
+}
+
+fn check_yaml(text: &str, allow_multiple_documents: bool, allow_custom_tags: bool) -> Vec<Problem> {
+    let validate = Validate { allow_custom_tags };
+    for (index, document) in serde_yaml::Deserializer::from_str(text).enumerate() {
+        if index == 1 && !allow_multiple_documents {
+            let line = second_document_line(text);
+            return vec![Problem::new(
+                line,
+                1,
+                "expected a single document; pass --allow-multiple-documents to allow more",
+                "multiple-documents",
+            )];
+        }
+        if let Err(error) = validate.deserialize(document) {
+            let (line, column) = error
+                .location()
+                .map_or((1, 1), |location| (location.line(), location.column()));
+            let message = error.to_string();
+            // serde_yaml appends " at line X column Y"; report that position instead.
+            let message = message
+                .rsplit_once(" at line ")
+                .map_or(message.as_str(), |(message, _)| message);
+            let rule = structured_data::rule_for(message);
+            // Duplicate keys are reported at the start of their mapping; point
+            // at the repeated key instead when it can be found.
+            let (line, column) = structured_data::duplicate_key(message)
+                .and_then(|key| locate_duplicate_key(text, line, column, &key))
+                .unwrap_or((line, column));
+            return vec![Problem::new(line, column, message, rule)];
+        }
+    }
+    vec![]
+}
+
+/// Finds the second occurrence of `key` in the block mapping that starts at
+/// `line`/`column`.
+fn locate_duplicate_key(
+    text: &str,
+    line: usize,
+    column: usize,
+    key: &str,
+) -> Option<(usize, usize)> {
+    let indent = column.checked_sub(1)?;
+    let quoted = [key.to_string(), format!("\"{key}\""), format!("'{key}'")];
+    let mut seen = 0;
+    for (index, content) in text.lines().enumerate().skip(line.checked_sub(1)?) {
+        let entry = if index + 1 == line {
+            content.chars().skip(indent).collect::<String>()
+        } else {
+            let trimmed = content.trim_start_matches(' ');
+            if trimmed.is_empty() || trimmed.starts_with('#') {
+                continue;
+            }
+            let current = content.len() - trimmed.len();
+            if current < indent {
+                break;
+            }
+            if current > indent {
+                continue;
+            }
+            trimmed.to_string()
+        };
+        let is_key = quoted.iter().any(|quoted| {
+            entry
+                .strip_prefix(quoted.as_str())
+                .is_some_and(|rest| rest.trim_start().starts_with(':'))
+        });
+        if is_key {
+            seen += 1;
+            if seen == 2 {
+                return Some((index + 1, column));
+            }
+        }
+    }
+    None
+}
+
+/// Finds the line of the `---` marker that starts the second document.
+fn second_document_line(text: &str) -> usize {
+    let is_marker = |line: &str| {
+        line.strip_prefix("---")
+            .is_some_and(|rest| rest.is_empty() || rest.starts_with(char::is_whitespace))
+    };
+    let starts_with_marker = text
+        .lines()
+        .map(str::trim_end)
+        .find(|line| !line.is_empty() && !line.starts_with('#') && !line.starts_with('%'))
+        .is_some_and(is_marker);
+    text.lines()
+        .enumerate()
+        .filter(|(_, line)| is_marker(line))
+        .nth(usize::from(starts_with_marker))
+        .map_or(1, |(index, _)| index + 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_valid_yaml() {
+        assert!(check_yaml("a: 1\nb:\n  - c\n  - d: e\n", false, false).is_empty());
+        assert!(check_yaml("", false, false).is_empty());
+    }
+
+    #[test]
+    fn test_syntax_error_position() {
+        let problems = check_yaml("a: 1\nb: [1, 2\nc: 3\n", false, false);
+        assert_eq!(problems.len(), 1);
+        assert_eq!(problems[0].rule, "syntax");
+        assert!(problems[0].line >= 2, "{problems:?}");
+    }
+
+    #[test]
+    fn test_duplicate_keys() {
+        let problems = check_yaml("a: 1\nb:\n  c: 1\n  c: 2\n", false, false);
+        assert_eq!(problems.len(), 1);
+        assert_eq!(problems[0].rule, "duplicate-key");
+        assert_eq!(problems[0].message, "b: duplicate key \"c\"");
+        assert_eq!(problems[0].column, 3);
+        assert_eq!(problems[0].line, 4);
+    }
+
+    #[test]
+    fn test_multiple_documents() {
+        let text = "---\na: 1\n---\nb: 2\n";
+        let problems = check_yaml(text, false, false);
+        assert_eq!(problems[0].rule, "multiple-documents");
+        assert_eq!(problems[0].line, 3);
+        assert!(check_yaml(text, true, false).is_empty());
+        assert_eq!(check_yaml("a: 1\n---\nb: 2\n", false, false)[0].line, 2);
+    }
+
+    #[test]
+    fn test_errors_in_later_documents() {
+        let problems = check_yaml("a: 1\n---\nb: 1\nb: 2\n", true, false);
+        assert_eq!(problems[0].rule, "duplicate-key");
+        assert_eq!(problems[0].line, 4);
+    }
+
+    #[test]
+    fn test_duplicate_keys_in_sequences() {
+        let problems = check_yaml("items:\n  - name: a\n    'name': b\n", false, false);
+        assert_eq!(problems[0].rule, "duplicate-key");
+        assert_eq!((problems[0].line, problems[0].column), (3, 5));
+    }
+
+    #[test]
+    fn test_custom_tags() {
+        let text = "Value: !Ref Bucket\nOther: !!str 1\n";
+        let problems = check_yaml(text, false, false);
+        assert_eq!(problems.len(), 1);
+        assert_eq!(problems[0].rule, "custom-tag");
+        assert_eq!(problems[0].message, "Value: unknown tag !Ref");
+        assert_eq!((problems[0].line, problems[0].column), (1, 8));
+        assert!(check_yaml(text, false, true).is_empty());
+    }
+}
@@ -0,0 +1,64 @@
+use crate::Result;
+use crate::codeowners::CodeOwners;
+use crate::git_util::find_work_tree_root;
+use std::path::{Path, PathBuf};
+
+/// List the CODEOWNERS owners of files
+///
+/// Prints each owner with the number of given files they own, e.g. to show
+/// which owners are affected by the changes in a `pre-push` step.
+#[derive(Debug, clap::Args)]
+pub struct Codeowners {
+    /// List each owner's files
+    #[clap(long)]
+    pub show_files: bool,
+
+    /// Files to look up
+    #[clap(value_hint = clap::ValueHint::FilePath)]
+    pub files: Vec<PathBuf>,
+}
+
+impl Codeowners {
+    pub async fn run(&self) -> Result<()> {
+        let root = find_work_tree_root();
+        let Some(codeowners) = CodeOwners::discover(&root)? else {
+            debug!("no CODEOWNERS file found");
+            return Ok(());
+        };
+        let cwd = std::env::current_dir()?;
+        let files = self
+            .files
+            .iter()
+            .map(|file| relative_to(&root, &cwd, file))
+            .collect::<Vec<_>>();
+        let ownership = codeowners.ownership(&files);
+        let unowned = files
+            .iter()
+            .filter(|file| codeowners.owners_of(file).is_empty())
+            .cloned()
+            .collect::<Vec<_>>();
+
+        let groups = ownership
+            .iter()
+            .map(|(owner, files)| (owner.as_str(), files))
+            .chain((!unowned.is_empty()).then_some(("(unowned)", &unowned)));
+        for (owner, files) in groups {
+            let count = files.len();
+            println!("{owner}: {count} file{}", if count == 1 { "" } else { "s" });
+            if self.show_files {
+                for file in files {
+                    println!("  {}", file.display());
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+fn relative_to(root: &Path, cwd: &Path, file: &Path) -> PathBuf {
+    let absolute = cwd.join(file);
+    absolute
+        .strip_prefix(root)
+        .map(Path::to_path_buf)
+        .unwrap_or(absolute)
+}
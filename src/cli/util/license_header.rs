@@ -0,0 +1,565 @@
+use crate::Result;
+use crate::file_type::get_file_types;
+use chrono::Datelike;
+use regex::Regex;
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use std::process::Command;
+use std::sync::LazyLock;
+
+/// Check for and optionally insert or update license headers
+///
+/// The header is rendered from a Tera template with `{{year}}` and
+/// `{{author}}` variables and commented with the syntax of each file's
+/// language. Shebangs, encoding declarations and `<?xml`/`<?php` lines stay
+/// at the top of the file.
+#[derive(Debug, clap::Args)]
+pub struct LicenseHeader {
+    /// Value of `{{author}}`, replacing the author of existing headers
+    /// (default: `git config user.name` for new headers, keeping the author
+    /// of existing ones)
+    #[clap(long)]
+    pub author: Option<String>,
+
+    /// Output a diff of the change. Cannot use with `fix`.
+    #[clap(short, long, conflicts_with = "fix")]
+    pub diff: bool,
+
+    /// Insert missing headers and update outdated ones
+    #[clap(short, long)]
+    pub fix: bool,
+
+    /// Header template, e.g. `SPDX-License-Identifier: MIT`
+    #[clap(
+        long,
+        required_unless_present = "template_file",
+        conflicts_with = "template_file"
+    )]
+    pub template: Option<String>,
+
+    /// Read the header template from a file
+    #[clap(long, value_hint = clap::ValueHint::FilePath)]
+    pub template_file: Option<PathBuf>,
+
+    /// Require the `{{year}}` in existing headers to end at the current
+    /// year, extending it to a range such as `2020-2026` when fixing
+    #[clap(long)]
+    pub update_year: bool,
+
+    /// Value of `{{year}}` (default: the current year)
+    #[clap(long)]
+    pub year: Option<String>,
+
+    /// Files to check or fix
+    #[clap(required = true)]
+    pub files: Vec<PathBuf>,
+}
+
+impl LicenseHeader {
+    pub async fn run(&self) -> Result<()> {
+        let template = match (&self.template, &self.template_file) {
+            (Some(template), _) => template.clone(),
+            (None, Some(path)) => xx::file::read_to_string(path)?,
+            (None, None) => unreachable!("clap requires --template or --template-file"),
+        };
+        let author = self
+            .author
+            .clone()
+            .or_else(git_user_name)
+            .unwrap_or_default();
+        let year = self
+            .year
+            .clone()
+            .unwrap_or_else(|| chrono::Local::now().year().to_string());
+        // Without an explicit author, every contributor would otherwise
+        // rewrite existing headers to their own git user name
+        let header = HeaderTemplate::new(&template, &author, &year)?
+            .update_author(self.author.is_some())
+            .update_year(self.update_year);
+
+        let mut found_issues = false;
+        for path in &self.files {
+            if path.is_dir() {
+                continue;
+            }
+            let Some(style) = CommentStyle::for_types(&get_file_types(path)) else {
+                debug!("{}: no known comment syntax, skipping", path.display());
+                continue;
+            };
+            let Ok(original) = std::fs::read_to_string(path) else {
+                continue;
+            };
+            let (status, fixed) = header.apply(&original, style);
+            if status == Status::Current {
+                continue;
+            }
+            if self.fix {
+                std::fs::write(path, &fixed)?;
+            } else if self.diff {
+                let path_str = path.display().to_string();
+                print!(
+                    "{}",
+                    crate::diff::render_unified_diff(
+                        &original,
+                        &fixed,
+                        &format!("a/{path_str}"),
+                        &format!("b/{path_str}"),
+                    )
+                );
+                found_issues = true;
+            } else {
+                println!("{}: {status} license header", path.display());
+                found_issues = true;
+            }
+        }
+
+        if !self.fix && found_issues {
+            std::process::exit(1);
+        }
+
+        Ok(())
+    }
+}
+
+fn git_user_name() -> Option<String> {
+    let output = Command::new("git")
+        .args(["config", "user.name"])
+        .output()
+        .ok()?;
+    let name = String::from_utf8(output.stdout).ok()?.trim().to_string();
+    (output.status.success() && !name.is_empty()).then_some(name)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum CommentStyle {
+    Line(&'static str),
+    Block {
+        start: &'static str,
+        middle: &'static str,
+        end: &'static str,
+    },
+}
+
+const SLASHES: CommentStyle = CommentStyle::Line("//");
+const HASH: CommentStyle = CommentStyle::Line("#");
+const C_BLOCK: CommentStyle = CommentStyle::Block {
+    start: "/*",
+    middle: " * ",
+    end: " */",
+};
+const MARKUP: CommentStyle = CommentStyle::Block {
+    start: "<!--",
+    middle: "",
+    end: "-->",
+};
+
+/// Comment syntax by file type, most specific first.
+const STYLES: &[(&str, CommentStyle)] = &[
+    ("rust", SLASHES),
+    ("go", SLASHES),
+    ("typescript", SLASHES),
+    ("javascript", SLASHES),
+    ("java", SLASHES),
+    ("kotlin", SLASHES),
+    ("swift", SLASHES),
+    ("c", SLASHES),
+    ("c++", SLASHES),
+    ("csharp", SLASHES),
+    ("php", SLASHES),
+    ("pkl", SLASHES),
+    ("css", C_BLOCK),
+    ("python", HASH),
+    ("ruby", HASH),
+    ("perl", HASH),
+    ("shell", HASH),
+    ("toml", HASH),
+    ("yaml", HASH),
+    ("dockerfile", HASH),
+    ("makefile", HASH),
+    ("lua", CommentStyle::Line("--")),
+    ("html", MARKUP),
+    ("xml", MARKUP),
+    ("markdown", MARKUP),
+];
+
+impl CommentStyle {
+    fn for_types(types: &HashSet<String>) -> Option<Self> {
+        STYLES
+            .iter()
+            .find(|(name, _)| types.contains(*name))
+            .map(|(_, style)| *style)
+    }
+
+    fn comment(&self, text: &str) -> Vec<String> {
+        let line = |prefix: &str, line: &str| format!("{prefix}{line}").trim_end().to_string();
+        match self {
+            CommentStyle::Line(prefix) => text
+                .lines()
+                .map(|l| line(&format!("{prefix} "), l))
+                .collect(),
+            CommentStyle::Block { start, middle, end } => std::iter::once(start.to_string())
+                .chain(text.lines().map(|l| line(middle, l)))
+                .chain(std::iter::once(end.to_string()))
+                .collect(),
+        }
+    }
+
+    /// The end (exclusive) of the comment starting at `lines[start]`, or
+    /// `start` if there is none.
+    fn comment_end(&self, lines: &[&str], start: usize) -> usize {
+        match self {
+            CommentStyle::Line(prefix) => {
+                start
+                    + lines[start..]
+                        .iter()
+                        .take_while(|line| line.trim_start().starts_with(prefix))
+                        .count()
+            }
+            CommentStyle::Block {
+                start: open, end, ..
+            } => {
+                if !lines
+                    .get(start)
+                    .is_some_and(|line| line.trim_start().starts_with(open))
+                {
+                    return start;
+                }
+                let close = end.trim();
+                lines[start..]
+                    .iter()
+                    .position(|line| line.contains(close))
+                    .map_or(lines.len(), |i| start + i + 1)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, strum::Display)]
+#[strum(serialize_all = "lowercase")]
+enum Status {
+    Current,
+    Missing,
+    Outdated,
+}
+
+const YEAR: &str = "\u{e000}year\u{e000}";
+const AUTHOR: &str = "\u{e000}author\u{e000}";
+
+static ENCODING: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^[ \t\f]*#.*?coding[:=][ \t]*[-_.a-zA-Z0-9]+").unwrap());
+
+struct HeaderTemplate {
+    /// The template rendered with placeholders for the year and author.
+    rendered: String,
+    author: String,
+    year: String,
+    update_author: bool,
+    update_year: bool,
+    /// Matches a header rendered with any year and author, per style.
+    loose: std::sync::Mutex<HashMap<CommentStyle, Regex>>,
+}
+
+impl HeaderTemplate {
+    fn new(template: &str, author: &str, year: &str) -> Result<Self> {
+        let mut ctx = crate::tera::Context::default();
+        ctx.insert("year", YEAR);
+        ctx.insert("author", AUTHOR);
+        let rendered = crate::tera::render(template.trim_end(), &ctx)?;
+        Ok(Self {
+            rendered,
+            author: author.to_string(),
+            year: year.to_string(),
+            update_author: false,
+            update_year: false,
+            loose: Default::default(),
+        })
+    }
+
+    /// Replace the author of existing headers instead of keeping it
+    fn update_author(mut self, update_author: bool) -> Self {
+        self.update_author = update_author;
+        self
+    }
+
+    /// Extend the year of existing headers to the current year
+    fn update_year(mut self, update_year: bool) -> Self {
+        self.update_year = update_year;
+        self
+    }
+
+    fn text(&self, year: &str, author: &str) -> String {
+        self.rendered.replace(YEAR, year).replace(AUTHOR, author)
+    }
+
+    fn loose(&self, style: CommentStyle) -> Regex {
+        let mut cache = self.loose.lock().unwrap();
+        cache
+            .entry(style)
+            .or_insert_with(|| {
+                let commented = style.comment(&self.rendered).join("\n");
+                let mut pattern = String::from("^");
+                let (mut years, mut authors) = (0, 0);
+                for (i, part) in commented.split('\u{e000}').enumerate() {
+                    match (i % 2, part) {
+                        (0, literal) => pattern.push_str(&regex::escape(literal)),
+                        (_, "year") if years == 0 => {
+                            years += 1;
+                            pattern.push_str(r"(?P<year>\d{4}(?:\s*[-–]\s*\d{4})?)");
+                        }
+                        (_, "year") => pattern.push_str(r"\d{4}(?:\s*[-–]\s*\d{4})?"),
+                        _ if authors == 0 => {
+                            authors += 1;
+                            pattern.push_str("(?P<author>.*?)");
+                        }
+                        _ => pattern.push_str(".*?"),
+                    }
+                }
+                pattern.push('$');
+                Regex::new(&pattern).unwrap()
+            })
+            .clone()
+    }
+
+    /// The year to write into an existing header whose year is `existing`.
+    fn updated_year(&self, existing: &str) -> String {
+        if !self.update_year {
+            return existing.to_string();
+        }
+        let first = |year: &str| year.chars().take(4).collect::<String>();
+        let (start, current) = (first(existing), first(&self.year));
+        if start.is_empty() || start >= current {
+            self.year.clone()
+        } else {
+            format!("{start}-{}", self.year)
+        }
+    }
+
+    /// Returns the header status of `text` and the text with the header
+    /// inserted or updated.
+    fn apply(&self, text: &str, style: CommentStyle) -> (Status, String) {
+        let newline = if text.contains("\r\n") { "\r\n" } else { "\n" };
+        let raw_lines = text.split_inclusive('\n').collect::<Vec<_>>();
+        let lines = raw_lines
+            .iter()
+            .map(|line| line.trim_end_matches(['\n', '\r']))
+            .collect::<Vec<_>>();
+
+        let prologue = prologue_len(&lines);
+        let start = prologue
+            + lines[prologue..]
+                .iter()
+                .take_while(|line| line.trim().is_empty())
+                .count();
+        let end = style.comment_end(&lines, start);
+        let existing = &lines[start..end];
+
+        let expected = style.comment(&self.text(&self.year, &self.author));
+        let n = expected.len();
+        let replace = |header: &[String], from: usize, to: usize| {
+            let mut out = raw_lines[..from].concat();
+            for line in header {
+                out.push_str(line);
+                out.push_str(newline);
+            }
+            out.push_str(&raw_lines[to..].concat());
+            out
+        };
+
+        if existing.len() >= n {
+            if existing[..n] == expected[..] {
+                return (Status::Current, text.to_string());
+            }
+            if let Some(captures) = self.loose(style).captures(&existing[..n].join("\n")) {
+                let year = captures
+                    .name("year")
+                    .map_or(self.year.clone(), |m| self.updated_year(m.as_str()));
+                let author = match captures.name("author") {
+                    Some(m) if !self.update_author => m.as_str(),
+                    _ => &self.author,
+                };
+                let updated = style.comment(&self.text(&year, author));
+                if existing[..n] == updated[..] {
+                    return (Status::Current, text.to_string());
+                }
+                return (Status::Outdated, replace(&updated, start, start + n));
+            }
+        }
+        let mentions_license = existing.iter().any(|line| {
+            let line = line.to_lowercase();
+            line.contains("spdx-license-identifier") || line.contains("copyright")
+        });
+        if mentions_license {
+            return (Status::Outdated, replace(&expected, start, end));
+        }
+
+        let mut out = raw_lines[..prologue].concat();
+        if !out.is_empty() && !out.ends_with('\n') {
+            out.push_str(newline);
+        }
+        for line in &expected {
+            out.push_str(line);
+            out.push_str(newline);
+        }
+        let rest = raw_lines[prologue..].concat();
+        if !rest.is_empty() && !rest.starts_with(['\n', '\r']) {
+            out.push_str(newline);
+        }
+        out.push_str(&rest);
+        (Status::Missing, out)
+    }
+}
+
+/// Number of leading lines that must stay above the header: a shebang, a
+/// Python encoding declaration, or an XML/PHP opening line.
+fn prologue_len(lines: &[&str]) -> usize {
+    let mut len = 0;
+    if lines.first().is_some_and(|line| {
+        line.starts_with("#!") || line.starts_with("<?xml") || line.starts_with("<?php")
+    }) {
+        len = 1;
+    }
+    if lines.get(len).is_some_and(|line| ENCODING.is_match(line)) {
+        len += 1;
+    }
+    len
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn header(template: &str, update_year: bool) -> HeaderTemplate {
+        HeaderTemplate::new(template, "Acme Inc.", "2026")
+            .unwrap()
+            .update_author(true)
+            .update_year(update_year)
+    }
+
+    const TEMPLATE: &str = "Copyright {{year}} {{author}}\nSPDX-License-Identifier: MIT";
+
+    #[test]
+    fn test_inserts_missing_header_after_shebang() {
+        let (status, fixed) = header(TEMPLATE, false).apply(
+            "#!/usr/bin/env python\n# -*- coding: utf-8 -*-\nprint(1)\n",
+            HASH,
+        );
+        assert_eq!(status, Status::Missing);
+        assert_eq!(
+            fixed,
+            "#!/usr/bin/env python\n# -*- coding: utf-8 -*-\n# Copyright 2026 Acme Inc.\n# SPDX-License-Identifier: MIT\n\nprint(1)\n"
+        );
+        assert_eq!(
+            header(TEMPLATE, false).apply(&fixed, HASH).0,
+            Status::Current
+        );
+    }
+
+    #[test]
+    fn test_inserts_block_comments() {
+        let (_, fixed) = header("SPDX-License-Identifier: MIT", false).apply("a {}\n", C_BLOCK);
+        assert_eq!(fixed, "/*\n * SPDX-License-Identifier: MIT\n */\n\na {}\n");
+        let (_, fixed) = header("SPDX-License-Identifier: MIT", false)
+            .apply("<?xml version=\"1.0\"?>\n<a/>\n", MARKUP);
+        assert_eq!(
+            fixed,
+            "<?xml version=\"1.0\"?>\n<!--\nSPDX-License-Identifier: MIT\n-->\n\n<a/>\n"
+        );
+    }
+
+    #[test]
+    fn test_keeps_existing_year_unless_updating() {
+        let text = "// Copyright 2020 Acme Inc.\n// SPDX-License-Identifier: MIT\n\nfn main() {}\n";
+        assert_eq!(
+            header(TEMPLATE, false).apply(text, SLASHES).0,
+            Status::Current
+        );
+
+        let (status, fixed) = header(TEMPLATE, true).apply(text, SLASHES);
+        assert_eq!(status, Status::Outdated);
+        assert_eq!(
+            fixed,
+            "// Copyright 2020-2026 Acme Inc.\n// SPDX-License-Identifier: MIT\n\nfn main() {}\n"
+        );
+        assert_eq!(
+            header(TEMPLATE, true).apply(&fixed, SLASHES).0,
+            Status::Current
+        );
+    }
+
+    #[test]
+    fn test_updates_outdated_author_and_license() {
+        let text =
+            "// Copyright 2020 Someone Else\n// SPDX-License-Identifier: MIT\nfn main() {}\n";
+        let (status, fixed) = header(TEMPLATE, false).apply(text, SLASHES);
+        assert_eq!(status, Status::Outdated);
+        assert!(
+            fixed.starts_with("// Copyright 2020 Acme Inc.\n"),
+            "{fixed}"
+        );
+
+        let text = "// SPDX-License-Identifier: Apache-2.0\n\nfn main() {}\n";
+        let (status, fixed) = header(TEMPLATE, false).apply(text, SLASHES);
+        assert_eq!(status, Status::Outdated);
+        assert_eq!(
+            fixed,
+            "// Copyright 2026 Acme Inc.\n// SPDX-License-Identifier: MIT\n\nfn main() {}\n"
+        );
+    }
+
+    #[test]
+    fn test_keeps_existing_author_unless_given() {
+        let text =
+            "// Copyright 2020 Someone Else\n// SPDX-License-Identifier: MIT\nfn main() {}\n";
+        let header = HeaderTemplate::new(TEMPLATE, "Acme Inc.", "2026").unwrap();
+        assert_eq!(header.apply(text, SLASHES).0, Status::Current);
+
+        let header = header.update_year(true);
+        let (status, fixed) = header.apply(text, SLASHES);
+        assert_eq!(status, Status::Outdated);
+        assert_eq!(
+            fixed,
+            "// Copyright 2020-2026 Someone Else\n// SPDX-License-Identifier: MIT\nfn main() {}\n"
+        );
+
+        let (_, fixed) = header.apply("fn main() {}\n", SLASHES);
+        assert!(
+            fixed.starts_with("// Copyright 2026 Acme Inc.\n"),
+            "{fixed}"
+        );
+    }
+
+    #[test]
+    fn test_keeps_unrelated_leading_comments() {
+        let text = "//! Crate docs\nfn main() {}\n";
+        let (status, fixed) = header("SPDX-License-Identifier: MIT", false).apply(text, SLASHES);
+        assert_eq!(status, Status::Missing);
+        assert_eq!(
+            fixed,
+            "// SPDX-License-Identifier: MIT\n\n//! Crate docs\nfn main() {}\n"
+        );
+    }
+
+    #[test]
+    fn test_preserves_crlf() {
+        let (_, fixed) = header("SPDX-License-Identifier: MIT", false).apply("x = 1\r\n", HASH);
+        assert_eq!(fixed, "# SPDX-License-Identifier: MIT\r\n\r\nx = 1\r\n");
+    }
+
+    #[test]
+    fn test_comment_style_for_types() {
+        let types = |names: &[&str]| names.iter().map(|n| n.to_string()).collect::<HashSet<_>>();
+        assert_eq!(
+            CommentStyle::for_types(&types(&["text", "rust"])),
+            Some(SLASHES)
+        );
+        assert_eq!(
+            CommentStyle::for_types(&types(&["text", "shell", "bash"])),
+            Some(HASH)
+        );
+        assert_eq!(
+            CommentStyle::for_types(&types(&["image", "svg", "xml"])),
+            Some(MARKUP)
+        );
+        assert_eq!(CommentStyle::for_types(&types(&["text"])), None);
+    }
+}
@@ -1,33 +1,59 @@
 mod byte_order_marker;
 mod check_added_large_files;
+mod check_branch_policy;
 mod check_case_conflict;
+mod check_codeowners;
+mod check_commit_msg;
 mod check_conventional_commit;
 mod check_executables_have_shebangs;
+mod check_json;
 mod check_merge_conflict;
+mod check_spelling;
 mod check_symlinks;
+mod check_toml;
+mod check_xml;
+mod check_yaml;
+mod codeowners;
 mod detect_private_key;
 mod end_of_file_fixer;
 mod fix_smart_quotes;
+mod license_header;
 mod mixed_line_ending;
 mod no_commit_to_branch;
+mod pretty_format_json;
 mod python_check_ast;
 mod python_debug_statements;
+mod reformat;
+mod sort_keys;
+mod structured_data;
 mod trailing_whitespace;
 
 pub use byte_order_marker::{CheckByteOrderMarker, FixByteOrderMarker};
 pub use check_added_large_files::CheckAddedLargeFiles;
+pub use check_branch_policy::CheckBranchPolicy;
 pub use check_case_conflict::CheckCaseConflict;
+pub use check_codeowners::CheckCodeowners;
+pub use check_commit_msg::CheckCommitMsg;
 pub use check_conventional_commit::CheckConventionalCommit;
 pub use check_executables_have_shebangs::CheckExecutablesHaveShebangs;
+pub use check_json::CheckJson;
 pub use check_merge_conflict::CheckMergeConflict;
+pub use check_spelling::CheckSpelling;
 pub use check_symlinks::CheckSymlinks;
+pub use check_toml::CheckToml;
+pub use check_xml::CheckXml;
+pub use check_yaml::CheckYaml;
+pub use codeowners::Codeowners;
 pub use detect_private_key::DetectPrivateKey;
 pub use end_of_file_fixer::EndOfFileFixer;
 pub use fix_smart_quotes::FixSmartQuotes;
+pub use license_header::LicenseHeader;
 pub use mixed_line_ending::MixedLineEnding;
 pub use no_commit_to_branch::NoCommitToBranch;
+pub use pretty_format_json::PrettyFormatJson;
 pub use python_check_ast::PythonCheckAst;
 pub use python_debug_statements::PythonDebugStatements;
+pub use sort_keys::SortKeys;
 pub use trailing_whitespace::TrailingWhitespace;
 
 use crate::Result;
@@ -43,10 +69,16 @@ pub struct Util {
 enum UtilCommands {
     /// Check for large files being added to repository
     CheckAddedLargeFiles(CheckAddedLargeFiles),
+    /// Enforce branch naming and protected-ref rules
+    CheckBranchPolicy(CheckBranchPolicy),
     /// Check for UTF-8 byte order marker (BOM)
     CheckByteOrderMarker(CheckByteOrderMarker),
     /// Check for case-insensitive filename conflicts
     CheckCaseConflict(CheckCaseConflict),
+    /// Validate CODEOWNERS files
+    CheckCodeowners(CheckCodeowners),
+    /// Lint a commit message against a configurable policy
+    CheckCommitMsg(CheckCommitMsg),
     /// Check for conventional commit message
     ///
     /// Titles starting with `fixup! `, `squash! `, or `amend! ` (temporary commits
@@ -54,11 +86,23 @@ enum UtilCommands {
     CheckConventionalCommit(CheckConventionalCommit),
     /// Check that executable files have shebangs
     CheckExecutablesHaveShebangs(CheckExecutablesHaveShebangs),
+    /// Check JSON files for valid syntax and duplicate keys
+    CheckJson(CheckJson),
     /// Check for merge conflict markers
     CheckMergeConflict(CheckMergeConflict),
+    /// Check for common misspellings in identifiers, comments and prose
+    CheckSpelling(CheckSpelling),
     /// Check for broken symlinks
     CheckSymlinks(CheckSymlinks),
-    /// Detect private keys in files
+    /// Check TOML files for valid syntax
+    CheckToml(CheckToml),
+    /// Check XML files for well-formedness
+    CheckXml(CheckXml),
+    /// Check YAML files for valid syntax and duplicate keys
+    CheckYaml(CheckYaml),
+    /// List the CODEOWNERS owners of files
+    Codeowners(Codeowners),
+    /// Detect private keys and other secrets in files
     DetectPrivateKey(DetectPrivateKey),
     /// Check for and optionally fix missing final newlines
     EndOfFileFixer(EndOfFileFixer),
@@ -66,14 +110,20 @@ enum UtilCommands {
     FixByteOrderMarker(FixByteOrderMarker),
     /// Replace UTF-8 smart quotes
     FixSmartQuotes(FixSmartQuotes),
+    /// Check for and optionally insert or update license headers
+    LicenseHeader(LicenseHeader),
     /// Detect and fix mixed line endings
     MixedLineEnding(MixedLineEnding),
     /// Prevent commits to specific branches
     NoCommitToBranch(NoCommitToBranch),
+    /// Normalize JSON indentation and optionally sort keys
+    PrettyFormatJson(PrettyFormatJson),
     /// Check Python files for valid syntax
     PythonCheckAst(PythonCheckAst),
     /// Detect Python debug statements
     PythonDebugStatements(PythonDebugStatements),
+    /// Sort keys in JSON and TOML files
+    SortKeys(SortKeys),
     /// Check for and optionally fix trailing whitespace
     TrailingWhitespace(TrailingWhitespace),
 }
@@ -82,20 +132,32 @@ impl Util {
     pub async fn run(&self) -> Result<()> {
         match &self.command {
             UtilCommands::CheckAddedLargeFiles(cmd) => cmd.run().await,
+            UtilCommands::CheckBranchPolicy(cmd) => cmd.run().await,
             UtilCommands::CheckByteOrderMarker(cmd) => cmd.run().await,
             UtilCommands::CheckCaseConflict(cmd) => cmd.run().await,
+            UtilCommands::CheckCodeowners(cmd) => cmd.run().await,
+            UtilCommands::CheckCommitMsg(cmd) => cmd.run().await,
             UtilCommands::CheckConventionalCommit(cmd) => cmd.run().await,
             UtilCommands::CheckExecutablesHaveShebangs(cmd) => cmd.run().await,
+            UtilCommands::CheckJson(cmd) => cmd.run().await,
             UtilCommands::CheckMergeConflict(cmd) => cmd.run().await,
+            UtilCommands::CheckSpelling(cmd) => cmd.run().await,
             UtilCommands::CheckSymlinks(cmd) => cmd.run().await,
+            UtilCommands::CheckToml(cmd) => cmd.run().await,
+            UtilCommands::CheckXml(cmd) => cmd.run().await,
+            UtilCommands::CheckYaml(cmd) => cmd.run().await,
+            UtilCommands::Codeowners(cmd) => cmd.run().await,
             UtilCommands::DetectPrivateKey(cmd) => cmd.run().await,
             UtilCommands::EndOfFileFixer(cmd) => cmd.run().await,
             UtilCommands::FixByteOrderMarker(cmd) => cmd.run().await,
             UtilCommands::FixSmartQuotes(cmd) => cmd.run().await,
+            UtilCommands::LicenseHeader(cmd) => cmd.run().await,
             UtilCommands::MixedLineEnding(cmd) => cmd.run().await,
             UtilCommands::NoCommitToBranch(cmd) => cmd.run().await,
+            UtilCommands::PrettyFormatJson(cmd) => cmd.run().await,
             UtilCommands::PythonCheckAst(cmd) => cmd.run().await,
             UtilCommands::PythonDebugStatements(cmd) => cmd.run().await,
+            UtilCommands::SortKeys(cmd) => cmd.run().await,
             UtilCommands::TrailingWhitespace(cmd) => cmd.run().await,
         }
     }
@@ -30,7 +30,7 @@ impl NoCommitToBranch {
     }
 }
 
-fn get_current_branch() -> Result<Option<String>> {
+pub(super) fn get_current_branch() -> Result<Option<String>> {
     get_current_branch_in(Path::new("."))
 }
 
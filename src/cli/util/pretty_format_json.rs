@@ -0,0 +1,292 @@
+use crate::Result;
+use std::path::PathBuf;
+
+use super::reformat::{KeyOrder, reformat_files};
+
+/// Normalize the indentation of JSON files, optionally sorting object keys
+#[derive(Debug, clap::Args)]
+pub struct PrettyFormatJson {
+    /// Output a diff of the change. Cannot use with `fix`.
+    #[clap(short, long, conflicts_with = "fix")]
+    pub diff: bool,
+
+    /// Rewrite files in place
+    #[clap(short, long)]
+    pub fix: bool,
+
+    /// Number of spaces to indent with, or `tab`
+    #[clap(long, default_value = "2", value_parser = parse_indent)]
+    pub indent: String,
+
+    /// Sort object keys
+    #[clap(long)]
+    pub sort_keys: bool,
+
+    /// Keys to place first in the top-level object, in order (implies --sort-keys)
+    #[clap(long, value_delimiter = ',')]
+    pub top_keys: Vec<String>,
+
+    /// Files to check or fix
+    #[clap(required = true)]
+    pub files: Vec<PathBuf>,
+}
+
+impl PrettyFormatJson {
+    pub async fn run(&self) -> Result<()> {
+        let order = (self.sort_keys || !self.top_keys.is_empty()).then_some(KeyOrder {
+            top: &self.top_keys,
+        });
+        reformat_files(&self.files, self.diff, self.fix, |_, text| {
+            format_json(text, &self.indent, order)
+        })
+    }
+}
+
+/// Parses `--indent`: a number of spaces, or `tab`.
+pub(super) fn parse_indent(value: &str) -> std::result::Result<String, String> {
+    match value {
+        "tab" | "\t" => Ok("\t".into()),
+        _ => value
+            .parse::<usize>()
+            .map(|width| " ".repeat(width))
+            .map_err(|_| format!("expected a number of spaces or `tab`, got `{value}`")),
+    }
+}
+
+/// Re-renders a JSON document with `indent`, sorting keys when `order` is
+/// set. Scalars are copied verbatim so numbers and string escapes are kept.
+pub(super) fn format_json(
+    text: &str,
+    indent: &str,
+    order: Option<KeyOrder>,
+) -> std::result::Result<String, String> {
+    let body = text.strip_prefix('\u{feff}').unwrap_or(text);
+    serde_json::from_str::<serde::de::IgnoredAny>(body).map_err(|error| error.to_string())?;
+
+    let mut parser = Parser { text: body, pos: 0 };
+    let mut value = parser.value();
+    if let Some(order) = order {
+        value.sort(order);
+    }
+
+    let mut out = String::with_capacity(text.len());
+    if body.len() != text.len() {
+        out.push('\u{feff}');
+    }
+    value.write(&mut out, indent, 0);
+    out.push('\n');
+    Ok(out)
+}
+
+#[derive(Debug)]
+enum Json<'a> {
+    /// A string, number, boolean or null, exactly as written.
+    Scalar(&'a str),
+    Array(Vec<Json<'a>>),
+    /// Members with their keys still quoted and escaped.
+    Object(Vec<(&'a str, Json<'a>)>),
+}
+
+impl Json<'_> {
+    fn sort(&mut self, order: KeyOrder) {
+        match self {
+            Json::Scalar(_) => {}
+            Json::Array(items) => {
+                for item in items {
+                    item.sort(order.nested());
+                }
+            }
+            Json::Object(members) => {
+                members.sort_by(|(a, _), (b, _)| order.compare(&unquote(a), &unquote(b)));
+                for (_, value) in members {
+                    value.sort(order.nested());
+                }
+            }
+        }
+    }
+
+    fn write(&self, out: &mut String, indent: &str, depth: usize) {
+        match self {
+            Json::Scalar(raw) => out.push_str(raw),
+            Json::Array(items) if items.is_empty() => out.push_str("[]"),
+            Json::Object(members) if members.is_empty() => out.push_str("{}"),
+            Json::Array(items) => {
+                out.push('[');
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        out.push(',');
+                    }
+                    newline(out, indent, depth + 1);
+                    item.write(out, indent, depth + 1);
+                }
+                newline(out, indent, depth);
+                out.push(']');
+            }
+            Json::Object(members) => {
+                out.push('{');
+                for (i, (key, value)) in members.iter().enumerate() {
+                    if i > 0 {
+                        out.push(',');
+                    }
+                    newline(out, indent, depth + 1);
+                    out.push_str(key);
+                    out.push_str(": ");
+                    value.write(out, indent, depth + 1);
+                }
+                newline(out, indent, depth);
+                out.push('}');
+            }
+        }
+    }
+}
+
+fn newline(out: &mut String, indent: &str, depth: usize) {
+    out.push('\n');
+    for _ in 0..depth {
+        out.push_str(indent);
+    }
+}
+
+fn unquote(key: &str) -> String {
+    serde_json::from_str(key).unwrap_or_else(|_| key.to_string())
+}
+
+/// A parser for documents already validated by serde_json, so it never
+/// needs to report errors.
+struct Parser<'a> {
+    text: &'a str,
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn value(&mut self) -> Json<'a> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some(b'[') => {
+                self.pos += 1;
+                let mut items = vec![];
+                while !self.close(b']') {
+                    items.push(self.value());
+                }
+                Json::Array(items)
+            }
+            Some(b'{') => {
+                self.pos += 1;
+                let mut members = vec![];
+                while !self.close(b'}') {
+                    self.skip_whitespace();
+                    let key = self.string();
+                    self.skip_whitespace();
+                    self.pos += 1; // `:`
+                    members.push((key, self.value()));
+                }
+                Json::Object(members)
+            }
+            Some(b'"') => Json::Scalar(self.string()),
+            _ => {
+                let start = self.pos;
+                while let Some(b) = self.peek()
+                    && !matches!(b, b',' | b']' | b'}' | b' ' | b'\t' | b'\n' | b'\r')
+                {
+                    self.pos += 1;
+                }
+                Json::Scalar(&self.text[start..self.pos])
+            }
+        }
+    }
+
+    /// Consumes a separating `,` and returns false, or consumes `close` and
+    /// returns true.
+    fn close(&mut self, close: u8) -> bool {
+        self.skip_whitespace();
+        match self.peek() {
+            Some(b',') => {
+                self.pos += 1;
+                false
+            }
+            Some(b) if b == close => {
+                self.pos += 1;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn string(&mut self) -> &'a str {
+        let start = self.pos;
+        self.pos += 1;
+        while let Some(b) = self.peek() {
+            self.pos += 1;
+            match b {
+                b'\\' => self.pos += 1,
+                b'"' => break,
+                _ => {}
+            }
+        }
+        &self.text[start..self.pos]
+    }
+
+    fn skip_whitespace(&mut self) {
+        while matches!(self.peek(), Some(b' ' | b'\t' | b'\n' | b'\r')) {
+            self.pos += 1;
+        }
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.text.as_bytes().get(self.pos).copied()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_json_normalizes_indentation() {
+        let text = "{\"b\": [1, 2.50, {}], \"a\": {\"x\": \"\\u00e9\"}, \"c\": []}";
+        assert_eq!(
+            format_json(text, "  ", None).unwrap(),
+            "{\n  \"b\": [\n    1,\n    2.50,\n    {}\n  ],\n  \"a\": {\n    \"x\": \"\\u00e9\"\n  },\n  \"c\": []\n}\n"
+        );
+        assert_eq!(format_json("[1]", "\t", None).unwrap(), "[\n\t1\n]\n");
+        assert_eq!(format_json(" \"x\" ", "  ", None).unwrap(), "\"x\"\n");
+    }
+
+    #[test]
+    fn test_format_json_sorts_keys() {
+        let top = vec!["name".to_string()];
+        let order = KeyOrder { top: &top };
+        let text = r#"{"z": {"name": 1, "b": 2, "a": 3}, "name": "x", "a": [{"d": 1, "c": 2}]}"#;
+        assert_eq!(
+            format_json(text, "  ", Some(order)).unwrap(),
+            r#"{
+  "name": "x",
+  "a": [
+    {
+      "c": 2,
+      "d": 1
+    }
+  ],
+  "z": {
+    "a": 3,
+    "b": 2,
+    "name": 1
+  }
+}
+"#
+        );
+    }
+
+    #[test]
+    fn test_format_json_rejects_invalid_json() {
+        let error = format_json("[1,]", "  ", None).unwrap_err();
+        assert!(error.contains("line 1 column 4"), "{error}");
+    }
+
+    #[test]
+    fn test_parse_indent() {
+        assert_eq!(parse_indent("4").unwrap(), "    ");
+        assert_eq!(parse_indent("tab").unwrap(), "\t");
+        assert!(parse_indent("wide").is_err());
+    }
+}
@@ -0,0 +1,107 @@
+//! Shared plumbing for the formatters that rewrite whole files
+//! (`pretty-format-json` and `sort-keys`).
+
+use std::cmp::Ordering;
+use std::path::PathBuf;
+
+/// Checks, diffs or fixes each file with `format`, which returns the
+/// formatted contents or a message explaining why the file can't be parsed.
+///
+/// In check and diff mode, exits with code 1 if any file would change.
+pub(super) fn reformat_files(
+    files: &[PathBuf],
+    diff: bool,
+    fix: bool,
+    format: impl Fn(&PathBuf, &str) -> Result<String, String>,
+) -> crate::Result<()> {
+    let mut found_unformatted = false;
+    let mut found_invalid = false;
+
+    for path in files {
+        if path.is_dir() {
+            continue;
+        }
+        let original = std::fs::read_to_string(path)?;
+        let formatted = match format(path, &original) {
+            Ok(formatted) => formatted,
+            Err(message) => {
+                eprintln!("{}: {message}", path.display());
+                found_invalid = true;
+                continue;
+            }
+        };
+        if formatted == original {
+            continue;
+        }
+
+        if fix {
+            std::fs::write(path, &formatted)?;
+        } else if diff {
+            let path_str = path.display().to_string();
+            print!(
+                "{}",
+                crate::diff::render_unified_diff(
+                    &original,
+                    &formatted,
+                    &format!("a/{path_str}"),
+                    &format!("b/{path_str}"),
+                )
+            );
+            found_unformatted = true;
+        } else {
+            println!("{}", path.display());
+            found_unformatted = true;
+        }
+    }
+
+    if found_invalid {
+        return Err(eyre::eyre!("Files that could not be parsed found"));
+    }
+    if !fix && found_unformatted {
+        std::process::exit(1);
+    }
+
+    Ok(())
+}
+
+/// How object keys are ordered: keys listed in `top` come first in the
+/// order given, followed by the rest sorted by code point.
+#[derive(Debug, Clone, Copy)]
+pub(super) struct KeyOrder<'a> {
+    pub top: &'a [String],
+}
+
+impl KeyOrder<'_> {
+    pub fn compare(&self, a: &str, b: &str) -> Ordering {
+        let rank = |key: &str| self.top.iter().position(|top| top == key);
+        match (rank(a), rank(b)) {
+            (Some(a), Some(b)) => a.cmp(&b),
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (None, None) => a.cmp(b),
+        }
+    }
+
+    /// Top keys only apply to the outermost object.
+    pub fn nested(&self) -> KeyOrder<'static> {
+        KeyOrder { top: &[] }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_key_order_puts_top_keys_first() {
+        let top = vec!["name".to_string(), "version".to_string()];
+        let order = KeyOrder { top: &top };
+        let mut keys = vec!["scripts", "version", "author", "name"];
+        keys.sort_by(|a, b| order.compare(a, b));
+        assert_eq!(keys, vec!["name", "version", "author", "scripts"]);
+
+        let mut keys = vec!["version", "b", "a"];
+        keys.sort_by(|a, b| order.nested().compare(a, b));
+        assert_eq!(keys, vec!["a", "b", "version"]);
+    }
+}
@@ -0,0 +1,238 @@
+use crate::Result;
+use std::path::{Path, PathBuf};
+use toml_edit::{DocumentMut, Item, Table, Value};
+
+use super::pretty_format_json::{format_json, parse_indent};
+use super::reformat::{KeyOrder, reformat_files};
+
+/// Sort object keys in JSON files and table keys in TOML files
+///
+/// TOML files keep their comments and formatting; only the order of keys
+/// and tables changes. JSON files are re-indented like `pretty-format-json`.
+#[derive(Debug, clap::Args)]
+pub struct SortKeys {
+    /// Output a diff of the change. Cannot use with `fix`.
+    #[clap(short, long, conflicts_with = "fix")]
+    pub diff: bool,
+
+    /// Rewrite files in place
+    #[clap(short, long)]
+    pub fix: bool,
+
+    /// Number of spaces to indent JSON with, or `tab`
+    #[clap(long, default_value = "2", value_parser = parse_indent)]
+    pub indent: String,
+
+    /// Keys to place first in the top-level object or table, in order,
+    /// e.g. `name,version,description` for `package.json`
+    #[clap(long, value_delimiter = ',')]
+    pub top_keys: Vec<String>,
+
+    /// Files to check or fix. Files ending in `.toml` are parsed as TOML,
+    /// everything else as JSON.
+    #[clap(required = true)]
+    pub files: Vec<PathBuf>,
+}
+
+impl SortKeys {
+    pub async fn run(&self) -> Result<()> {
+        let order = KeyOrder {
+            top: &self.top_keys,
+        };
+        reformat_files(&self.files, self.diff, self.fix, |path, text| {
+            if is_toml(path) {
+                sort_toml(text, order)
+            } else {
+                format_json(text, &self.indent, Some(order))
+            }
+        })
+    }
+}
+
+fn is_toml(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("toml"))
+}
+
+/// Sorts the keys of every table in a TOML document. Comments and
+/// whitespace are attached to the keys and table headers they precede, so
+/// they move along with them; blank lines before table headers are then
+/// evened out so the reordered tables stay visually separated.
+fn sort_toml(text: &str, order: KeyOrder) -> std::result::Result<String, String> {
+    let mut doc = text
+        .parse::<DocumentMut>()
+        .map_err(|error| error.to_string().trim_end().to_string())?;
+    let root = doc.as_table_mut();
+    let mut layout = Layout {
+        position: 0,
+        separate_headers: has_separated_headers(root),
+        started: !root.get_values().is_empty(),
+    };
+    sort_table(root, order, &mut layout);
+    Ok(doc.to_string())
+}
+
+/// Tracks where sorted tables are written.
+struct Layout {
+    position: isize,
+    /// Whether the document put blank lines before its table headers.
+    separate_headers: bool,
+    /// Whether anything has been written before the next header.
+    started: bool,
+}
+
+/// Sorts `table` recursively and renumbers header positions so tables are
+/// written in the sorted order.
+fn sort_table(table: &mut Table, order: KeyOrder, layout: &mut Layout) {
+    if !table.is_dotted() {
+        table.set_position(Some(layout.position));
+        if layout.position > 0 && !(table.is_implicit() && table.get_values().is_empty()) {
+            let prefix = header_prefix(table);
+            let comments = prefix.trim_start_matches(['\n', '\r']);
+            let prefix = if !layout.started {
+                comments.to_string()
+            } else if layout.separate_headers {
+                format!("\n{comments}")
+            } else {
+                prefix.to_string()
+            };
+            table.decor_mut().set_prefix(prefix);
+            layout.started = true;
+        }
+        layout.position += 1;
+    }
+    table.sort_values_by(|a, _, b, _| order.compare(a.get(), b.get()));
+    for (_, item) in table.iter_mut() {
+        match item {
+            Item::Table(table) => sort_table(table, order.nested(), layout),
+            Item::ArrayOfTables(array) => {
+                for table in array.iter_mut() {
+                    sort_table(table, order.nested(), layout);
+                }
+            }
+            Item::Value(value) => sort_value(value),
+            Item::None => {}
+        }
+    }
+}
+
+fn header_prefix(table: &Table) -> &str {
+    table
+        .decor()
+        .prefix()
+        .and_then(|prefix| prefix.as_str())
+        .unwrap_or_default()
+}
+
+fn has_separated_headers(table: &Table) -> bool {
+    table.iter().any(|(_, item)| match item {
+        Item::Table(table) => {
+            (!table.is_dotted() && header_prefix(table).starts_with(['\n', '\r']))
+                || has_separated_headers(table)
+        }
+        Item::ArrayOfTables(array) => array.iter().any(|table| {
+            header_prefix(table).starts_with(['\n', '\r']) || has_separated_headers(table)
+        }),
+        _ => false,
+    })
+}
+
+fn sort_value(value: &mut Value) {
+    match value {
+        Value::InlineTable(table) => {
+            let keys = table.iter().map(|(key, _)| key).collect::<Vec<_>>();
+            if !keys.is_sorted() {
+                // the spacing around keys moves with them, so re-space
+                table.sort_values();
+                table.fmt();
+            }
+            for (_, value) in table.iter_mut() {
+                sort_value(value);
+            }
+        }
+        Value::Array(array) => {
+            for value in array.iter_mut() {
+                sort_value(value);
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sort_toml_keeps_comments() {
+        let text = r#"# header comment
+name = "x"
+# about b
+b = { z = 1, a = 2 }
+a = 1 # trailing
+
+# second table
+[z]
+d = 1
+c = 2
+
+# first table
+[m]
+y = 1
+x = 2
+"#;
+        let top = vec!["name".to_string()];
+        assert_eq!(
+            sort_toml(text, KeyOrder { top: &top }).unwrap(),
+            r#"# header comment
+name = "x"
+a = 1 # trailing
+# about b
+b = { a = 2, z = 1 }
+
+# first table
+[m]
+x = 2
+y = 1
+
+# second table
+[z]
+c = 2
+d = 1
+"#
+        );
+    }
+
+    #[test]
+    fn test_sort_toml_keeps_array_of_tables_order() {
+        let text =
+            "[[bin]]\nname = \"b\"\npath = \"b.rs\"\n\n[[bin]]\npath = \"a.rs\"\nname = \"a\"\n";
+        let sorted = sort_toml(text, KeyOrder { top: &[] }).unwrap();
+        assert_eq!(
+            sorted,
+            "[[bin]]\nname = \"b\"\npath = \"b.rs\"\n\n[[bin]]\nname = \"a\"\npath = \"a.rs\"\n"
+        );
+    }
+
+    #[test]
+    fn test_sort_toml_evens_out_blank_lines_before_headers() {
+        let text = "# z\n[z]\nb = 1\n\n# m\n[m]\na = 1\n";
+        assert_eq!(
+            sort_toml(text, KeyOrder { top: &[] }).unwrap(),
+            "# m\n[m]\na = 1\n\n# z\n[z]\nb = 1\n"
+        );
+    }
+
+    #[test]
+    fn test_sort_toml_is_idempotent() {
+        let text = "b = 1\na = 2\n[t]\ny = 1\nx = 2\n";
+        let once = sort_toml(text, KeyOrder { top: &[] }).unwrap();
+        assert_eq!(sort_toml(&once, KeyOrder { top: &[] }).unwrap(), once);
+    }
+
+    #[test]
+    fn test_is_toml() {
+        assert!(is_toml(Path::new("Cargo.toml")));
+        assert!(!is_toml(Path::new("package.json")));
+    }
+}
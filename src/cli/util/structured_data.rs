@@ -0,0 +1,331 @@
+//! Shared plumbing for the structured-data validators (`check-json`,
+//! `check-yaml`, `check-toml` and `check-xml`).
+
+use serde::de::{self, DeserializeSeed, Deserializer, EnumAccess, MapAccess, SeqAccess, Visitor};
+use std::collections::HashSet;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+/// A problem found in a file, reported as a GCC-style diagnostic so it can be
+/// parsed with `diagnostic_format = "gcc"`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct Problem {
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+    pub rule: &'static str,
+}
+
+impl Problem {
+    pub fn new(line: usize, column: usize, message: impl Into<String>, rule: &'static str) -> Self {
+        Self {
+            line: line.max(1),
+            column: column.max(1),
+            message: message.into(),
+            rule,
+        }
+    }
+
+    /// Builds a problem from a byte offset into `text`.
+    pub fn at_offset(
+        text: &str,
+        offset: usize,
+        message: impl Into<String>,
+        rule: &'static str,
+    ) -> Self {
+        let (line, column) = position(text, offset);
+        Self::new(line, column, message, rule)
+    }
+}
+
+/// Converts a byte offset into a 1-based line and character column.
+pub(super) fn position(text: &str, offset: usize) -> (usize, usize) {
+    let mut offset = offset.min(text.len());
+    while !text.is_char_boundary(offset) {
+        offset -= 1;
+    }
+    let before = &text[..offset];
+    let line = before.matches('\n').count() + 1;
+    let line_start = before.rfind('\n').map_or(0, |index| index + 1);
+    (line, before[line_start..].chars().count() + 1)
+}
+
+/// Validates each file with `check`, printing one diagnostic per problem.
+pub(super) fn check_files(
+    files: &[PathBuf],
+    kind: &str,
+    check: impl Fn(&str) -> Vec<Problem>,
+) -> crate::Result<()> {
+    let mut found_invalid = false;
+    for path in files {
+        let problems = match std::fs::read(path) {
+            Ok(bytes) => match String::from_utf8(bytes) {
+                Ok(text) => check(text.strip_prefix('\u{feff}').unwrap_or(&text)),
+                Err(error) => {
+                    let text = String::from_utf8_lossy(error.as_bytes());
+                    let offset = error.utf8_error().valid_up_to();
+                    vec![Problem::at_offset(
+                        &text,
+                        offset,
+                        "file is not valid UTF-8",
+                        "encoding",
+                    )]
+                }
+            },
+            Err(error) => vec![Problem::new(
+                1,
+                1,
+                format!("failed to read file: {error}"),
+                "io",
+            )],
+        };
+        for problem in &problems {
+            println!("{}", format_problem(path, problem));
+        }
+        found_invalid |= !problems.is_empty();
+    }
+
+    if found_invalid {
+        return Err(eyre::eyre!("Files with invalid {kind} found"));
+    }
+
+    Ok(())
+}
+
+fn format_problem(path: &Path, problem: &Problem) -> String {
+    format!(
+        "{}:{}:{}: error: {} [{}]",
+        path.display(),
+        problem.line,
+        problem.column,
+        problem.message,
+        problem.rule
+    )
+}
+
+/// Classifies an error raised while walking a document with [`Validate`].
+/// YAML errors may be prefixed with the path to the offending value.
+pub(super) fn rule_for(message: &str) -> &'static str {
+    if message.contains("duplicate key ") {
+        "duplicate-key"
+    } else if message.contains("unknown tag !") {
+        "custom-tag"
+    } else {
+        "syntax"
+    }
+}
+
+/// Extracts the raw key from a duplicate key error raised by [`Validate`].
+pub(super) fn duplicate_key(message: &str) -> Option<String> {
+    let (_, key) = message.split_once("duplicate key ")?;
+    Some(serde_json::from_str::<String>(key).unwrap_or_else(|_| key.to_string()))
+}
+
+/// Walks a JSON or YAML document without building it, rejecting duplicate
+/// mapping keys and, unless allowed, YAML tags outside the core schema.
+#[derive(Debug, Clone, Copy)]
+pub(super) struct Validate {
+    pub allow_custom_tags: bool,
+}
+
+impl<'de> DeserializeSeed<'de> for Validate {
+    type Value = ();
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<(), D::Error> {
+        deserializer.deserialize_any(self)
+    }
+}
+
+impl<'de> Visitor<'de> for Validate {
+    type Value = ();
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("any value")
+    }
+
+    fn visit_bool<E>(self, _: bool) -> Result<(), E> {
+        Ok(())
+    }
+
+    fn visit_i64<E>(self, _: i64) -> Result<(), E> {
+        Ok(())
+    }
+
+    fn visit_i128<E>(self, _: i128) -> Result<(), E> {
+        Ok(())
+    }
+
+    fn visit_u64<E>(self, _: u64) -> Result<(), E> {
+        Ok(())
+    }
+
+    fn visit_u128<E>(self, _: u128) -> Result<(), E> {
+        Ok(())
+    }
+
+    fn visit_f64<E>(self, _: f64) -> Result<(), E> {
+        Ok(())
+    }
+
+    fn visit_str<E>(self, _: &str) -> Result<(), E> {
+        Ok(())
+    }
+
+    fn visit_bytes<E>(self, _: &[u8]) -> Result<(), E> {
+        Ok(())
+    }
+
+    fn visit_unit<E>(self) -> Result<(), E> {
+        Ok(())
+    }
+
+    fn visit_none<E>(self) -> Result<(), E> {
+        Ok(())
+    }
+
+    fn visit_some<D: Deserializer<'de>>(self, deserializer: D) -> Result<(), D::Error> {
+        self.deserialize(deserializer)
+    }
+
+    fn visit_newtype_struct<D: Deserializer<'de>>(self, deserializer: D) -> Result<(), D::Error> {
+        self.deserialize(deserializer)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<(), A::Error> {
+        while seq.next_element_seed(self)?.is_some() {}
+        Ok(())
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<(), A::Error> {
+        let mut seen = HashSet::new();
+        while let Some(key) = map.next_key_seed(KeySeed(self))? {
+            if let Some(key) = key
+                && !seen.insert(key.clone())
+            {
+                return Err(de::Error::custom(format!("duplicate key {key}")));
+            }
+            map.next_value_seed(self)?;
+        }
+        Ok(())
+    }
+
+    fn visit_enum<A: EnumAccess<'de>>(self, data: A) -> Result<(), A::Error> {
+        let (tag, value) = data.variant::<String>()?;
+        if !self.allow_custom_tags && !is_core_tag(&tag) {
+            return Err(de::Error::custom(format!("unknown tag !{tag}")));
+        }
+        de::VariantAccess::newtype_variant_seed(value, self)
+    }
+}
+
+/// Tags from the YAML 1.1 type repository that safe YAML loaders accept.
+fn is_core_tag(tag: &str) -> bool {
+    let tag = tag
+        .strip_prefix("tag:yaml.org,2002:")
+        .or_else(|| tag.strip_prefix('!'))
+        .unwrap_or(tag);
+    matches!(
+        tag,
+        "binary"
+            | "bool"
+            | "float"
+            | "int"
+            | "map"
+            | "merge"
+            | "null"
+            | "omap"
+            | "pairs"
+            | "seq"
+            | "set"
+            | "str"
+            | "timestamp"
+            | "value"
+    )
+}
+
+/// A scalar mapping key, rendered for duplicate detection and messages.
+/// Complex keys (YAML sequences or mappings) are validated but not compared.
+struct KeySeed(Validate);
+
+impl<'de> DeserializeSeed<'de> for KeySeed {
+    type Value = Option<String>;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        deserializer.deserialize_any(self)
+    }
+}
+
+impl<'de> Visitor<'de> for KeySeed {
+    type Value = Option<String>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a mapping key")
+    }
+
+    fn visit_bool<E>(self, value: bool) -> Result<Self::Value, E> {
+        Ok(Some(value.to_string()))
+    }
+
+    fn visit_i64<E>(self, value: i64) -> Result<Self::Value, E> {
+        Ok(Some(value.to_string()))
+    }
+
+    fn visit_i128<E>(self, value: i128) -> Result<Self::Value, E> {
+        Ok(Some(value.to_string()))
+    }
+
+    fn visit_u64<E>(self, value: u64) -> Result<Self::Value, E> {
+        Ok(Some(value.to_string()))
+    }
+
+    fn visit_u128<E>(self, value: u128) -> Result<Self::Value, E> {
+        Ok(Some(value.to_string()))
+    }
+
+    fn visit_f64<E>(self, value: f64) -> Result<Self::Value, E> {
+        Ok(Some(value.to_string()))
+    }
+
+    fn visit_str<E>(self, value: &str) -> Result<Self::Value, E> {
+        Ok(Some(serde_json::Value::from(value).to_string()))
+    }
+
+    fn visit_unit<E>(self) -> Result<Self::Value, E> {
+        Ok(Some("null".into()))
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, seq: A) -> Result<Self::Value, A::Error> {
+        self.0.visit_seq(seq).map(|()| None)
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, map: A) -> Result<Self::Value, A::Error> {
+        self.0.visit_map(map).map(|()| None)
+    }
+
+    fn visit_enum<A: EnumAccess<'de>>(self, data: A) -> Result<Self::Value, A::Error> {
+        self.0.visit_enum(data).map(|()| None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_position_counts_characters() {
+        let text = "a\né: x\n";
+        assert_eq!(position(text, 0), (1, 1));
+        assert_eq!(position(text, 2), (2, 1));
+        assert_eq!(position(text, 4), (2, 2));
+        assert_eq!(position(text, 100), (3, 1));
+    }
+
+    #[test]
+    fn test_format_problem_is_gcc_style() {
+        let problem = Problem::new(3, 7, "duplicate key \"a\"", "duplicate-key");
+        assert_eq!(
+            format_problem(Path::new("a.json"), &problem),
+            "a.json:3:7: error: duplicate key \"a\" [duplicate-key]"
+        );
+    }
+}
@@ -1,10 +1,32 @@
 use eyre::bail;
+use serde_json::json;
 
+use crate::config_lint::{self, Finding, Severity};
+use crate::git::Git;
+use crate::settings::Settings;
+use crate::ui::style;
 use crate::{Result, config::Config};
 
 /// Validate the config file
+///
+/// Besides the structural checks hk runs whenever it loads the config, this
+/// reports `depends` cycles and references to unknown steps, globs that match
+/// no tracked file, steps that stage the same files in parallel, command
+/// templates using unknown variables, conditions that fail to parse and
+/// profiles that no step uses.
+///
+/// Exits non-zero if any error is found, or any warning with `--strict`.
 #[derive(Debug, clap::Args)]
-pub struct Validate {}
+#[clap(verbatim_doc_comment)]
+pub struct Validate {
+    /// Output format
+    #[clap(long, value_parser = ["human", "json"], default_value = "human")]
+    format: String,
+
+    /// Fail on warnings as well as errors
+    #[clap(long)]
+    strict: bool,
+}
 
 impl Validate {
     pub async fn run(&self) -> Result<()> {
@@ -16,7 +38,50 @@ impl Validate {
                 xx::file::display_path(&config.path)
             );
         }
-        info!("{} is valid", xx::file::display_path(&config.path));
+        // Outside a repository there is nothing to check globs against
+        let files = Git::new()
+            .and_then(|git| git.all_files(None))
+            .map(|files| files.into_iter().collect::<Vec<_>>())
+            .unwrap_or_default();
+        let findings = config_lint::lint(&config, &files, &Settings::get().profiles);
+        let count = |severity: Severity| findings.iter().filter(|f| f.severity == severity).count();
+        let (errors, warnings) = (count(Severity::Error), count(Severity::Warning));
+
+        if self.format == "json" {
+            let output = json!({
+                "path": config.path,
+                "findings": findings,
+                "errors": errors,
+                "warnings": warnings,
+            });
+            println!("{}", serde_json::to_string_pretty(&output)?);
+        } else {
+            for finding in &findings {
+                print_finding(finding);
+            }
+        }
+        if errors > 0 || (self.strict && warnings > 0) {
+            bail!(
+                "{} has {errors} error(s) and {warnings} warning(s)",
+                xx::file::display_path(&config.path)
+            );
+        }
+        if self.format != "json" {
+            info!("{} is valid", xx::file::display_path(&config.path));
+        }
         Ok(())
     }
 }
+
+fn print_finding(finding: &Finding) {
+    let severity = match finding.severity {
+        Severity::Error => style::nred("error"),
+        Severity::Warning => style::nyellow("warning"),
+    };
+    let location = match (&finding.hook, &finding.step) {
+        (Some(hook), Some(step)) => format!("step '{step}' in hook '{hook}': "),
+        (Some(hook), None) => format!("hook '{hook}': "),
+        _ => String::new(),
+    };
+    println!("{severity}: {}{}", style::nbold(location), finding.message);
+}
@@ -0,0 +1,306 @@
+//! Parsing and matching for `CODEOWNERS` files.
+//!
+//! Follows GitHub's rules: the file is read from `.github/`, the repository
+//! root or `docs/` (first found wins), patterns use gitignore-style syntax
+//! relative to the repository root, and the last matching pattern decides a
+//! path's owners.
+
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+
+use globset::{GlobBuilder, GlobSet, GlobSetBuilder};
+use indexmap::IndexMap;
+use regex::Regex;
+
+use crate::Result;
+
+/// Where `CODEOWNERS` is looked for, relative to the repository root.
+pub const LOCATIONS: &[&str] = &[".github/CODEOWNERS", "CODEOWNERS", "docs/CODEOWNERS"];
+
+static OWNER: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^(@[A-Za-z0-9][A-Za-z0-9-]*(/[A-Za-z0-9._-]+)?|[^@\s]+@[^@\s]+\.[^@\s]+)$")
+        .unwrap()
+});
+
+#[derive(Debug)]
+pub struct Rule {
+    pub line: usize,
+    pub pattern: String,
+    pub owners: Vec<String>,
+    globs: GlobSet,
+}
+
+impl Rule {
+    pub fn is_match(&self, path: &Path) -> bool {
+        self.globs.is_match(path)
+    }
+}
+
+/// A problem with one line of a `CODEOWNERS` file.
+#[derive(Debug, PartialEq, Eq)]
+pub struct LineError {
+    pub line: usize,
+    pub message: String,
+}
+
+#[derive(Debug, Default)]
+pub struct CodeOwners {
+    pub path: PathBuf,
+    pub rules: Vec<Rule>,
+    pub errors: Vec<LineError>,
+}
+
+impl CodeOwners {
+    /// Finds and parses the `CODEOWNERS` file of the repository at `root`.
+    pub fn discover(root: &Path) -> Result<Option<Self>> {
+        for location in LOCATIONS {
+            let path = root.join(location);
+            if path.is_file() {
+                return Self::from_path(&path).map(Some);
+            }
+        }
+        Ok(None)
+    }
+
+    pub fn from_path(path: &Path) -> Result<Self> {
+        let mut codeowners = Self::parse(&xx::file::read_to_string(path)?);
+        codeowners.path = path.to_path_buf();
+        Ok(codeowners)
+    }
+
+    pub fn parse(text: &str) -> Self {
+        let mut codeowners = Self::default();
+        for (i, raw) in text.lines().enumerate() {
+            let line = i + 1;
+            let content = strip_comment(raw);
+            let content = content.trim();
+            // GitLab section headers, e.g. `[Docs]` or `^[Optional][2] @owner`
+            if content.is_empty() || content.starts_with('[') || content.starts_with("^[") {
+                continue;
+            }
+            let mut parts = split_fields(content).into_iter();
+            let Some(pattern) = parts.next() else {
+                continue;
+            };
+            let owners = parts.collect::<Vec<_>>();
+            let mut error = |message: String| codeowners.errors.push(LineError { line, message });
+            for owner in &owners {
+                if !OWNER.is_match(owner) {
+                    error(format!(
+                        "invalid owner `{owner}`, expected @user, @org/team or an email address"
+                    ));
+                }
+            }
+            match build_globs(&pattern) {
+                Ok(globs) => codeowners.rules.push(Rule {
+                    line,
+                    pattern,
+                    owners,
+                    globs,
+                }),
+                Err(message) => error(format!("invalid pattern `{pattern}`: {message}")),
+            }
+        }
+        codeowners
+    }
+
+    /// The rule that decides the owners of `path`: the last one matching.
+    pub fn rule_for(&self, path: &Path) -> Option<&Rule> {
+        self.rules.iter().rev().find(|rule| rule.is_match(path))
+    }
+
+    pub fn owners_of(&self, path: &Path) -> &[String] {
+        self.rule_for(path)
+            .map(|rule| rule.owners.as_slice())
+            .unwrap_or_default()
+    }
+
+    /// Groups `files` by owner, in the order owners are first seen. Files
+    /// without owners are left out.
+    pub fn ownership<P: AsRef<Path>>(&self, files: &[P]) -> IndexMap<String, Vec<PathBuf>> {
+        let mut ownership: IndexMap<String, Vec<PathBuf>> = IndexMap::new();
+        for file in files {
+            for owner in self.owners_of(file.as_ref()) {
+                ownership
+                    .entry(owner.clone())
+                    .or_default()
+                    .push(file.as_ref().to_path_buf());
+            }
+        }
+        ownership
+    }
+}
+
+fn strip_comment(line: &str) -> String {
+    let mut out = String::new();
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => {
+                out.push(c);
+                if let Some(next) = chars.next() {
+                    out.push(next);
+                }
+            }
+            '#' => break,
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+/// Splits a line on whitespace, keeping escaped spaces in the pattern.
+fn split_fields(line: &str) -> Vec<String> {
+    let mut fields = vec![];
+    let mut field = String::new();
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => match chars.next() {
+                Some(next @ (' ' | '#')) => field.push(next),
+                Some(next) => {
+                    field.push('\\');
+                    field.push(next);
+                }
+                None => field.push('\\'),
+            },
+            c if c.is_whitespace() => {
+                if !field.is_empty() {
+                    fields.push(std::mem::take(&mut field));
+                }
+            }
+            c => field.push(c),
+        }
+    }
+    if !field.is_empty() {
+        fields.push(field);
+    }
+    fields
+}
+
+/// Translates a gitignore-style pattern into globs over repository-relative
+/// paths. Patterns are anchored to the root when they contain a `/` other
+/// than a trailing one, and match anything beneath a matching directory
+/// unless their last component has a wildcard (`docs/*` only matches files
+/// directly in `docs`).
+fn build_globs(pattern: &str) -> std::result::Result<GlobSet, String> {
+    if pattern.starts_with('!') {
+        return Err("negation is not supported".to_string());
+    }
+    if pattern.contains('[') {
+        return Err("character ranges are not supported".to_string());
+    }
+    let dir_only = pattern.ends_with('/');
+    let trimmed = pattern.trim_end_matches('/');
+    let anchored = trimmed.contains('/');
+    let trimmed = trimmed.trim_start_matches('/');
+    let base = if trimmed.is_empty() {
+        "**".to_string()
+    } else if anchored || trimmed.starts_with("**") {
+        trimmed.to_string()
+    } else {
+        format!("**/{trimmed}")
+    };
+    let last = base.rsplit('/').next().unwrap_or_default();
+
+    let mut globs = vec![];
+    if !dir_only {
+        globs.push(base.clone());
+    }
+    if dir_only || !last.contains('*') {
+        globs.push(format!("{base}/**"));
+    }
+    let mut builder = GlobSetBuilder::new();
+    for glob in globs {
+        let glob = GlobBuilder::new(&glob)
+            .literal_separator(true)
+            .build()
+            .map_err(|err| err.kind().to_string())?;
+        builder.add(glob);
+    }
+    builder.build().map_err(|err| err.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn owners(codeowners: &CodeOwners, path: &str) -> Vec<String> {
+        codeowners.owners_of(Path::new(path)).to_vec()
+    }
+
+    #[test]
+    fn test_last_match_wins() {
+        let codeowners = CodeOwners::parse(
+            "# comment\n* @global\n*.js @js-owner # trailing comment\n/build/logs/ @doctocat\n",
+        );
+        assert!(codeowners.errors.is_empty());
+        assert_eq!(owners(&codeowners, "README.md"), vec!["@global"]);
+        assert_eq!(owners(&codeowners, "src/app.js"), vec!["@js-owner"]);
+        assert_eq!(owners(&codeowners, "build/logs/a/b.txt"), vec!["@doctocat"]);
+        assert_eq!(owners(&codeowners, "x/build/logs/b.txt"), vec!["@global"]);
+    }
+
+    #[test]
+    fn test_pattern_anchoring() {
+        let codeowners = CodeOwners::parse(
+            "apps/ @apps\ndocs/* docs@example.com\n**/logs @logs\n/scripts/ @org/ops\n",
+        );
+        assert_eq!(owners(&codeowners, "apps/a.rs"), vec!["@apps"]);
+        assert_eq!(owners(&codeowners, "nested/apps/a.rs"), vec!["@apps"]);
+        assert_eq!(
+            owners(&codeowners, "docs/intro.md"),
+            vec!["docs@example.com"]
+        );
+        assert!(owners(&codeowners, "docs/guide/intro.md").is_empty());
+        assert_eq!(owners(&codeowners, "deep/logs/x.log"), vec!["@logs"]);
+        assert_eq!(owners(&codeowners, "scripts/deploy.sh"), vec!["@org/ops"]);
+        assert!(owners(&codeowners, "src/scripts/deploy.sh").is_empty());
+    }
+
+    #[test]
+    fn test_unowned_and_escaped_patterns() {
+        let codeowners = CodeOwners::parse("* @all\n/vendor/\nmy\\ file.txt @spaces\n");
+        assert!(owners(&codeowners, "vendor/lib.c").is_empty());
+        assert_eq!(owners(&codeowners, "my file.txt"), vec!["@spaces"]);
+    }
+
+    #[test]
+    fn test_reports_invalid_lines() {
+        let codeowners = CodeOwners::parse("[Docs]\n!*.md @a\n*.rs bad-owner\n*.c [x]\n");
+        assert_eq!(
+            codeowners.errors,
+            vec![
+                LineError {
+                    line: 2,
+                    message: "invalid pattern `!*.md`: negation is not supported".to_string()
+                },
+                LineError {
+                    line: 3,
+                    message:
+                        "invalid owner `bad-owner`, expected @user, @org/team or an email address"
+                            .to_string()
+                },
+                LineError {
+                    line: 4,
+                    message: "invalid owner `[x]`, expected @user, @org/team or an email address"
+                        .to_string()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_ownership_groups_files_by_owner() {
+        let codeowners = CodeOwners::parse("*.rs @rust @core\n*.md @docs\n");
+        let ownership = codeowners.ownership(&["a.rs", "b.md", "c.txt", "d.rs"]);
+        assert_eq!(
+            ownership.keys().collect::<Vec<_>>(),
+            vec!["@rust", "@core", "@docs"]
+        );
+        assert_eq!(
+            ownership["@rust"],
+            vec![PathBuf::from("a.rs"), PathBuf::from("d.rs")]
+        );
+    }
+}
@@ -0,0 +1,46 @@
+use crate::{Result, config::Config, git, hook_options::HookOptions};
+
+/// Runs the `commit-msg` hook in check mode against the message of every
+/// commit in `from_ref..to_ref`, so commits made with `--no-verify` or
+/// outside of git hooks are still validated. Failures are reported per
+/// commit and the first error is not fatal to the rest of the range.
+pub(crate) async fn check_commit_msgs(config: &Config, from_ref: &str, to_ref: &str) -> Result<()> {
+    let Some(hook) = config.hooks.get("commit-msg") else {
+        warn!("commit message checks requested but no commit-msg hook is configured");
+        return Ok(());
+    };
+    let commits = git::commits_between(from_ref, to_ref)?;
+    debug!(
+        "checking {} commit message(s) in {from_ref}..{to_ref}",
+        commits.len()
+    );
+    let tmp = tempfile::tempdir()?;
+    let mut failed = vec![];
+    for commit in &commits {
+        let path = tmp.path().join(format!("{}.COMMIT_EDITMSG", commit.sha));
+        xx::file::write(&path, &commit.message)?;
+        let mut opts = HookOptions {
+            check: true,
+            files: Some(vec![]),
+            stash: Some("none".to_string()),
+            ..Default::default()
+        };
+        let path = path.to_string_lossy();
+        opts.tctx.insert("commit_msg_file", &path);
+        opts.tctx.insert("hook_args", &path);
+        opts.tctx.insert("commit_sha", &commit.sha);
+        if let Err(err) = hook.run(opts).await {
+            error!("{} {}: {err}", commit.short_sha(), commit.subject());
+            failed.push(commit.short_sha().to_string());
+        }
+    }
+    if failed.is_empty() {
+        Ok(())
+    } else {
+        Err(eyre::eyre!(
+            "commit message checks failed for {} commit(s): {}",
+            failed.len(),
+            failed.join(", ")
+        ))
+    }
+}
@@ -1,10 +1,15 @@
 use indexmap::IndexMap;
 use indexmap::IndexSet;
 use once_cell::sync::OnceCell;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize, de::DeserializeOwned};
 use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
 
-use crate::{Result, cache::CacheManagerBuilder, env, hash, hook::Hook, version};
+use crate::{
+    Result, cache::CacheManagerBuilder, env, hash, hook::Hook, pkl_vendor, policy::Policy,
+    step_template, version,
+};
 use eyre::{WrapErr, bail};
 
 impl Config {
@@ -20,9 +25,16 @@ impl Config {
 
     #[tracing::instrument(level = "info", name = "config.load")]
     fn load() -> Result<Self> {
+        // Policies load first so their min_hk_version is checked before any
+        // project config is read
+        let policies = Policy::get()?;
         let mut config = Self::load_project_config()?;
         config.load_subprojects()?;
         config.apply_hkrc()?;
+        // Policy is applied last so no other layer can override it
+        for policy in policies {
+            policy.apply(&mut config);
+        }
         config.validate()?;
         Ok(config)
     }
@@ -31,22 +43,27 @@ impl Config {
     fn read(path: &Path, apply_env: bool) -> Result<Self> {
         let ext = path.extension().unwrap_or_default().to_str().unwrap();
         let mut config: Config = match ext {
-            "toml" => {
-                let raw = xx::file::read_to_string(path)?;
-                toml::from_str(&raw)?
-            }
-            "yaml" | "yml" => {
+            "toml" | "yaml" | "yml" | "json" => {
                 let raw = xx::file::read_to_string(path)?;
-                serde_yaml::from_str(&raw)?
-            }
-            "json" => {
-                let raw = xx::file::read_to_string(path)?;
-                serde_json::from_str(&raw)?
+                let mut value: serde_json::Value = match ext {
+                    "toml" => toml::from_str(&raw)?,
+                    "json" => serde_json::from_str(&raw)?,
+                    _ => serde_yaml::from_str(&raw)?,
+                };
+                // Templates and matrices only exist in the raw config
+                step_template::expand(&mut value)?;
+                serde_json::from_value(value)?
             }
             "pkl" => {
                 if env::use_pklr_backend() {
                     run_pklr(path)?
                 } else {
+                    if let Some(lockfile) = pkl_vendor::Vendor::find_lockfile(path) {
+                        bail!(
+                            "{} vendors remote Pkl modules, which only the pklr backend can load; unset HK_PKL_BACKEND",
+                            lockfile.display()
+                        );
+                    }
                     run_pkl(&["eval"], path)?
                 }
             }
@@ -207,6 +224,54 @@ impl Config {
         Self::find_project_config(&Self::project_config_search_paths()).is_some()
     }
 
+    /// Path of the project config that would be loaded, if any
+    pub(crate) fn project_config_path() -> Option<PathBuf> {
+        Self::find_project_config(&Self::project_config_search_paths())
+    }
+
+    /// Evaluate a config file directly, bypassing the resolved-config cache.
+    /// Used by `hk doctor` to measure evaluation time.
+    pub(crate) fn evaluate(path: &Path) -> Result<Self> {
+        Self::read(path, false)
+    }
+
+    /// Download every remote module the Pkl config at `path` and its
+    /// subproject configs import into the project's vendor directory.
+    /// Returns the vendor directory and the written lockfile.
+    pub(crate) fn vendor(path: &Path) -> Result<(PathBuf, pkl_vendor::Lockfile)> {
+        if path.extension().is_none_or(|ext| ext != "pkl") {
+            bail!(
+                "{} is not a Pkl config, only Pkl configs import remote modules",
+                path.display()
+            );
+        }
+        let modules = Arc::new(Mutex::new(Default::default()));
+        let evaluate = |path: &Path| -> Result<Config> {
+            let capabilities =
+                pkl_vendor::VendorCapabilities::recording(modules.clone(), &pklr_http_rewrites());
+            let json = block_on_pklr(eval_pklr_with(
+                path,
+                capabilities,
+                build_pklr_http_client()?,
+            ))?
+            .map_err(|e| handle_pklr_eval_error(&e.to_string(), path))?;
+            serde_json::from_value(json)
+                .map_err(|e| handle_pklr_deserialize_error(&e.to_string(), path))
+        };
+        let config = evaluate(path)?;
+        let root = Self::project_root_of(path);
+        if let Some(patterns) = &config.subprojects {
+            for (_, config_path) in Self::discover_subprojects(&root, patterns)? {
+                if config_path.extension().is_some_and(|ext| ext == "pkl") {
+                    evaluate(&config_path)?;
+                }
+            }
+        }
+        let dir = root.join(pkl_vendor::VENDOR_DIR);
+        let lockfile = pkl_vendor::Vendor::write(&dir, &modules.lock().unwrap())?;
+        Ok((dir, lockfile))
+    }
+
     /// Returns true when project config discovery from `start` would find a
     /// config in that directory or one of its ancestors.
     pub fn project_config_exists_from(start: &Path) -> bool {
@@ -250,6 +315,9 @@ impl Config {
             // resolvedImports already contains it.
             let mut files: IndexSet<PathBuf> = import_analysis.local_paths;
             files.insert(path.clone());
+            if let Some(vendor) = pkl_vendor::Vendor::find(&path)? {
+                files.extend(vendor.files());
+            }
             (files.into_iter().collect(), has_untracked_imports)
         } else {
             (vec![path.clone()], false)
@@ -661,6 +729,7 @@ impl Config {
                 stash: sub_hook.stash.clone(),
                 stage: sub_hook.stage,
                 fail_on_fix: sub_hook.fail_on_fix,
+                check_commit_msgs: sub_hook.check_commit_msgs,
                 report: sub_hook.report.clone(),
                 ..Default::default()
             });
@@ -738,27 +807,56 @@ fn get_http_proxy() -> Option<String> {
 
 fn run_pklr<T: DeserializeOwned>(path: &Path) -> Result<T> {
     let client = build_pklr_http_client()?;
-    let http_rewrites = env::HK_PKL_HTTP_REWRITE
-        .as_deref()
-        .map(|s| s.split(',').map(String::from).collect::<Vec<_>>())
-        .unwrap_or_default();
-    let evaluator = pklr::EvaluatorBuilder::new()
-        .http_client(client)
-        .http_rewrites(http_rewrites)
-        .package_cache_dir(env::HK_PKL_CACHE_DIR.clone())
-        .offline(*env::HK_PKL_OFFLINE);
-    let rt = tokio::runtime::Handle::try_current();
-    let json = match rt {
-        Ok(handle) => tokio::task::block_in_place(|| handle.block_on(evaluator.eval_to_json(path))),
-        Err(_) => {
-            let rt = tokio::runtime::Runtime::new()?;
-            rt.block_on(evaluator.eval_to_json(path))
+    let json = match pkl_vendor::Vendor::find(path)? {
+        // Vendored configs resolve every download from the vendor directory,
+        // so neither the package cache nor offline mode applies.
+        Some(vendor) => {
+            let capabilities = pkl_vendor::VendorCapabilities::vendored(Arc::new(vendor));
+            block_on_pklr(eval_pklr_with(path, capabilities, client))
         }
-    }
+        None => {
+            let evaluator = pklr::EvaluatorBuilder::new()
+                .http_client(client)
+                .http_rewrites(pklr_http_rewrites())
+                .package_cache_dir(env::HK_PKL_CACHE_DIR.clone())
+                .offline(*env::HK_PKL_OFFLINE);
+            block_on_pklr(evaluator.eval_to_json(path))
+        }
+    }?
     .map_err(|e| handle_pklr_eval_error(&e.to_string(), path))?;
     serde_json::from_value(json).map_err(|e| handle_pklr_deserialize_error(&e.to_string(), path))
 }
 
+fn pklr_http_rewrites() -> Vec<String> {
+    env::HK_PKL_HTTP_REWRITE
+        .as_deref()
+        .map(|s| s.split(',').map(String::from).collect::<Vec<_>>())
+        .unwrap_or_default()
+}
+
+fn block_on_pklr<F: std::future::Future>(future: F) -> Result<F::Output> {
+    match tokio::runtime::Handle::try_current() {
+        Ok(handle) => Ok(tokio::task::block_in_place(|| handle.block_on(future))),
+        Err(_) => Ok(tokio::runtime::Runtime::new()?.block_on(future)),
+    }
+}
+
+/// Evaluate a Pkl file like `pklr::EvaluatorBuilder::eval_to_json`, but with
+/// custom IO and without the persistent package cache or HTTP rewrites.
+async fn eval_pklr_with(
+    path: &Path,
+    capabilities: pkl_vendor::VendorCapabilities,
+    client: pklr::reqwest::Client,
+) -> pklr::Result<serde_json::Value> {
+    let source =
+        std::fs::read_to_string(path).map_err(|e| pklr::Error::Io(path.to_path_buf(), e))?;
+    let mut evaluator = pklr::Evaluator::with_capabilities(capabilities);
+    evaluator.set_http_client(client);
+    evaluator.set_base_path(path.parent().unwrap_or(Path::new(".")));
+    let value = evaluator.eval_source(&source, path).await?;
+    Ok(evaluator.apply_converters(value).await?.to_json())
+}
+
 /// Build a reqwest::Client with proxy and CA certificate settings
 /// matching proxy and HK_PKL_* environment variables.
 fn build_pklr_http_client() -> Result<pklr::reqwest::Client> {
@@ -960,10 +1058,15 @@ fn failed_pkl_config_error(path: &Path, code: Option<&str>, stderr: &str) -> eyr
     }
 }
 
-#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[derive(Debug, Clone, Default, Deserialize, Serialize, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 #[cfg_attr(debug_assertions, serde(deny_unknown_fields))]
+#[schemars(deny_unknown_fields)]
 pub struct Config {
+    /// JSON Schema for editors, see `hk config schema`; ignored by hk
+    #[serde(rename = "$schema", default, skip_serializing)]
+    #[schemars(with = "Option<String>")]
+    _schema: serde::de::IgnoredAny,
     pub min_hk_version: Option<String>,
     #[serde(default)]
     pub hooks: IndexMap<String, Hook>,
@@ -996,6 +1099,22 @@ impl std::fmt::Display for Config {
 }
 
 impl Config {
+    /// Renders the resolved config as a standalone `hk.json` or `hk.toml`
+    /// that [`Config::read`] loads back without Pkl.
+    pub fn export(&self, format: &str) -> Result<String> {
+        let mut config = self.clone();
+        // Subproject hooks are already merged in, scoped to their directories
+        config.subprojects = None;
+        // Unset fields are left out: TOML has no null, and it keeps the export
+        // readable when diffed
+        let mut value = serde_json::to_value(&config)?;
+        strip_nulls(&mut value);
+        Ok(match format {
+            "toml" => toml::to_string_pretty(&value)?,
+            _ => serde_json::to_string_pretty(&value)? + "\n",
+        })
+    }
+
     pub fn validate(&self) -> Result<()> {
         for (hook_name, hook) in &self.hooks {
             for (step_name, step_or_group) in &hook.steps {
@@ -1019,6 +1138,17 @@ impl Config {
     }
 }
 
+fn strip_nulls(value: &mut serde_json::Value) {
+    match value {
+        serde_json::Value::Object(map) => {
+            map.retain(|_, v| !v.is_null());
+            map.values_mut().for_each(strip_nulls);
+        }
+        serde_json::Value::Array(items) => items.iter_mut().for_each(strip_nulls),
+        _ => {}
+    }
+}
+
 fn validate_step(step: &crate::step::Step, step_name: &str, location: &str) -> Result<()> {
     if step.stage.is_some() && step.fix.is_none() {
         bail!(
@@ -1082,7 +1212,7 @@ fn validate_step(step: &crate::step::Step, step_name: &str, location: &str) -> R
     Ok(())
 }
 
-#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[derive(Debug, Clone, Default, Deserialize, Serialize, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub struct UserConfig {
     #[serde(default)]
@@ -1100,7 +1230,7 @@ pub struct UserConfig {
     pub stage: Option<bool>,
 }
 
-#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[derive(Debug, Clone, Default, Deserialize, Serialize, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub struct UserDefaults {
     pub jobs: Option<u16>,
@@ -1114,7 +1244,7 @@ pub struct UserDefaults {
     pub skip_hooks: Option<StringOrList>,
 }
 
-#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[derive(Debug, Clone, Default, Deserialize, Serialize, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub struct UserHookConfig {
     #[serde(default)]
@@ -1129,7 +1259,7 @@ pub struct UserHookConfig {
     pub steps: IndexMap<String, UserStepConfig>,
 }
 
-#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[derive(Debug, Clone, Default, Deserialize, Serialize, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub struct UserStepConfig {
     #[serde(default)]
@@ -1143,7 +1273,7 @@ pub struct UserStepConfig {
     pub exclude: Option<crate::step::Pattern>,
 }
 
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
 #[serde(untagged)]
 pub enum StringOrList {
     String(String),
@@ -1196,6 +1326,40 @@ mod tests {
         }
     }
 
+    #[test]
+    fn export_round_trips_through_json_and_toml() {
+        let mut config = Config {
+            subprojects: Some(vec!["packages/*".to_string()]),
+            ..Default::default()
+        };
+        config.env.insert("FOO".to_string(), "bar".to_string());
+        let mut hook = hook("check");
+        for name in ["zeta", "alpha"] {
+            let step = Step {
+                _type: Some("step".to_string()),
+                check: Some(format!("echo {name}").parse().unwrap()),
+                ..step(name)
+            };
+            hook.steps
+                .insert(name.to_string(), StepOrGroup::Step(Box::new(step)));
+        }
+        config.hooks.insert("check".to_string(), hook);
+
+        let json = config.export("json").unwrap();
+        assert!(!json.contains("null"));
+        assert!(!json.contains("subprojects"));
+        let from_json: Config = serde_json::from_str(&json).unwrap();
+        assert_eq!(from_json.export("json").unwrap(), json);
+
+        let from_toml: Config = toml::from_str(&config.export("toml").unwrap()).unwrap();
+        assert_eq!(from_toml.export("json").unwrap(), json);
+        // step order is kept
+        assert_eq!(
+            from_toml.hooks["check"].steps.keys().collect::<Vec<_>>(),
+            vec!["zeta", "alpha"]
+        );
+    }
+
     #[test]
     fn merge_subproject_scopes_flat_steps() {
         let mut root = Config::default();
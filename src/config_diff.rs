@@ -0,0 +1,374 @@
+//! Structural diff of the evaluated config between two git revisions.
+//!
+//! Each side is the `hk config export` JSON of a revision, evaluated in a
+//! temporary worktree, so Pkl `amends`, builtins and subprojects are resolved
+//! before comparing. Hooks and steps are matched by name and their fields
+//! compared by value.
+
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+use crate::Result;
+
+#[derive(Debug, Default, Serialize)]
+pub struct ConfigDiff {
+    /// Changed top-level config fields, e.g. `exclude` or `env`
+    pub fields: Vec<FieldChange>,
+    pub hooks: Vec<HookChange>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Change {
+    Added,
+    Removed,
+    Changed,
+}
+
+#[derive(Debug, Serialize)]
+pub struct HookChange {
+    pub name: String,
+    pub change: Change,
+    pub fields: Vec<FieldChange>,
+    pub steps: Vec<StepChange>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct StepChange {
+    /// Step name; steps inside a group are named `<group>/<step>`
+    pub name: String,
+    pub change: Change,
+    pub fields: Vec<FieldChange>,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+pub struct FieldChange {
+    pub field: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub before: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub after: Option<Value>,
+}
+
+impl ConfigDiff {
+    pub fn is_empty(&self) -> bool {
+        self.fields.is_empty() && self.hooks.is_empty()
+    }
+
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+        for field in &self.fields {
+            writeln!(out, "~ {}", field.render()).unwrap();
+        }
+        for hook in &self.hooks {
+            writeln!(out, "{} hook {}", hook.change.symbol(), hook.name).unwrap();
+            if hook.change == Change::Changed {
+                for field in &hook.fields {
+                    writeln!(out, "    {}", field.render()).unwrap();
+                }
+            }
+            for step in &hook.steps {
+                writeln!(out, "  {} {}", step.change.symbol(), step.name).unwrap();
+                if step.change == Change::Changed {
+                    for field in &step.fields {
+                        writeln!(out, "      {}", field.render()).unwrap();
+                    }
+                }
+            }
+        }
+        out
+    }
+}
+
+impl Change {
+    fn symbol(self) -> char {
+        match self {
+            Change::Added => '+',
+            Change::Removed => '-',
+            Change::Changed => '~',
+        }
+    }
+}
+
+impl FieldChange {
+    fn render(&self) -> String {
+        let show = |value: &Option<Value>| match value {
+            Some(value) => value.to_string(),
+            None => "(unset)".to_string(),
+        };
+        format!(
+            "{}: {} -> {}",
+            self.field,
+            show(&self.before),
+            show(&self.after)
+        )
+    }
+}
+
+/// Compares two `hk config export` JSON documents.
+pub fn diff(before: &Value, after: &Value) -> ConfigDiff {
+    let (before, after) = (object(Some(before)), object(Some(after)));
+    let mut diff = ConfigDiff {
+        fields: diff_fields(before, after, &["hooks"]),
+        hooks: vec![],
+    };
+    let before_hooks = object(before.get("hooks"));
+    let after_hooks = object(after.get("hooks"));
+    for name in union_keys(before_hooks, after_hooks) {
+        let (before_hook, after_hook) = (
+            object(before_hooks.get(name)),
+            object(after_hooks.get(name)),
+        );
+        let change = match (
+            before_hooks.contains_key(name),
+            after_hooks.contains_key(name),
+        ) {
+            (false, _) => Change::Added,
+            (_, false) => Change::Removed,
+            _ => Change::Changed,
+        };
+        let fields = diff_fields(before_hook, after_hook, &["steps"]);
+        let mut steps = vec![];
+        diff_steps(
+            "",
+            object(before_hook.get("steps")),
+            object(after_hook.get("steps")),
+            &mut steps,
+        );
+        if change != Change::Changed || !fields.is_empty() || !steps.is_empty() {
+            diff.hooks.push(HookChange {
+                name: name.clone(),
+                change,
+                fields,
+                steps,
+            });
+        }
+    }
+    diff
+}
+
+fn diff_steps(
+    prefix: &str,
+    before: &Map<String, Value>,
+    after: &Map<String, Value>,
+    out: &mut Vec<StepChange>,
+) {
+    for name in union_keys(before, after) {
+        let (before_step, after_step) = (object(before.get(name)), object(after.get(name)));
+        let change = match (before.contains_key(name), after.contains_key(name)) {
+            (false, _) => Change::Added,
+            (_, false) => Change::Removed,
+            _ => Change::Changed,
+        };
+        let groups =
+            [before_step, after_step].map(|step| step.get("_type") == Some(&"group".into()));
+        // Group members are compared as steps of their own, so only the
+        // group's other fields are reported on the group itself
+        let nested = groups[0] || groups[1];
+        let skip: &[&str] = if nested { &["steps"] } else { &[] };
+        let fields = diff_fields(before_step, after_step, skip);
+        if change != Change::Changed || !fields.is_empty() {
+            out.push(StepChange {
+                name: format!("{prefix}{name}"),
+                change,
+                fields,
+            });
+        }
+        if nested {
+            diff_steps(
+                &format!("{prefix}{name}/"),
+                object(before_step.get("steps")),
+                object(after_step.get("steps")),
+                out,
+            );
+        }
+    }
+}
+
+fn diff_fields(
+    before: &Map<String, Value>,
+    after: &Map<String, Value>,
+    skip: &[&str],
+) -> Vec<FieldChange> {
+    union_keys(before, after)
+        .filter(|key| !skip.contains(&key.as_str()))
+        .filter(|key| before.get(*key) != after.get(*key))
+        .map(|key| FieldChange {
+            field: key.clone(),
+            before: before.get(key).cloned(),
+            after: after.get(key).cloned(),
+        })
+        .collect()
+}
+
+fn object(value: Option<&Value>) -> &Map<String, Value> {
+    static EMPTY: std::sync::LazyLock<Map<String, Value>> = std::sync::LazyLock::new(Map::new);
+    value.and_then(Value::as_object).unwrap_or(&EMPTY)
+}
+
+/// Keys of `before` in order, followed by the keys only `after` has.
+fn union_keys<'a>(
+    before: &'a Map<String, Value>,
+    after: &'a Map<String, Value>,
+) -> impl Iterator<Item = &'a String> {
+    before
+        .keys()
+        .chain(after.keys().filter(|key| !before.contains_key(*key)))
+}
+
+/// Evaluates the project config as of `rev`, or of the working tree when
+/// `rev` is `None`, and returns its `hk config export` JSON.
+pub fn export_at(rev: Option<&str>) -> Result<Value> {
+    let root = PathBuf::from(xx::process::cmd("git", ["rev-parse", "--show-toplevel"]).read()?);
+    let prefix = xx::process::cmd("git", ["rev-parse", "--show-prefix"]).read()?;
+    let worktree = match rev {
+        Some(rev) => Some(Worktree::add(rev)?),
+        None => None,
+    };
+    let dir = worktree.as_ref().map_or(root.as_path(), |w| w.path());
+    // Keep the cwd relative to the repository so nested configs resolve the
+    // same way they do for the working tree
+    let dir = Some(dir.join(&prefix))
+        .filter(|d| d.is_dir())
+        .unwrap_or(dir.to_path_buf());
+    let json = xx::process::cmd(
+        std::env::current_exe()?,
+        ["config", "export", "--format", "json"],
+    )
+    .cwd(&dir)
+    .env("HK_CACHE", "0")
+    .read()
+    .map_err(|e| {
+        eyre::eyre!(
+            "failed to evaluate the config at {}: {e}",
+            rev.unwrap_or("the working tree")
+        )
+    })?;
+    Ok(serde_json::from_str(&json)?)
+}
+
+/// A detached `git worktree` checkout, removed when dropped.
+struct Worktree {
+    dir: tempfile::TempDir,
+}
+
+impl Worktree {
+    fn add(rev: &str) -> Result<Self> {
+        let dir = tempfile::Builder::new()
+            .prefix("hk-config-diff-")
+            .tempdir()?;
+        let path = dir.path().to_string_lossy().to_string();
+        // `rev` comes from the command line; keep git from reading it as an option
+        xx::process::cmd(
+            "git",
+            [
+                "worktree",
+                "add",
+                "--detach",
+                "--quiet",
+                "--end-of-options",
+                &path,
+                rev,
+            ],
+        )
+        .read()
+        .map_err(|e| eyre::eyre!("failed to check out {rev}: {e}"))?;
+        Ok(Self { dir })
+    }
+
+    fn path(&self) -> &Path {
+        self.dir.path()
+    }
+}
+
+impl Drop for Worktree {
+    fn drop(&mut self) {
+        let path = self.dir.path().to_string_lossy().to_string();
+        if let Err(err) = xx::process::cmd("git", ["worktree", "remove", "--force", &path]).read() {
+            warn!("failed to remove worktree {path}: {err}");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_diff_steps_and_fields() {
+        let before = json!({
+            "exclude": ["vendor/**"],
+            "hooks": {
+                "pre-commit": {
+                    "fix": true,
+                    "steps": {
+                        "ruff": {"glob": ["**/*.py"], "check": "ruff check"},
+                        "eslint": {"check": "eslint"},
+                        "web": {"_type": "group", "steps": {"prettier": {"check": "prettier"}}},
+                    }
+                },
+                "pre-push": {"steps": {}},
+            }
+        });
+        let after = json!({
+            "hooks": {
+                "pre-commit": {
+                    "fix": true,
+                    "steps": {
+                        "ruff": {"glob": ["src/**/*.py"], "check": "ruff check"},
+                        "web": {"_type": "group", "steps": {"prettier": {"check": "prettier --check"}}},
+                        "taplo": {"check": "taplo"},
+                    }
+                },
+                "pre-push": {"steps": {}},
+            }
+        });
+        let diff = diff(&before, &after);
+        assert_eq!(
+            diff.fields,
+            vec![FieldChange {
+                field: "exclude".into(),
+                before: Some(json!(["vendor/**"])),
+                after: None,
+            }]
+        );
+        assert_eq!(diff.hooks.len(), 1);
+        let steps = &diff.hooks[0].steps;
+        let summary: Vec<_> = steps.iter().map(|s| (s.name.as_str(), s.change)).collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("ruff", Change::Changed),
+                ("eslint", Change::Removed),
+                ("web/prettier", Change::Changed),
+                ("taplo", Change::Added),
+            ]
+        );
+        assert_eq!(steps[0].fields[0].field, "glob");
+        assert_eq!(
+            diff.render(),
+            r#"~ exclude: ["vendor/**"] -> (unset)
+~ hook pre-commit
+  ~ ruff
+      glob: ["**/*.py"] -> ["src/**/*.py"]
+  - eslint
+  ~ web/prettier
+      check: "prettier" -> "prettier --check"
+  + taplo
+"#
+        );
+    }
+
+    #[test]
+    fn test_diff_identical_is_empty() {
+        let config = json!({"hooks": {"check": {"steps": {"a": {"check": "true"}}}}});
+        assert!(diff(&config, &config).is_empty());
+        let added = diff(&json!({}), &config);
+        assert_eq!(added.hooks[0].change, Change::Added);
+        assert_eq!(added.hooks[0].steps[0].change, Change::Added);
+    }
+}
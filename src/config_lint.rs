@@ -0,0 +1,453 @@
+//! Static checks run by `hk validate`.
+//!
+//! [`Config::validate`] rejects configs that cannot run at all whenever they
+//! are loaded. The checks here catch mistakes that otherwise only show up at
+//! runtime, or not at all: `depends` cycles and dangling references, globs
+//! that match nothing, steps that stage the same files in parallel, templates
+//! using unknown variables, unparsable conditions and unused profiles.
+
+use std::collections::HashSet;
+use std::path::PathBuf;
+
+use indexmap::{IndexMap, IndexSet};
+use itertools::Itertools;
+use serde::Serialize;
+
+use crate::config::Config;
+use crate::git::GitStatus;
+use crate::hook::{Hook, StepOrGroup};
+use crate::step::{Pattern, Step};
+use crate::{glob, tera};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Finding {
+    pub severity: Severity,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hook: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub step: Option<String>,
+    pub message: String,
+}
+
+struct Findings(Vec<Finding>);
+
+impl Findings {
+    fn push(&mut self, severity: Severity, hook: &str, step: &str, message: String) {
+        self.0.push(Finding {
+            severity,
+            hook: Some(hook.to_string()),
+            step: Some(step.to_string()),
+            message,
+        });
+    }
+}
+
+/// Runs every check against `config`.
+///
+/// `files` are the tracked files globs are checked against; glob and stage
+/// checks are skipped when it is empty. `profiles` are the profiles enabled
+/// (or disabled with `!`) for this run.
+pub fn lint(config: &Config, files: &[PathBuf], profiles: &IndexSet<String>) -> Vec<Finding> {
+    let mut findings = Findings(vec![]);
+    let tctx = placeholder_context();
+    for (hook_name, hook) in &config.hooks {
+        let groups = run_groups(hook);
+        check_depends(&mut findings, hook_name, &groups);
+        if !files.is_empty() {
+            check_stage_conflicts(&mut findings, hook_name, &groups, files);
+        }
+        for step in groups.iter().flatten() {
+            if !files.is_empty() {
+                check_globs(&mut findings, hook_name, step, files);
+            }
+            check_templates(&mut findings, hook_name, step, &tctx);
+            check_conditions(&mut findings, hook_name, step);
+        }
+    }
+    check_profiles(&mut findings, config, profiles);
+    findings.0
+}
+
+/// Splits a hook's steps the way [`crate::step_group::StepGroup::build_all`]
+/// runs them. Groups run one after another and `depends` only orders steps
+/// within a group.
+fn run_groups(hook: &Hook) -> Vec<Vec<&Step>> {
+    let mut groups: Vec<Vec<&Step>> = vec![];
+    for step_or_group in hook.steps.values() {
+        match step_or_group {
+            StepOrGroup::Group(group) => groups.push(group.steps.values().collect()),
+            StepOrGroup::Step(step) => {
+                if step.exclusive || groups.is_empty() {
+                    groups.push(vec![]);
+                }
+                groups.last_mut().unwrap().push(step);
+                if step.exclusive {
+                    groups.push(vec![]);
+                }
+            }
+        }
+    }
+    groups.retain(|steps| !steps.is_empty());
+    groups
+}
+
+/// `depends` edges between steps of the same group
+fn depends_graph<'a>(group: &[&'a Step]) -> IndexMap<&'a str, Vec<&'a str>> {
+    let names: HashSet<&str> = group.iter().map(|s| s.name.as_str()).collect();
+    group
+        .iter()
+        .map(|step| {
+            let deps = step
+                .depends
+                .iter()
+                .map(String::as_str)
+                .filter(|dep| names.contains(dep))
+                .collect();
+            (step.name.as_str(), deps)
+        })
+        .collect()
+}
+
+fn check_depends(findings: &mut Findings, hook_name: &str, groups: &[Vec<&Step>]) {
+    let group_of: IndexMap<&str, usize> = groups
+        .iter()
+        .enumerate()
+        .flat_map(|(i, steps)| steps.iter().map(move |s| (s.name.as_str(), i)))
+        .collect();
+    for (i, group) in groups.iter().enumerate() {
+        for step in group {
+            for dep in &step.depends {
+                match group_of.get(dep.as_str()) {
+                    None => findings.push(
+                        Severity::Error,
+                        hook_name,
+                        &step.name,
+                        format!("depends on unknown step '{dep}'"),
+                    ),
+                    Some(&j) if j > i => findings.push(
+                        Severity::Error,
+                        hook_name,
+                        &step.name,
+                        format!(
+                            "depends on '{dep}', which runs in a later group; \
+                            the dependency is ignored and '{}' runs first",
+                            step.name
+                        ),
+                    ),
+                    // Earlier groups always finish first
+                    Some(_) => {}
+                }
+            }
+        }
+        for cycle in find_cycles(&depends_graph(group)) {
+            findings.push(
+                Severity::Error,
+                hook_name,
+                &cycle[0],
+                format!("depends cycle: {}", cycle.join(" -> ")),
+            );
+        }
+    }
+}
+
+fn find_cycles(graph: &IndexMap<&str, Vec<&str>>) -> Vec<Vec<String>> {
+    fn visit<'a>(
+        name: &'a str,
+        graph: &IndexMap<&'a str, Vec<&'a str>>,
+        path: &mut Vec<&'a str>,
+        visited: &mut HashSet<&'a str>,
+        cycles: &mut Vec<Vec<String>>,
+    ) {
+        if let Some(start) = path.iter().position(|n| *n == name) {
+            let mut cycle = path[start..].iter().map(|n| n.to_string()).collect_vec();
+            cycle.push(name.to_string());
+            cycles.push(cycle);
+            return;
+        }
+        if !visited.insert(name) {
+            return;
+        }
+        path.push(name);
+        for dep in &graph[name] {
+            visit(dep, graph, path, visited, cycles);
+        }
+        path.pop();
+    }
+
+    let mut cycles = vec![];
+    let mut visited = HashSet::new();
+    for name in graph.keys() {
+        visit(name, graph, &mut vec![], &mut visited, &mut cycles);
+    }
+    cycles
+}
+
+/// Whether `from` (transitively) depends on `to` within the same group
+fn depends_on(graph: &IndexMap<&str, Vec<&str>>, from: &str, to: &str) -> bool {
+    let mut stack = vec![from];
+    let mut seen = HashSet::new();
+    while let Some(name) = stack.pop() {
+        if !seen.insert(name) {
+            continue;
+        }
+        for dep in graph.get(name).into_iter().flatten() {
+            if *dep == to {
+                return true;
+            }
+            stack.push(*dep);
+        }
+    }
+    false
+}
+
+fn check_globs(findings: &mut Findings, hook_name: &str, step: &Step, files: &[PathBuf]) {
+    let selectors = step
+        .match_any
+        .iter()
+        .flatten()
+        .filter_map(|s| s.glob.as_ref());
+    for pattern in step.glob.iter().chain(selectors) {
+        if pattern.is_empty() {
+            continue;
+        }
+        match glob::get_pattern_matches(pattern, files, step.dir.as_deref()) {
+            Ok(matches) if matches.is_empty() => findings.push(
+                Severity::Warning,
+                hook_name,
+                &step.name,
+                format!("glob {} matches no tracked files", describe(pattern)),
+            ),
+            Ok(_) => {}
+            Err(err) => findings.push(
+                Severity::Error,
+                hook_name,
+                &step.name,
+                format!("invalid glob {}: {err}", describe(pattern)),
+            ),
+        }
+    }
+}
+
+fn describe(pattern: &Pattern) -> String {
+    match pattern {
+        Pattern::Globs(globs) => format!("{globs:?}"),
+        Pattern::Regex { pattern, .. } => format!("regex {pattern:?}"),
+    }
+}
+
+/// Tracked files matched by a step's literal `stage` globs
+fn staged_files(step: &Step, files: &[PathBuf]) -> IndexSet<PathBuf> {
+    let globs = step
+        .stage
+        .iter()
+        .flatten()
+        .filter(|g| *g != "<JOB_FILES>" && !g.contains("{{"))
+        .cloned()
+        .collect_vec();
+    if globs.is_empty() {
+        return IndexSet::new();
+    }
+    glob::get_pattern_matches(&Pattern::Globs(globs), files, step.dir.as_deref())
+        .map(|matches| matches.into_iter().collect())
+        .unwrap_or_default()
+}
+
+fn check_stage_conflicts(
+    findings: &mut Findings,
+    hook_name: &str,
+    groups: &[Vec<&Step>],
+    files: &[PathBuf],
+) {
+    for group in groups {
+        let graph = depends_graph(group);
+        let staged = group
+            .iter()
+            .map(|step| (step, staged_files(step, files)))
+            .filter(|(_, staged)| !staged.is_empty())
+            .collect_vec();
+        for [(a, a_files), (b, b_files)] in staged.iter().array_combinations() {
+            if depends_on(&graph, &a.name, &b.name) || depends_on(&graph, &b.name, &a.name) {
+                continue;
+            }
+            if let Some(file) = a_files.intersection(b_files).next() {
+                findings.push(
+                    Severity::Warning,
+                    hook_name,
+                    &b.name,
+                    format!(
+                        "'stage' overlaps with step '{}', which runs in parallel (both stage {}); \
+                        order them with 'depends' or make one 'exclusive'",
+                        a.name,
+                        file.display()
+                    ),
+                );
+            }
+        }
+    }
+}
+
+/// A template context with every variable hk sets for step commands. Hook
+/// specific variables like `commit_msg_file` are included for every hook.
+fn placeholder_context() -> tera::Context {
+    let mut tctx = tera::Context::default();
+    for key in ["files", "workspace_files", "workspace_indicator"] {
+        tctx.insert(key, "file");
+    }
+    for key in ["files_list", "workspace_files_list"] {
+        tctx.insert(key, &["file"]);
+    }
+    tctx.insert("workspace", ".");
+    tctx.insert("globs", "");
+    tctx.insert("git", &GitStatus::default());
+    tctx.insert("codeowners", &IndexMap::<String, Vec<PathBuf>>::new());
+    tctx.insert("owners", &Vec::<String>::new());
+    for key in [
+        "hook",
+        "hook_args",
+        "hook_stdin",
+        "commit_msg_file",
+        "commit_sha",
+        "source",
+        "sha",
+        "prev_head",
+        "new_head",
+    ] {
+        tctx.insert(key, "");
+    }
+    tctx.insert("is_branch_checkout", &false);
+    tctx
+}
+
+fn check_templates(findings: &mut Findings, hook_name: &str, step: &Step, tctx: &tera::Context) {
+    let mut tctx = tctx.clone();
+    tctx.insert("step", &step.name);
+    let commands = [
+        ("check", &step.check),
+        ("check_list_files", &step.check_list_files),
+        ("check_diff", &step.check_diff),
+        ("fix", &step.fix),
+    ];
+    for (field, command) in commands {
+        let Some(command) = command else {
+            continue;
+        };
+        if let Err(err) = command.render(&tctx, step.prefix.as_ref()) {
+            findings.push(
+                Severity::Error,
+                hook_name,
+                &step.name,
+                format!("'{field}' does not render: {err:#}"),
+            );
+        }
+    }
+    if let Some(stdin) = &step.stdin
+        && let Err(err) = tera::render(stdin, &tctx)
+    {
+        findings.push(
+            Severity::Error,
+            hook_name,
+            &step.name,
+            format!("'stdin' does not render: {err:#}"),
+        );
+    }
+}
+
+fn check_conditions(findings: &mut Findings, hook_name: &str, step: &Step) {
+    let conditions = [
+        ("condition", &step.job_condition),
+        ("step_condition", &step.step_condition),
+    ];
+    for (field, condition) in conditions {
+        if let Some(condition) = condition
+            && let Err(err) = crate::step::parse_condition(condition)
+        {
+            findings.push(
+                Severity::Error,
+                hook_name,
+                &step.name,
+                format!("'{field}' does not parse: {err}"),
+            );
+        }
+    }
+}
+
+fn check_profiles(findings: &mut Findings, config: &Config, profiles: &IndexSet<String>) {
+    let known: HashSet<&str> = config
+        .hooks
+        .values()
+        .flat_map(|hook| run_groups(hook).into_iter().flatten())
+        .flat_map(|step| step.profiles.iter().flatten())
+        .map(|p| p.trim_start_matches('!'))
+        .collect();
+    let requested: IndexSet<&str> = profiles.iter().map(|p| p.trim_start_matches('!')).collect();
+    for profile in requested {
+        if !known.contains(profile) {
+            findings.0.push(Finding {
+                severity: Severity::Warning,
+                hook: None,
+                step: None,
+                message: format!("profile '{profile}' is not used by any step"),
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn step(name: &str, depends: &[&str]) -> Step {
+        Step {
+            name: name.to_string(),
+            depends: depends.iter().map(|d| d.to_string()).collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_find_cycles() {
+        let steps = [
+            step("a", &["b"]),
+            step("b", &["c"]),
+            step("c", &["a"]),
+            step("d", &["d"]),
+            step("e", &["a"]),
+        ];
+        let group = steps.iter().collect_vec();
+        let graph = depends_graph(&group);
+        assert_eq!(
+            find_cycles(&graph),
+            vec![vec!["a", "b", "c", "a"], vec!["d", "d"]]
+        );
+        assert!(depends_on(&graph, "e", "c"));
+        assert!(!depends_on(&graph, "a", "e"));
+    }
+
+    #[test]
+    fn test_depends_across_groups() {
+        let mut hook = Hook::default();
+        let mut exclusive = step("b", &[]);
+        exclusive.exclusive = true;
+        for step in [step("a", &["b", "missing"]), exclusive, step("c", &["a"])] {
+            hook.steps
+                .insert(step.name.clone(), StepOrGroup::Step(Box::new(step)));
+        }
+        let mut findings = Findings(vec![]);
+        check_depends(&mut findings, "check", &run_groups(&hook));
+        let messages = findings.0.iter().map(|f| f.message.as_str()).collect_vec();
+        assert_eq!(
+            messages,
+            vec![
+                "depends on 'b', which runs in a later group; the dependency is ignored and 'a' runs first",
+                "depends on unknown step 'missing'",
+            ]
+        );
+    }
+}
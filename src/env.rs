@@ -44,6 +44,9 @@ pub static HK_OUTPUT_FILE: LazyLock<PathBuf> = LazyLock::new(|| {
         .unwrap_or_else(|| HK_STATE_DIR.join("output.log"))
 });
 
+// Org policy file applied on top of /etc/hk/policy.*, see crate::policy
+pub static HK_POLICY: LazyLock<Option<PathBuf>> = LazyLock::new(|| var_path("HK_POLICY"));
+
 // When set, write a JSON timing report to this path after the hook finishes
 pub static HK_TIMING_JSON: LazyLock<Option<PathBuf>> = LazyLock::new(|| var_path("HK_TIMING_JSON"));
 
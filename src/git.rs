@@ -15,6 +15,7 @@ use clx::progress::{ProgressJob, ProgressJobBuilder, ProgressStatus};
 use eyre::{WrapErr, eyre};
 use git2::{Diff, ErrorCode, Repository, StatusOptions, StatusShow};
 use itertools::Itertools;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 #[cfg(unix)]
 use std::os::unix::ffi::OsStringExt;
@@ -143,7 +144,9 @@ enum StashType {
     Git,
 }
 
-#[derive(Debug, Clone, Copy, Eq, PartialEq, Deserialize, Serialize, strum::EnumString)]
+#[derive(
+    Debug, Clone, Copy, Eq, PartialEq, Deserialize, Serialize, JsonSchema, strum::EnumString,
+)]
 #[serde(rename_all = "kebab-case")]
 #[strum(serialize_all = "kebab-case")]
 pub enum StashMethod {
@@ -1785,6 +1788,56 @@ fn git_merge_base(from_ref: &str, to_ref: &str) -> Result<Option<String>> {
     }
 }
 
+/// A commit reachable from `to_ref` but not `from_ref`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct RangeCommit {
+    pub sha: String,
+    pub message: String,
+}
+
+impl RangeCommit {
+    pub fn short_sha(&self) -> &str {
+        &self.sha[..self.sha.len().min(12)]
+    }
+
+    pub fn subject(&self) -> &str {
+        self.message.lines().next().unwrap_or_default()
+    }
+}
+
+/// Lists the commits in `from_ref..to_ref`, oldest first. A deleted ref
+/// (all-zeros `to_ref`) has no commits.
+pub(crate) fn commits_between(from_ref: &str, to_ref: &str) -> Result<Vec<RangeCommit>> {
+    if is_zero_sha(to_ref) {
+        return Ok(vec![]);
+    }
+    let range = format!("{from_ref}..{to_ref}");
+    let output = git_read_raw([
+        "log",
+        "--reverse",
+        "-z",
+        "--format=%H%n%B",
+        "--end-of-options",
+        &range,
+    ])
+    .wrap_err_with(|| format!("Failed to list commits in {range}"))?;
+    Ok(parse_range_commits(&output))
+}
+
+fn parse_range_commits(output: &str) -> Vec<RangeCommit> {
+    output
+        .split('\0')
+        .filter_map(|entry| {
+            let entry = entry.trim_start_matches('\n');
+            let (sha, message) = entry.split_once('\n').unwrap_or((entry, ""));
+            (!sha.is_empty()).then(|| RangeCommit {
+                sha: sha.to_string(),
+                message: message.to_string(),
+            })
+        })
+        .collect()
+}
+
 #[derive(Debug, Clone, Serialize, Default)]
 pub(crate) struct GitStatus {
     pub unstaged_files: BTreeSet<PathBuf>,
@@ -1802,3 +1855,19 @@ pub(crate) struct GitStatus {
     pub unstaged_deleted_files: BTreeSet<PathBuf>,
     pub unstaged_renamed_files: BTreeSet<PathBuf>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_range_commits() {
+        let output = "aaa\nfeat: one\n\nbody\n\n\0\nbbb\nfix: two\n\n\0";
+        let commits = parse_range_commits(output);
+        assert_eq!(commits.len(), 2);
+        assert_eq!(commits[0].sha, "aaa");
+        assert_eq!(commits[0].message, "feat: one\n\nbody\n\n");
+        assert_eq!(commits[1].subject(), "fix: two");
+        assert!(parse_range_commits("").is_empty());
+    }
+}
@@ -2,15 +2,18 @@ use clx::progress::{ProgressJob, ProgressJobBuilder, ProgressOutput, ProgressSta
 use eyre::WrapErr;
 use indexmap::IndexMap;
 use itertools::Itertools;
+use regex::Regex;
+use schemars::{JsonSchema, Schema, SchemaGenerator, json_schema};
 use serde::{Deserialize, Deserializer, Serialize, Serializer, de::Error, ser};
 use serde_with::{DisplayFromStr, PickFirst, serde_as};
 use std::{
+    borrow::Cow,
     collections::{BTreeSet, HashMap, HashSet},
     ffi::OsString,
     fmt,
     path::{Path, PathBuf},
     sync::{
-        Arc, Mutex as StdMutex,
+        Arc, LazyLock, Mutex as StdMutex,
         atomic::{AtomicBool, Ordering},
     },
     time::Instant,
@@ -22,12 +25,15 @@ use tokio::{
 use tokio_util::sync::CancellationToken;
 
 use crate::{
-    Result, env,
+    Result,
+    codeowners::CodeOwners,
+    env,
     file_rw_locks::FileRwLocks,
     git::{Git, GitStatus, StashMethod},
     glob,
     hook_options::HookOptions,
     plan::{ParallelGroup, Plan, PlannedStep, Reason, ReasonKind, StepStatus},
+    policy,
     settings::Settings,
     step::{CommandEffect, EXPR_CTX, OutputSummary, RunType, Script, Step, eval_condition},
     step_context::StepContext,
@@ -95,10 +101,11 @@ impl SkipReason {
     }
 }
 
-#[serde_as]
-#[derive(Debug, Clone, Default, Deserialize, Serialize, Eq, PartialEq)]
+#[serde_as(schemars = false)]
+#[derive(Debug, Clone, Default, Deserialize, Serialize, Eq, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 #[cfg_attr(debug_assertions, serde(deny_unknown_fields))]
+#[schemars(deny_unknown_fields)]
 pub struct Hook {
     #[serde(default)]
     pub name: String,
@@ -109,13 +116,18 @@ pub struct Hook {
     pub stage: Option<bool>,
     #[serde(default)]
     pub fail_on_fix: bool,
+    /// Validate every commit message in the checked range with the
+    /// `commit-msg` hook
+    #[serde(default)]
+    pub check_commit_msgs: bool,
     #[serde(default)]
     pub env: IndexMap<String, String>,
     #[serde_as(as = "Option<PickFirst<(_, DisplayFromStr)>>")]
+    #[schemars(with = "Option<crate::schema::OrString<Script>>")]
     pub report: Option<Script>,
 }
 
-#[derive(Debug, Clone, Deserialize, Serialize, Eq, PartialEq)]
+#[derive(Debug, Clone, Deserialize, Serialize, Eq, PartialEq, JsonSchema)]
 #[serde(untagged)]
 pub enum StashSetting {
     Method(StashMethod),
@@ -159,12 +171,34 @@ impl Serialize for StepOrGroup {
     }
 }
 
+impl JsonSchema for StepOrGroup {
+    fn schema_name() -> Cow<'static, str> {
+        "StepOrGroup".into()
+    }
+
+    fn json_schema(generator: &mut SchemaGenerator) -> Schema {
+        // Mirrors `Deserialize`: objects with `steps` are groups
+        let mut group = generator.subschema_for::<StepGroup>();
+        group.insert("required".into(), serde_json::json!(["steps"]));
+        json_schema!({
+            "anyOf": [
+                generator.subschema_for::<Step>(),
+                group,
+                crate::schema::builtin_step_schema(),
+            ]
+        })
+    }
+}
+
 impl<'de> Deserialize<'de> for StepOrGroup {
     fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
     where
         D: Deserializer<'de>,
     {
-        let value = serde_json::Value::deserialize(deserializer)?;
+        let mut value = serde_json::Value::deserialize(deserializer)?;
+        if let Some(object) = value.as_object_mut() {
+            crate::builtins::expand_builtin(object).map_err(D::Error::custom)?;
+        }
         let object = value.as_object();
         let has_steps = object.is_some_and(|obj| obj.contains_key("steps"));
         let is_group = match object.and_then(|obj| obj.get("_type")) {
@@ -299,6 +333,56 @@ mod tests {
         };
         assert!(group.steps.contains_key("echo"));
     }
+
+    #[test]
+    fn depends_order_runs_dependencies_first_and_ignores_unselected() {
+        let step = |name: &str, depends: &[&str]| {
+            (
+                name.to_string(),
+                Step {
+                    name: name.to_string(),
+                    depends: depends.iter().map(|d| d.to_string()).collect(),
+                    ..Default::default()
+                },
+            )
+        };
+        let steps = IndexMap::from([
+            step("lint", &["format"]),
+            step("format", &["missing"]),
+            step("sort", &[]),
+        ]);
+
+        let names = depends_order(steps)
+            .into_iter()
+            .map(|step| step.name)
+            .collect_vec();
+
+        assert_eq!(names, ["format", "lint", "sort"]);
+    }
+
+    #[test]
+    fn ownership_is_only_used_by_variable_references() {
+        let uses = |step: serde_json::Value| {
+            let step: Step = serde_json::from_value(step).unwrap();
+            step_uses_ownership(&step)
+        };
+        assert!(uses(json!({"condition": "'@acme/docs' in owners"})));
+        assert!(uses(json!({"step_condition": "len(codeowners) > 1"})));
+        assert!(uses(
+            json!({"check": "notify {{ owners | join(sep=',') }}"})
+        ));
+        assert!(uses(
+            json!({"fix": {"linux": "{% for o in owners %}{{o}}{% endfor %}"}})
+        ));
+        assert!(uses(
+            json!({"env": {"OWNERS": "{{ codeowners | json_encode() }}"}})
+        ));
+
+        assert!(!uses(json!({"check": "check-owners {{ files }}"})));
+        assert!(!uses(
+            json!({"glob": ["owners/**"], "env": {"OWNERS": "none"}})
+        ));
+    }
 }
 
 type CommandEffectsByStep = IndexMap<String, Vec<(String, Option<CommandEffect>)>>;
@@ -660,7 +744,7 @@ impl Hook {
             validate_safe_commands(&groups, &files, run_type, &skip_steps)?;
         }
 
-        let expr_ctx = build_expr_ctx(&git_status);
+        let expr_ctx = build_expr_ctx(&git_status, &file_ownership(&groups, &files));
 
         let mut plan = Plan::new(self.name.clone(), run_type.as_str().to_string())
             .with_profiles(settings.enabled_profiles().iter().cloned().collect());
@@ -1085,6 +1169,116 @@ impl Hook {
         Ok(())
     }
 
+    /// Fix or check a buffer read from stdin as if it were stored at `path`.
+    /// Matching steps run in hook order (respecting `depends`) on a temporary
+    /// copy so nothing in the working tree changes. Fixers each run against
+    /// the previous step's output and the result is written to stdout;
+    /// checks report like a regular run.
+    pub async fn run_stdin(&self, opts: HookOptions, path: &Path) -> Result<()> {
+        use std::io::{Read, Write};
+
+        let run_type = self.run_type(&opts);
+        let cwd = std::env::current_dir()?;
+        let path = path.strip_prefix(&cwd).unwrap_or(path);
+        if path.file_name().is_none() {
+            eyre::bail!("--stdin-filepath must name a file: {}", path.display());
+        }
+        let mut contents = Vec::new();
+        std::io::stdin().read_to_end(&mut contents)?;
+
+        let settings = Settings::get();
+        let skip_steps = build_skip_steps(&settings, &opts);
+        let expr_ctx = build_expr_ctx(&GitStatus::default(), &IndexMap::new());
+        let mut steps = Vec::new();
+        for group in self.get_step_groups(&opts) {
+            let mut selected = IndexMap::new();
+            for (name, step) in group.steps {
+                if let Some(reason) = skip_steps
+                    .get(&name)
+                    .cloned()
+                    .or_else(|| step.profile_skip_reason())
+                {
+                    debug!("{name}: skipping for stdin: {}", reason.message());
+                    continue;
+                }
+                if !step.selects_stdin_path(path, &contents, run_type)? {
+                    continue;
+                }
+                let mut condition_false = false;
+                for condition in [&step.step_condition, &step.job_condition]
+                    .into_iter()
+                    .flatten()
+                {
+                    if eval_condition(condition, &expr_ctx)? == expr::Value::Bool(false) {
+                        condition_false = true;
+                    }
+                }
+                if !condition_false {
+                    selected.insert(name, step);
+                }
+            }
+            steps.extend(depends_order(selected));
+        }
+
+        let (_tmp, copy) = crate::step::stdin_copy(path, &contents)?;
+        if run_type == RunType::Check {
+            return self.check_stdin(&opts, path, &copy, &steps).await;
+        }
+        for step in &steps {
+            step.fix_stdin_copy(&opts.tctx, path, &copy).await?;
+        }
+        let mut stdout = std::io::stdout().lock();
+        stdout.write_all(&xx::file::read(&copy)?)?;
+        stdout.flush()?;
+        Ok(())
+    }
+
+    async fn check_stdin(
+        &self,
+        opts: &HookOptions,
+        path: &Path,
+        copy: &Path,
+        steps: &[Step],
+    ) -> Result<()> {
+        let output_format = Settings::cli_output_format();
+        let started_at = chrono::Utc::now().to_rfc3339();
+        let run_started = std::time::Instant::now();
+        let mut runs = vec![];
+        for step in steps {
+            let step_started = std::time::Instant::now();
+            let (passed, output) = step.check_stdin_copy(&opts.tctx, path, copy).await?;
+            if !passed && output_format == crate::structured_output::OutputFormat::Human {
+                eprintln!("{step}: {}", output.trim_end());
+            }
+            runs.push(crate::structured_output::StdinStepRun {
+                step,
+                passed,
+                duration_ms: step_started.elapsed().as_millis(),
+                output,
+            });
+        }
+        let failed = runs
+            .iter()
+            .filter(|run| !run.passed)
+            .map(|run| run.step.name.as_str())
+            .collect_vec();
+        let failure = (!failed.is_empty()).then(|| format!("{} failed", failed.join(", ")));
+        if output_format != crate::structured_output::OutputFormat::Human {
+            crate::structured_output::emit_stdin_run(
+                output_format,
+                &self.name,
+                started_at,
+                run_started.elapsed().as_millis(),
+                runs,
+                failure.clone(),
+            )?;
+        }
+        match failure {
+            Some(failure) => Err(eyre::eyre!(failure)),
+            None => Ok(()),
+        }
+    }
+
     #[tracing::instrument(level = "info", name = "hook.run", skip(self, opts), fields(hook = %self.name))]
     pub async fn run(&self, opts: HookOptions) -> Result<()> {
         tracing::info!("running hook");
@@ -1108,7 +1302,26 @@ impl Hook {
             .or(self.stage)
             .unwrap_or(true);
 
-        if settings.skip_hooks.contains(&self.name) {
+        // A skipped hook still runs the steps an org policy requires
+        let policy_steps = self
+            .steps
+            .iter()
+            .flat_map(|(name, step_or_group)| match step_or_group {
+                StepOrGroup::Step(_) => vec![name],
+                StepOrGroup::Group(group) => group.steps.keys().collect(),
+            })
+            .filter_map(|name| policy::required_by(name).map(|policy| (name, policy)))
+            .collect::<IndexMap<_, _>>();
+        let skip_hook = settings.skip_hooks.contains(&self.name);
+        if skip_hook && !policy_steps.is_empty() {
+            for (step, policy) in &policy_steps {
+                warn!(
+                    "{}: not skipping {step}, required by policy {}",
+                    &self.name,
+                    xx::file::display_path(&policy.path)
+                );
+            }
+        } else if skip_hook {
             warn!("{}: skipping hook due to HK_SKIP_HOOK", &self.name);
             crate::structured_output::emit_noop_run(
                 output_format,
@@ -1212,7 +1425,17 @@ impl Hook {
             }
         };
 
-        let skip_steps = build_skip_steps(&settings, &opts);
+        let mut skip_steps = build_skip_steps(&settings, &opts);
+        if skip_hook {
+            for name in groups.iter().flat_map(|group| group.steps.keys()) {
+                if !policy_steps.contains_key(name) {
+                    skip_steps.insert(
+                        name.clone(),
+                        SkipReason::DisabledByEnv("HK_SKIP_HOOK".to_string()),
+                    );
+                }
+            }
+        }
         if files.is_empty()
             && let Some(noop_steps) = early_exit_steps(&groups, &files, run_type, &skip_steps)
         {
@@ -1256,7 +1479,10 @@ impl Hook {
         // Insert a serializable view under "git"
         tctx.insert("git", &git_status_for_ctx);
         tctx.insert("hook", &self.name);
-        let expr_ctx = build_expr_ctx(&git_status_for_ctx);
+        let ownership = file_ownership(&groups, &files);
+        tctx.insert("codeowners", &ownership);
+        tctx.insert("owners", &ownership.keys().collect::<Vec<_>>());
+        let expr_ctx = build_expr_ctx(&git_status_for_ctx, &ownership);
         let hook_ctx = Arc::new(HookContext::new(
             files,
             repo.clone(),
@@ -1852,6 +2078,22 @@ fn all_files_in_dir(dir: &Path) -> Result<Vec<PathBuf>> {
     Ok(files)
 }
 
+/// Order steps so each runs after the selected steps it `depends` on,
+/// otherwise keeping configuration order. Dependencies on steps that were not
+/// selected are ignored; a cycle falls back to configuration order.
+fn depends_order(mut steps: IndexMap<String, Step>) -> Vec<Step> {
+    let mut ordered = Vec::with_capacity(steps.len());
+    while !steps.is_empty() {
+        let next = steps
+            .iter()
+            .position(|(_, step)| step.depends.iter().all(|dep| !steps.contains_key(dep)))
+            .unwrap_or(0);
+        let (_, step) = steps.shift_remove_index(next).unwrap();
+        ordered.push(step);
+    }
+    ordered
+}
+
 fn build_skip_steps(settings: &Settings, opts: &HookOptions) -> IndexMap<String, SkipReason> {
     let mut m: IndexMap<String, SkipReason> = IndexMap::new();
     for s in env::HK_SKIP_STEPS.iter() {
@@ -1871,6 +2113,16 @@ fn build_skip_steps(settings: &Settings, opts: &HookOptions) -> IndexMap<String,
             SkipReason::DisabledByCli(format!("--skip-step {}", s)),
         );
     }
+    m.retain(|step, _| match policy::required_by(step) {
+        Some(policy) => {
+            warn!(
+                "{step}: not skipping, required by policy {}",
+                xx::file::display_path(&policy.path)
+            );
+            false
+        }
+        None => true,
+    });
     m
 }
 
@@ -1910,14 +2162,80 @@ fn validate_safe_commands(
     Ok(())
 }
 
-fn build_expr_ctx(git_status: &GitStatus) -> expr::Context {
+fn build_expr_ctx(
+    git_status: &GitStatus,
+    ownership: &IndexMap<String, Vec<PathBuf>>,
+) -> expr::Context {
     let mut expr_ctx = EXPR_CTX.clone();
     if let Ok(val) = expr::to_value(git_status) {
         expr_ctx.insert("git", val);
     }
+    if let Ok(val) = expr::to_value(ownership) {
+        expr_ctx.insert("codeowners", val);
+    }
+    if let Ok(val) = expr::to_value(&ownership.keys().collect::<Vec<_>>()) {
+        expr_ctx.insert("owners", val);
+    }
     expr_ctx
 }
 
+/// Groups the hook's files by their owners in CODEOWNERS, if there is one.
+/// Empty when no step refers to `codeowners` or `owners`, so hooks that
+/// don't filter by ownership never read CODEOWNERS.
+fn file_ownership<'a>(
+    groups: &[StepGroup],
+    files: impl IntoIterator<Item = &'a PathBuf>,
+) -> IndexMap<String, Vec<PathBuf>> {
+    if !uses_ownership(groups) {
+        return IndexMap::new();
+    }
+    let files = files.into_iter().collect::<Vec<_>>();
+    match CodeOwners::discover(&crate::git_util::find_work_tree_root()) {
+        Ok(Some(codeowners)) => codeowners.ownership(&files),
+        Ok(None) => IndexMap::new(),
+        Err(err) => {
+            warn!("failed to read CODEOWNERS: {err}");
+            IndexMap::new()
+        }
+    }
+}
+
+static OWNERSHIP_VAR: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\b(code)?owners\b").unwrap());
+static TEMPLATE_TAG: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?s)\{[{%].*?[%}]\}").unwrap());
+
+/// Whether any step can use the `codeowners` or `owners` variables: in a
+/// condition, or inside a template tag of a command, `stdin` or `env` value.
+fn uses_ownership(groups: &[StepGroup]) -> bool {
+    groups
+        .iter()
+        .flat_map(|group| group.steps.values())
+        .any(step_uses_ownership)
+}
+
+fn step_uses_ownership(step: &Step) -> bool {
+    let mut conditions = [&step.job_condition, &step.step_condition]
+        .into_iter()
+        .flatten();
+    let mut templates = [
+        &step.check,
+        &step.check_diff,
+        &step.check_list_files,
+        &step.fix,
+    ]
+    .into_iter()
+    .flatten()
+    .flat_map(|command| command.templates())
+    .chain(step.stdin.as_deref())
+    .chain(step.env.values().map(String::as_str));
+    conditions.any(|condition| OWNERSHIP_VAR.is_match(condition))
+        || templates.any(|template| {
+            TEMPLATE_TAG
+                .find_iter(template)
+                .any(|tag| OWNERSHIP_VAR.is_match(tag.as_str()))
+        })
+}
+
 fn early_exit_steps(
     groups: &[StepGroup],
     files: &BTreeSet<PathBuf>,
@@ -1,7 +1,10 @@
-use crate::{Result, config::Config, git::Git, settings::Settings, tera::Context};
+use crate::{
+    Result, commit_range::check_commit_msgs, config::Config, git::Git, settings::Settings,
+    tera::Context,
+};
 use std::path::PathBuf;
 
-#[derive(clap::Args)]
+#[derive(Default, clap::Args)]
 pub(crate) struct HookOptions {
     /// Run on specific files
     #[clap(conflicts_with_all = &["all", "fix", "check"], value_hint = clap::ValueHint::FilePath)]
@@ -34,6 +37,10 @@ pub(crate) struct HookOptions {
     /// Show detailed reasons for inclusion/exclusion. Pass a step name to focus on one step, or omit the value to show reasons for all steps. Implies --plan.
     #[clap(short = 'W', long, value_name = "STEP", num_args = 0..=1, default_missing_value = "")]
     pub why: Option<String>,
+    /// Also validate every commit message in --from-ref..--to-ref with the
+    /// commit-msg hook
+    #[clap(long)]
+    pub check_commit_msgs: bool,
     /// Abort on first failure
     #[clap(long, overrides_with = "no_fail_fast")]
     pub fail_fast: bool,
@@ -88,6 +95,16 @@ pub(crate) struct HookOptions {
     /// Display statistics about files matching each step
     #[clap(long)]
     pub stats: bool,
+    /// Read file contents from stdin and fix or check them as if they were
+    /// stored at this path, without touching the working tree. Fixed contents
+    /// are written to stdout
+    #[clap(
+        long,
+        value_name = "PATH",
+        value_hint = clap::ValueHint::FilePath,
+        conflicts_with_all = &["files", "all", "check", "files0_from", "from_ref", "glob", "plan", "pr", "staged", "stats", "to_ref", "unstaged", "why"]
+    )]
+    pub stdin_filepath: Option<PathBuf>,
     /// End reference for checking files (requires --from-ref)
     #[clap(long)]
     pub to_ref: Option<String>,
@@ -214,10 +231,27 @@ impl HookOptions {
         }
         match config.hooks.get(name) {
             Some(hook) => {
-                if self.stats {
+                if let Some(path) = self.stdin_filepath.take() {
+                    hook.run_stdin(self, &path).await?;
+                } else if self.stats {
                     hook.stats(self, name).await?;
                 } else if self.plan || self.why.is_some() {
                     hook.plan(self).await?;
+                } else if self.check_commit_msgs || hook.check_commit_msgs {
+                    let range = self.from_ref.clone().zip(self.to_ref.clone());
+                    let result = hook.run(self).await;
+                    match range {
+                        Some((from_ref, to_ref)) => {
+                            let msgs = check_commit_msgs(&config, &from_ref, &to_ref).await;
+                            result.and(msgs)?;
+                        }
+                        None => {
+                            warn!(
+                                "{name}: commit message checks need --from-ref and --to-ref or --pr, skipping"
+                            );
+                            result?;
+                        }
+                    }
                 } else {
                     hook.run(self).await?;
                 }
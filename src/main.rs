@@ -10,7 +10,11 @@ pub use eyre::Result;
 mod builtins;
 mod cache;
 mod cli;
+mod codeowners;
+mod commit_range;
 mod config;
+mod config_diff;
+mod config_lint;
 mod diagnostics;
 mod diff;
 mod env;
@@ -26,7 +30,10 @@ mod hook_options;
 mod logger;
 mod merge;
 mod mise_env;
+mod pkl_vendor;
 mod plan;
+mod policy;
+mod schema;
 mod settings;
 mod step;
 mod step_context;
@@ -34,6 +41,7 @@ mod step_depends;
 mod step_group;
 mod step_job;
 mod step_locks;
+mod step_template;
 mod step_test;
 mod structured_output;
 mod tera;
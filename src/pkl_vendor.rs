@@ -0,0 +1,328 @@
+//! Repo-local copies of the remote modules a Pkl config imports.
+//!
+//! `hk config vendor` evaluates the config while recording every http(s)
+//! module and package the pklr evaluator downloads, and writes them to
+//! `.hk/pkl/` together with a `lock.json` of sha256 checksums. Once that
+//! lockfile exists, config loading serves those downloads from disk instead
+//! of the network and refuses files whose checksum no longer matches.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use itertools::Itertools;
+use pklr::capabilities::BoxFuture;
+use pklr::{EvalCapabilities, NativeCapabilities};
+use serde::{Deserialize, Serialize};
+
+use crate::Result;
+
+/// Where vendored modules are written, relative to the project root.
+pub const VENDOR_DIR: &str = ".hk/pkl";
+const LOCKFILE: &str = "lock.json";
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Lockfile {
+    /// Vendored modules keyed by the URL they were downloaded from.
+    pub modules: BTreeMap<String, LockedModule>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LockedModule {
+    /// Path of the vendored copy, relative to the vendor directory.
+    pub path: String,
+    pub sha256: String,
+}
+
+#[derive(Debug)]
+pub struct Vendor {
+    dir: PathBuf,
+    lockfile: Lockfile,
+}
+
+impl Vendor {
+    /// The lockfile governing a config file: `.hk/pkl/lock.json` in the
+    /// config's directory or the nearest ancestor that has one.
+    pub fn find_lockfile(config_path: &Path) -> Option<PathBuf> {
+        let dir = config_path.parent().filter(|p| !p.as_os_str().is_empty());
+        let dir = std::path::absolute(dir.unwrap_or(Path::new("."))).ok()?;
+        dir.ancestors()
+            .map(|dir| dir.join(VENDOR_DIR).join(LOCKFILE))
+            .find(|path| path.is_file())
+    }
+
+    pub fn find(config_path: &Path) -> Result<Option<Self>> {
+        match Self::find_lockfile(config_path) {
+            Some(path) => Self::from_lockfile(&path).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    fn from_lockfile(path: &Path) -> Result<Self> {
+        let raw = xx::file::read_to_string(path)?;
+        let lockfile = serde_json::from_str(&raw)
+            .map_err(|e| eyre::eyre!("invalid Pkl vendor lockfile {}: {e}", path.display()))?;
+        Ok(Self {
+            dir: path.parent().unwrap().to_path_buf(),
+            lockfile,
+        })
+    }
+
+    /// The lockfile and the vendored copies, for config cache invalidation.
+    pub fn files(&self) -> Vec<PathBuf> {
+        let modules = self.lockfile.modules.values();
+        std::iter::once(self.dir.join(LOCKFILE))
+            .chain(modules.map(|module| self.dir.join(&module.path)))
+            .collect()
+    }
+
+    /// Replaces the contents of `dir` with `modules` and a lockfile listing them.
+    pub fn write(dir: &Path, modules: &BTreeMap<String, Vec<u8>>) -> Result<Lockfile> {
+        if dir.exists() {
+            xx::file::remove_dir_all(dir)?;
+        }
+        let mut lockfile = Lockfile::default();
+        for (url, bytes) in modules {
+            let path = vendored_path(url);
+            xx::file::write(dir.join(&path), bytes)?;
+            let module = LockedModule {
+                path,
+                sha256: xx::hash::sha256(bytes),
+            };
+            lockfile.modules.insert(url.clone(), module);
+        }
+        let json = serde_json::to_string_pretty(&lockfile)?;
+        xx::file::write(dir.join(LOCKFILE), json + "\n")?;
+        Ok(lockfile)
+    }
+
+    /// Reads the vendored copy of `url`, verifying it against the lockfile.
+    fn read(&self, url: &str) -> std::result::Result<Vec<u8>, String> {
+        let lockfile = self.dir.join(LOCKFILE);
+        let Some(module) = self.lockfile.modules.get(url) else {
+            return Err(format!(
+                "{url} is not vendored in {}, run `hk config vendor` to update it",
+                lockfile.display()
+            ));
+        };
+        let path = self.dir.join(&module.path);
+        let bytes = std::fs::read(&path)
+            .map_err(|e| format!("failed to read vendored {url} from {}: {e}", path.display()))?;
+        let sha256 = xx::hash::sha256(&bytes);
+        if sha256 != module.sha256 {
+            return Err(format!(
+                "checksum mismatch for vendored {url}: {} has sha256 {sha256} but {} expects {}",
+                path.display(),
+                lockfile.display(),
+                module.sha256
+            ));
+        }
+        Ok(bytes)
+    }
+}
+
+/// A filesystem-safe path for a URL's vendored copy that still reads like the
+/// URL, e.g. `github.com/jdx/hk/releases/download/v1.0.0/hk@1.0.0-1a2b3c4d.zip`.
+/// Sanitizing is lossy (`a b` and `a_b` both become `a_b`), so the file name
+/// carries a short hash of the full URL to keep distinct URLs apart.
+fn vendored_path(url: &str) -> String {
+    let hash = &xx::hash::sha256(url.as_bytes())[..8];
+    let rest = url.split_once("://").map_or(url, |(_, rest)| rest);
+    let segments = rest
+        .split('/')
+        .filter(|s| !s.is_empty() && *s != "." && *s != "..")
+        .map(|s| {
+            s.chars()
+                .map(|c| match c {
+                    'a'..='z' | 'A'..='Z' | '0'..='9' | '.' | '-' | '_' | '@' | '+' => c,
+                    _ => '_',
+                })
+                .collect::<String>()
+        })
+        .collect::<Vec<_>>();
+    let Some((file, dirs)) = segments.split_last() else {
+        return xx::hash::sha256(url.as_bytes());
+    };
+    let file = match file.rsplit_once('.') {
+        Some((stem, ext)) if !stem.is_empty() => format!("{stem}-{hash}.{ext}"),
+        _ => format!("{file}-{hash}"),
+    };
+    dirs.iter().chain([&file]).join("/")
+}
+
+/// pklr IO that records downloads for `hk config vendor` or serves them from
+/// the vendor directory. Everything else is delegated to the native IO.
+pub struct VendorCapabilities {
+    native: NativeCapabilities,
+    mode: Mode,
+}
+
+enum Mode {
+    Record {
+        modules: Arc<Mutex<BTreeMap<String, Vec<u8>>>>,
+        http_rewrites: Vec<(String, String)>,
+    },
+    Replay(Arc<Vendor>),
+}
+
+impl VendorCapabilities {
+    /// Downloads as usual, keeping a copy of every response in `modules`.
+    ///
+    /// `http_rewrites` are applied here rather than by the evaluator so the
+    /// lockfile records the URLs the config imports, and loading the vendored
+    /// modules doesn't depend on the rewrites in effect when vendoring.
+    pub fn recording(
+        modules: Arc<Mutex<BTreeMap<String, Vec<u8>>>>,
+        http_rewrites: &[String],
+    ) -> Self {
+        let http_rewrites = http_rewrites
+            .iter()
+            .filter_map(|rule| rule.split_once('='))
+            .filter(|(src, _)| !src.is_empty())
+            .map(|(src, tgt)| (src.to_string(), tgt.to_string()))
+            .collect();
+        Self {
+            native: NativeCapabilities::new(),
+            mode: Mode::Record {
+                modules,
+                http_rewrites,
+            },
+        }
+    }
+
+    /// Serves downloads from `vendor` without touching the network.
+    pub fn vendored(vendor: Arc<Vendor>) -> Self {
+        Self {
+            native: NativeCapabilities::new(),
+            mode: Mode::Replay(vendor),
+        }
+    }
+
+    async fn fetch(&mut self, url: &str) -> pklr::Result<Vec<u8>> {
+        match &self.mode {
+            Mode::Record {
+                modules,
+                http_rewrites,
+            } => {
+                let fetch_url = http_rewrites
+                    .iter()
+                    .filter(|(src, _)| url.starts_with(src.as_str()))
+                    .max_by_key(|(src, _)| src.len())
+                    .map(|(src, tgt)| format!("{tgt}{}", &url[src.len()..]))
+                    .unwrap_or_else(|| url.to_string());
+                let modules = modules.clone();
+                let bytes = self.native.fetch_bytes(&fetch_url).await?;
+                modules
+                    .lock()
+                    .unwrap()
+                    .insert(url.to_string(), bytes.clone());
+                Ok(bytes)
+            }
+            Mode::Replay(vendor) => vendor.read(url).map_err(pklr::Error::Eval),
+        }
+    }
+}
+
+impl EvalCapabilities for VendorCapabilities {
+    fn read_to_string<'a>(&'a mut self, path: &'a Path) -> BoxFuture<'a, pklr::Result<String>> {
+        self.native.read_to_string(path)
+    }
+
+    fn path_exists<'a>(&'a mut self, path: &'a Path) -> BoxFuture<'a, pklr::Result<bool>> {
+        self.native.path_exists(path)
+    }
+
+    fn canonicalize<'a>(&'a mut self, path: &'a Path) -> BoxFuture<'a, pklr::Result<PathBuf>> {
+        self.native.canonicalize(path)
+    }
+
+    fn read_env<'a>(&'a mut self, name: &'a str) -> BoxFuture<'a, pklr::Result<Option<String>>> {
+        self.native.read_env(name)
+    }
+
+    fn fetch_text<'a>(&'a mut self, url: &'a str) -> BoxFuture<'a, pklr::Result<String>> {
+        Box::pin(async move {
+            let bytes = self.fetch(url).await?;
+            String::from_utf8(bytes)
+                .map_err(|e| pklr::Error::Eval(format!("{url} is not UTF-8: {e}")))
+        })
+    }
+
+    fn fetch_bytes<'a>(&'a mut self, url: &'a str) -> BoxFuture<'a, pklr::Result<Vec<u8>>> {
+        Box::pin(self.fetch(url))
+    }
+
+    fn set_http_client(&mut self, client: pklr::reqwest::Client) {
+        self.native.set_http_client(client);
+    }
+
+    fn temp_dir<'a>(&'a mut self, prefix: &'a str) -> BoxFuture<'a, pklr::Result<PathBuf>> {
+        self.native.temp_dir(prefix)
+    }
+
+    fn glob<'a>(
+        &'a mut self,
+        base: &'a Path,
+        pattern: &'a str,
+    ) -> BoxFuture<'a, pklr::Result<Vec<PathBuf>>> {
+        self.native.glob(base, pattern)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_vendored_path() {
+        let url = "https://github.com/jdx/hk/releases/download/v1.0.0/hk@1.0.0.zip";
+        let hash = &xx::hash::sha256(url.as_bytes())[..8];
+        assert_eq!(
+            vendored_path(url),
+            format!("github.com/jdx/hk/releases/download/v1.0.0/hk@1.0.0-{hash}.zip")
+        );
+        let url = "http://example.com/../a b/c.pkl?x=1";
+        let hash = &xx::hash::sha256(url.as_bytes())[..8];
+        assert_eq!(
+            vendored_path(url),
+            format!("example.com/a_b/c-{hash}.pkl_x_1")
+        );
+    }
+
+    #[test]
+    fn test_vendored_paths_do_not_collide() {
+        let urls = [
+            "https://example.com/a b.pkl",
+            "https://example.com/a_b.pkl",
+            "https://example.com/a?b.pkl",
+            "http://example.com/a_b.pkl",
+        ];
+        let paths = urls.map(vendored_path);
+        assert_eq!(paths.iter().unique().count(), urls.len(), "{paths:?}");
+    }
+
+    #[test]
+    fn test_vendor_round_trip_verifies_checksums() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path().join(VENDOR_DIR);
+        let url = "https://example.com/base.pkl";
+        let modules = BTreeMap::from([(url.to_string(), b"foo = 1\n".to_vec())]);
+        let lockfile = Vendor::write(&dir, &modules).unwrap();
+        let path = &lockfile.modules[url].path;
+        assert_eq!(path, &vendored_path(url));
+        assert!(path.starts_with("example.com/base-"), "{path}");
+
+        let config = tmp.path().join("sub").join("hk.pkl");
+        let vendor = Vendor::find(&config).unwrap().unwrap();
+        assert_eq!(vendor.read(url).unwrap(), b"foo = 1\n");
+        assert!(
+            vendor
+                .read("https://example.com/other.pkl")
+                .unwrap_err()
+                .contains("is not vendored")
+        );
+
+        std::fs::write(dir.join(path), "foo = 2\n").unwrap();
+        assert!(vendor.read(url).unwrap_err().contains("checksum mismatch"));
+    }
+}
@@ -0,0 +1,154 @@
+//! Org policy: a mandatory config layer that projects and users cannot
+//! override.
+//!
+//! hk loads the first of `/etc/hk/policy.{toml,yaml,yml,json}` that exists
+//! and then the file in `HK_POLICY`, if set. Setting `HK_POLICY` adds to the
+//! system policy rather than replacing it. A policy can pin
+//! `min_hk_version`, add steps to hooks (replacing project steps of the same
+//! name) and list `unskippable_steps`. Policy steps are unskippable too, so
+//! `skip_steps`, `HK_SKIP_STEPS` and `--skip-step` ignore all of them, and
+//! a hook in `skip_hooks` or `HK_SKIP_HOOK` still runs them.
+
+use std::path::{Path, PathBuf};
+
+use eyre::{WrapErr, bail};
+use indexmap::{IndexMap, IndexSet};
+use once_cell::sync::OnceCell;
+use serde::Deserialize;
+
+use crate::{Result, config::Config, env, hook::Hook, step_template, version};
+
+const SYSTEM_POLICY_DIR: &str = "/etc/hk";
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Policy {
+    #[serde(skip)]
+    pub path: PathBuf,
+    /// Minimum hk version, checked before any project config is read
+    #[serde(default)]
+    pub min_hk_version: Option<String>,
+    /// Steps to add to hooks
+    #[serde(default)]
+    pub hooks: IndexMap<String, Hook>,
+    /// Steps, besides the policy's own, that no skip setting can skip
+    #[serde(default)]
+    pub unskippable_steps: IndexSet<String>,
+}
+
+impl Policy {
+    /// The policies in effect for this hk process, system policy first
+    pub fn get() -> Result<&'static [Policy]> {
+        static POLICIES: OnceCell<Vec<Policy>> = OnceCell::new();
+        Ok(POLICIES.get_or_try_init(Self::load)?)
+    }
+
+    /// Paths of the policy files in effect, whether or not they have loaded
+    pub fn paths() -> Vec<PathBuf> {
+        let system = ["toml", "yaml", "yml", "json"]
+            .iter()
+            .map(|ext| Path::new(SYSTEM_POLICY_DIR).join(format!("policy.{ext}")))
+            .find(|path| path.exists());
+        system.into_iter().chain(env::HK_POLICY.clone()).collect()
+    }
+
+    fn load() -> Result<Vec<Policy>> {
+        Self::paths().iter().map(|path| Self::read(path)).collect()
+    }
+
+    fn read(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            bail!("policy file not found: {}", path.display());
+        }
+        let raw = xx::file::read_to_string(path)?;
+        let ext = path.extension().unwrap_or_default().to_str().unwrap_or("");
+        let mut value: serde_json::Value = match ext {
+            "toml" => toml::from_str(&raw)?,
+            "yaml" | "yml" => serde_yaml::from_str(&raw)?,
+            "json" => serde_json::from_str(&raw)?,
+            _ => bail!(
+                "unsupported policy file extension: {} (expected toml, yaml or json)",
+                path.display()
+            ),
+        };
+        step_template::expand(&mut value)?;
+        let mut policy: Policy = serde_json::from_value(value)
+            .wrap_err_with(|| format!("failed to parse policy {}", path.display()))?;
+        policy.path = path.to_path_buf();
+        if let Some(min_hk_version) = &policy.min_hk_version {
+            version::version_cmp_or_bail(min_hk_version)
+                .wrap_err_with(|| format!("required by policy {}", path.display()))?;
+        }
+        for (name, hook) in policy.hooks.iter_mut() {
+            hook.init(name)?;
+        }
+        Ok(policy)
+    }
+
+    /// Adds the policy's steps to the config. Runs after every other layer
+    /// has been merged, so policy steps win on collision.
+    pub fn apply(&self, config: &mut Config) {
+        for (hook_name, hook) in &self.hooks {
+            match config.hooks.get_mut(hook_name) {
+                Some(project_hook) => {
+                    for (step_name, step) in &hook.steps {
+                        project_hook.steps.insert(step_name.clone(), step.clone());
+                    }
+                }
+                None => {
+                    config.hooks.insert(hook_name.clone(), hook.clone());
+                }
+            }
+        }
+    }
+
+    fn requires(&self, step: &str) -> bool {
+        self.unskippable_steps.contains(step)
+            || self
+                .hooks
+                .values()
+                .any(|hook| hook.steps.contains_key(step))
+    }
+
+    /// Steps this policy requires, for `hk config explain skip_steps`
+    pub fn required_steps(&self) -> IndexSet<String> {
+        let mut steps = self.unskippable_steps.clone();
+        for hook in self.hooks.values() {
+            steps.extend(hook.steps.keys().cloned());
+        }
+        steps
+    }
+}
+
+/// The policy that forbids skipping `step`, if any
+pub fn required_by(step: &str) -> Option<&'static Policy> {
+    // Policy errors already failed config loading; nothing to enforce here
+    Policy::get()
+        .ok()?
+        .iter()
+        .find(|policy| policy.requires(step))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_policy_steps_are_required() {
+        let raw = r#"
+            unskippable_steps = ["lint"]
+
+            [hooks.pre-commit.steps.secrets]
+            check = "detect-secrets"
+        "#;
+        let value: serde_json::Value = toml::from_str(raw).unwrap();
+        let policy: Policy = serde_json::from_value(value).unwrap();
+        assert!(policy.requires("lint"));
+        assert!(policy.requires("secrets"));
+        assert!(!policy.requires("fmt"));
+        assert_eq!(
+            policy.required_steps().into_iter().collect::<Vec<_>>(),
+            vec!["lint", "secrets"]
+        );
+    }
+}
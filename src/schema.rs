@@ -0,0 +1,139 @@
+//! JSON Schema for hk config files, derived from the config types.
+//!
+//! `hk config schema` prints it so editors can complete and validate
+//! `hk.yaml`, `hk.toml` and `hk.json`. Most types derive [`JsonSchema`];
+//! this module holds the pieces for fields whose serde representation the
+//! derive cannot see.
+//!
+//! Unknown fields are rejected by the schema in every build, so editors
+//! flag typos even though release builds ignore them when loading a config.
+
+use std::borrow::Cow;
+use std::marker::PhantomData;
+
+use schemars::{JsonSchema, Schema, SchemaGenerator, json_schema};
+
+use crate::config::{Config, UserConfig};
+
+/// Schema for the project config (`hk.yaml`, `hk.toml`, `hk.json`)
+pub fn config_schema() -> Schema {
+    let mut schema = schemars::schema_for!(Config);
+    schema.insert("title".into(), "hk config".into());
+    add_step_templates(&mut schema);
+    schema
+}
+
+/// `templates`, `extends` and `matrix` are expanded before the config is
+/// deserialized (see [`crate::step_template`]), so no type carries them.
+fn add_step_templates(schema: &mut Schema) {
+    let extends = json_schema!({
+        "type": "string",
+        "description": "Name of the template in `templates` this step starts from",
+    });
+    let matrix = json_schema!({
+        "type": "object",
+        "description": "Expands the step into one step per combination of these field values",
+        "additionalProperties": { "type": "array", "minItems": 1 },
+    });
+    if let Some(properties) = schema
+        .pointer_mut("/$defs/Step/properties")
+        .and_then(|p| p.as_object_mut())
+    {
+        properties.insert("extends".into(), extends.clone().into());
+        properties.insert("matrix".into(), matrix.into());
+    }
+    // A template is a partial step, so nothing in it is required
+    let template = json_schema!({
+        "type": "object",
+        "description": "Partial step that steps pull in with `extends`",
+        "properties": { "extends": extends },
+        "additionalProperties": true,
+    });
+    if let Some(properties) = schema.get_mut("properties").and_then(|p| p.as_object_mut()) {
+        properties.insert(
+            "templates".into(),
+            json_schema!({
+                "type": "object",
+                "description": "Reusable partial steps for TOML, YAML and JSON configs",
+                "additionalProperties": template,
+            })
+            .into(),
+        );
+    }
+}
+
+/// Schema for the legacy user config (`.hkrc.pkl` amending `UserConfig.pkl`)
+pub fn user_config_schema() -> Schema {
+    let mut schema = schemars::schema_for!(UserConfig);
+    schema.insert("title".into(), "hk user config".into());
+    schema
+}
+
+/// Schema for fields that also accept a plain string, i.e. fields
+/// deserialized with `PickFirst<(_, DisplayFromStr)>`.
+pub struct OrString<T>(PhantomData<T>);
+
+impl<T: JsonSchema> JsonSchema for OrString<T> {
+    fn inline_schema() -> bool {
+        true
+    }
+
+    fn schema_name() -> Cow<'static, str> {
+        format!("{}OrString", T::schema_name()).into()
+    }
+
+    fn json_schema(generator: &mut SchemaGenerator) -> Schema {
+        json_schema!({
+            "anyOf": [
+                { "type": "string" },
+                generator.subschema_for::<T>(),
+            ]
+        })
+    }
+}
+
+/// Schema for a step that starts from an embedded builtin, e.g.
+/// `builtin = "prettier"`. Any other step field overrides the builtin's.
+pub fn builtin_step_schema() -> Schema {
+    let names = crate::builtins::builtin_names().collect::<Vec<_>>();
+    let mut builtin = json_schema!({
+        "type": "string",
+        "description": "Name of the builtin step to start from (see `hk builtins`)",
+    });
+    if !names.is_empty() {
+        builtin.insert("enum".into(), names.into());
+    }
+    json_schema!({
+        "type": "object",
+        "required": ["builtin"],
+        "properties": { "builtin": builtin },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_config_schema_defines_config_types() {
+        let schema = serde_json::to_value(config_schema()).unwrap();
+        let defs = &schema["$defs"];
+        for name in [
+            "Hook",
+            "Step",
+            "StepGroup",
+            "StepOrGroup",
+            "StepTest",
+            "Pattern",
+        ] {
+            assert!(defs.get(name).is_some(), "missing definition for {name}");
+        }
+        assert_eq!(defs["Step"]["additionalProperties"], false);
+        assert!(schema["properties"]["$schema"].is_object());
+        assert!(schema["properties"]["templates"].is_object());
+        assert!(defs["Step"]["properties"]["matrix"].is_object());
+
+        let user = serde_json::to_value(user_config_schema()).unwrap();
+        assert!(user["$defs"].get("UserHookConfig").is_some());
+    }
+}
@@ -689,6 +689,25 @@ impl Settings {
             "================================================"
         )?;
 
+        // Policy: steps it requires cannot be skipped from any other source
+        if field_name == "skip_steps" {
+            for policy in crate::policy::Policy::get()? {
+                writeln!(
+                    &mut output,
+                    "  ORG POLICY: {}",
+                    xx::file::display_path(&policy.path)
+                )?;
+                let required = policy.required_steps();
+                if !required.is_empty() {
+                    writeln!(
+                        &mut output,
+                        "    ✓ Cannot be skipped: {:?}",
+                        required.into_iter().collect::<Vec<_>>()
+                    )?;
+                }
+            }
+        }
+
         // CLI
         if !meta.sources.cli.is_empty() {
             writeln!(&mut output, "  CLI FLAGS: {}", meta.sources.cli.join(", "))?;
@@ -37,6 +37,13 @@ pub fn eval_condition(code: &str, ctx: &expr::Context) -> expr::Result<expr::Val
     EXPR_ENV.eval(&escape_quoted_newlines(code), ctx)
 }
 
+/// Parse an hk condition without evaluating it, so `hk validate` can report
+/// syntax errors without running `exec()` calls.
+pub fn parse_condition(code: &str) -> expr::Result<()> {
+    expr::compile(&escape_quoted_newlines(code))?;
+    Ok(())
+}
+
 #[derive(Clone, Copy)]
 enum LexState {
     Normal,
@@ -17,6 +17,7 @@
 //! - [`output`] - Output capture and fix suggestions
 //! - [`progress`] - Progress bar management
 //! - [`expr_env`] - Expression evaluation for conditions
+//! - [`stdin`] - In-memory fixing of a single buffer (`--stdin-filepath`)
 //!
 //! # Usage
 //!
@@ -42,14 +43,17 @@ mod output;
 mod progress;
 mod runner;
 mod shell;
+mod stdin;
 mod types;
 
 // Re-export public API
-pub use expr_env::{EXPR_CTX, eval_condition};
+pub use expr_env::{EXPR_CTX, eval_condition, parse_condition};
 pub use shell::ShellType;
-pub(crate) use types::RenderedCommand;
+pub(crate) use stdin::stdin_copy;
 #[cfg(test)]
-pub(crate) use types::{ArgvCommand, Command};
+pub(crate) use types::ArgvCommand;
+pub(crate) use types::Command;
+pub(crate) use types::RenderedCommand;
 pub use types::{
     CommandEffect, CommandPrefix, DiagnosticFormat, FileSelector, OutputSummary, Pattern, RunType,
     Script, Step,
@@ -104,6 +104,68 @@ impl Step {
         }
     }
 
+    /// Every positive glob/regex pattern, exposed to templates as `{{globs}}`.
+    /// Type-only selectors are represented by the already-filtered `{{files}}` list.
+    pub(crate) fn glob_patterns(&self) -> Vec<String> {
+        let patterns = if let Some(selectors) = &self.match_any {
+            selectors
+                .iter()
+                .filter_map(|selector| selector.glob.as_ref())
+                .collect::<Vec<_>>()
+        } else {
+            self.glob.iter().collect()
+        };
+        patterns
+            .into_iter()
+            .flat_map(|pattern| match pattern {
+                Pattern::Globs(globs) => globs.clone(),
+                Pattern::Regex { pattern, .. } => vec![pattern.clone()],
+            })
+            .collect()
+    }
+
+    /// Build the process for a rendered command: argv commands run directly,
+    /// scripts run through the step's `shell` or `sh -o errexit -c`.
+    pub(crate) fn command_runner(&self, rendered: &RenderedCommand) -> CmdLineRunner {
+        match rendered {
+            RenderedCommand::Argv(argv) => CmdLineRunner::new_direct(&argv[0]).args(&argv[1..]),
+            RenderedCommand::Shell(run) => {
+                // On Windows, `cmd.exe` has its own quoting rules that collide with
+                // Rust's MSVCRT-style argv escaping. `ShellType::Cmd::quote` produces
+                // cmd-appropriate quoting for rendered `{{files}}` / `{{workspace_files}}`
+                // strings, so we must append the final command line verbatim via
+                // `raw_arg` — otherwise Rust re-escapes the already-quoted payload and
+                // cmd.exe delivers tools arguments with literal `"` characters embedded.
+                let use_raw_cmd = cfg!(windows) && matches!(self.shell_type(), ShellType::Cmd);
+                if let Some(shell) = &self.shell {
+                    let shell = shell.to_string();
+                    let shell = shell.split_whitespace().collect_vec();
+                    let mut cmd = if use_raw_cmd {
+                        CmdLineRunner::new_direct(shell[0])
+                    } else {
+                        CmdLineRunner::new(shell[0])
+                    };
+                    for arg in shell[1..].iter() {
+                        cmd = cmd.arg(arg);
+                    }
+                    if use_raw_cmd {
+                        cmd.raw_arg(run)
+                    } else {
+                        cmd.arg(run)
+                    }
+                } else if use_raw_cmd {
+                    CmdLineRunner::new_direct("cmd.exe").arg("/c").raw_arg(run)
+                } else {
+                    CmdLineRunner::new("sh")
+                        .arg("-o")
+                        .arg("errexit")
+                        .arg("-c")
+                        .arg(run)
+                }
+            }
+        }
+    }
+
     /// Execute a single job.
     ///
     /// This is the core execution function that runs a command for a step.
@@ -170,24 +232,7 @@ impl Step {
             return Ok(());
         }
         let mut tctx = job.tctx(&ctx.hook_ctx.tctx);
-        // `{{globs}}` contains every positive glob/regex pattern. Type-only
-        // selectors are represented by the already-filtered `{{files}}` list.
-        let patterns = if let Some(selectors) = &self.match_any {
-            selectors
-                .iter()
-                .filter_map(|selector| selector.glob.as_ref())
-                .collect::<Vec<_>>()
-        } else {
-            self.glob.iter().collect()
-        };
-        let globs = patterns
-            .into_iter()
-            .flat_map(|pattern| match pattern {
-                Pattern::Globs(globs) => globs.clone(),
-                Pattern::Regex { pattern, .. } => vec![pattern.clone()],
-            })
-            .collect::<Vec<_>>();
-        tctx.with_globs(&globs);
+        tctx.with_globs(&self.glob_patterns());
         let file_msg = |files: &[PathBuf]| {
             format!(
                 "{} file{}",
@@ -266,43 +311,7 @@ impl Step {
                 trace!("{self}: {}", file.display());
             }
         }
-        // On Windows, `cmd.exe` has its own quoting rules that collide with
-        // Rust's MSVCRT-style argv escaping. `ShellType::Cmd::quote` produces
-        // cmd-appropriate quoting for rendered `{{files}}` / `{{workspace_files}}`
-        // strings, so we must append the final command line verbatim via
-        // `raw_arg` — otherwise Rust re-escapes the already-quoted payload and
-        // cmd.exe delivers tools arguments with literal `"` characters embedded.
-        let mut cmd = match &rendered_command {
-            RenderedCommand::Argv(argv) => CmdLineRunner::new_direct(&argv[0]).args(&argv[1..]),
-            RenderedCommand::Shell(run) => {
-                let use_raw_cmd = cfg!(windows) && matches!(self.shell_type(), ShellType::Cmd);
-                if let Some(shell) = &self.shell {
-                    let shell = shell.to_string();
-                    let shell = shell.split_whitespace().collect_vec();
-                    let mut cmd = if use_raw_cmd {
-                        CmdLineRunner::new_direct(shell[0])
-                    } else {
-                        CmdLineRunner::new(shell[0])
-                    };
-                    for arg in shell[1..].iter() {
-                        cmd = cmd.arg(arg);
-                    }
-                    if use_raw_cmd {
-                        cmd.raw_arg(run)
-                    } else {
-                        cmd.arg(run)
-                    }
-                } else if use_raw_cmd {
-                    CmdLineRunner::new_direct("cmd.exe").arg("/c").raw_arg(run)
-                } else {
-                    CmdLineRunner::new("sh")
-                        .arg("-o")
-                        .arg("errexit")
-                        .arg("-c")
-                        .arg(run)
-                }
-            }
-        };
+        let mut cmd = self.command_runner(&rendered_command);
         cmd = cmd
             .with_pr(job.progress.as_ref().unwrap().clone())
             .with_cancel_token(ctx.hook_ctx.failed.clone())
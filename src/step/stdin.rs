@@ -0,0 +1,163 @@
+//! In-memory fixing and checking of editor buffers.
+//!
+//! `hk fix --stdin-filepath` never touches the working tree: the buffer is
+//! written to a temporary copy, each matching step's `fix` command runs
+//! against that copy, and the final contents are returned to the caller.
+//! `hk check --stdin-filepath` runs the `check` commands against the copy
+//! instead and reports their output as if it came from the real path.
+//!
+//! The copy lives in a hidden directory beside the virtual path, so tools
+//! that search upward from the file for their config (prettier, rustfmt,
+//! ruff, biome) format it the same way they would format the file itself.
+
+use crate::{Result, env, tera};
+use ensembler::CmdLineRunner;
+use eyre::WrapErr;
+use std::path::{Path, PathBuf};
+
+use super::types::{RunType, Step};
+
+/// A temporary stand-in for a buffer that would be stored at `path`. The
+/// directory is removed when the returned guard is dropped.
+pub(crate) fn stdin_copy(path: &Path, contents: &[u8]) -> Result<(tempfile::TempDir, PathBuf)> {
+    let Some(file_name) = path.file_name() else {
+        eyre::bail!("--stdin-filepath must name a file: {}", path.display());
+    };
+    let path = std::path::absolute(path)?;
+    // The buffer may be for a file whose directory does not exist yet
+    let dir = path.ancestors().skip(1).find(|dir| dir.is_dir());
+    let tmp = match dir {
+        Some(dir) => tempfile::Builder::new()
+            .prefix(".hk-stdin-")
+            .tempdir_in(dir)
+            .or_else(|_| tempfile::tempdir())?,
+        None => tempfile::tempdir()?,
+    };
+    let copy = tmp.path().join(file_name);
+    xx::file::write(&copy, contents)?;
+    Ok((tmp, copy))
+}
+
+impl Step {
+    /// Whether this step would select a file at `path` if it existed with
+    /// the buffer's contents. When fixing, steps without a `fix` command are
+    /// never selected since they cannot produce new contents; steps that need
+    /// a terminal are never selected at all.
+    pub(crate) fn selects_stdin_path(
+        &self,
+        path: &Path,
+        contents: &[u8],
+        run_type: RunType,
+    ) -> Result<bool> {
+        let command = match run_type {
+            RunType::Fix => self.fix.as_ref(),
+            RunType::Check => self.run_cmd(RunType::Check),
+        };
+        if command.is_none_or(|command| command.is_empty()) || self.interactive {
+            return Ok(false);
+        }
+        if !self.allow_binary && contents.contains(&0) {
+            return Ok(false);
+        }
+        if self.workspace_indicator.is_some() && self.stdin_workspace(path).is_none() {
+            return Ok(false);
+        }
+        Ok(!self.filter_files(&[path.to_path_buf()])?.is_empty())
+    }
+
+    /// Run the `fix` command against `copy`, a temporary stand-in for `path`.
+    pub(crate) async fn fix_stdin_copy(
+        &self,
+        base: &tera::Context,
+        path: &Path,
+        copy: &Path,
+    ) -> Result<()> {
+        let Some(cmd) = self.stdin_command(RunType::Fix, base, path, copy).await? else {
+            return Ok(());
+        };
+        cmd.execute()
+            .await
+            .map_err(|err| match err {
+                ensembler::Error::ScriptFailed(e) => eyre::eyre!("{}", e.2),
+                err => err.into(),
+            })
+            .wrap_err_with(|| format!("{self}: fix failed"))?;
+        Ok(())
+    }
+
+    /// Run the `check` command against `copy`, a temporary stand-in for
+    /// `path`. Returns whether it passed and its output, with the copy's
+    /// path replaced by `path` so diagnostics point at the real file.
+    pub(crate) async fn check_stdin_copy(
+        &self,
+        base: &tera::Context,
+        path: &Path,
+        copy: &Path,
+    ) -> Result<(bool, String)> {
+        let Some(cmd) = self.stdin_command(RunType::Check, base, path, copy).await? else {
+            return Ok((true, String::new()));
+        };
+        let (passed, output) = match cmd.execute().await {
+            Ok(result) => (true, result.combined_output),
+            Err(ensembler::Error::ScriptFailed(e)) => (false, e.3.combined_output),
+            Err(err) => return Err(err).wrap_err_with(|| format!("{self}: check failed")),
+        };
+        // Tools print the copy's path either as given or relative to the cwd
+        let cwd = std::env::current_dir()?;
+        let relative = copy.strip_prefix(&cwd).unwrap_or(copy);
+        let output = output
+            .replace(&*copy.to_string_lossy(), &path.to_string_lossy())
+            .replace(&*relative.to_string_lossy(), &path.to_string_lossy());
+        Ok((passed, output))
+    }
+
+    async fn stdin_command(
+        &self,
+        run_type: RunType,
+        base: &tera::Context,
+        path: &Path,
+        copy: &Path,
+    ) -> Result<Option<CmdLineRunner>> {
+        let Some(run_cmd) = self.run_cmd(run_type) else {
+            return Ok(None);
+        };
+        let mut tctx = base.clone();
+        tctx.insert("step", &self.name);
+        tctx.with_files(self.shell_type(), &[copy]);
+        tctx.with_globs(&self.glob_patterns());
+        if let Some(workspace_indicator) = self.stdin_workspace(path) {
+            tctx.with_workspace_indicator(&workspace_indicator);
+            let workspace_dir = workspace_indicator
+                .parent()
+                .filter(|p| !p.as_os_str().is_empty())
+                .unwrap_or(Path::new("."));
+            tctx.with_workspace_files(self.shell_type(), workspace_dir, &[copy]);
+        }
+        let rendered = run_cmd
+            .render(&tctx, self.prefix.as_ref())
+            .wrap_err_with(|| format!("{self}: failed to render command template"))?;
+        let mut cmd = self.command_runner(&rendered);
+        if let Some(stdin) = &self.stdin {
+            cmd = cmd.stdin_string(tera::render(stdin, &tctx)?);
+        }
+        if let Some(dir) = &self.dir {
+            cmd = cmd.current_dir(dir);
+            if *env::HK_MISE {
+                let mise_env = crate::mise_env::mise_env_for_dir(Path::new(dir)).await;
+                for (key, value) in mise_env.iter() {
+                    cmd = cmd.env(key, value);
+                }
+            }
+        }
+        for (key, value) in &self.env {
+            cmd = cmd.env(key, tera::render(value, &tctx)?);
+        }
+        debug!("{self}: {}", rendered.display(self.shell_type()));
+        Ok(Some(cmd))
+    }
+
+    fn stdin_workspace(&self, path: &Path) -> Option<PathBuf> {
+        let workspace_indicator = self.workspace_indicator.as_ref()?;
+        xx::file::find_up(path.parent()?, &[workspace_indicator])
+    }
+}
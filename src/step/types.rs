@@ -9,11 +9,12 @@
 //! - [`RunType`] - Whether to run in check or fix mode
 //! - [`OutputSummary`] - How to capture and display command output
 
-use crate::{Result, step_test::StepTest, tera};
+use crate::{Result, schema::OrString, step_test::StepTest, tera};
 use indexmap::IndexMap;
+use schemars::{JsonSchema, Schema, SchemaGenerator, json_schema};
 use serde::{Deserialize, Serialize};
 use serde_with::{DisplayFromStr, PickFirst, serde_as};
-use std::{fmt, fmt::Display, path::PathBuf, str::FromStr};
+use std::{borrow::Cow, fmt, fmt::Display, path::PathBuf, str::FromStr};
 
 /// A file matching pattern that can be either glob patterns or a regex.
 ///
@@ -93,13 +94,38 @@ impl<'de> Deserialize<'de> for Pattern {
     }
 }
 
+impl JsonSchema for Pattern {
+    fn schema_name() -> Cow<'static, str> {
+        "Pattern".into()
+    }
+
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        json_schema!({
+            "description": "One or more glob patterns, or a regex",
+            "anyOf": [
+                { "type": "string" },
+                { "type": "array", "items": { "type": "string" } },
+                {
+                    "type": "object",
+                    "properties": {
+                        "_type": { "const": "regex" },
+                        "pattern": { "type": "string" },
+                    },
+                    "required": ["_type", "pattern"],
+                },
+            ]
+        })
+    }
+}
+
 /// A positive file-selection clause.
 ///
 /// Glob and type filters within one selector use AND semantics. Multiple
 /// selectors in [`Step::match_any`] use OR semantics.
-#[derive(Debug, Default, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[derive(Debug, Default, Clone, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 #[cfg_attr(debug_assertions, serde(deny_unknown_fields))]
+#[schemars(deny_unknown_fields)]
 pub struct FileSelector {
     /// File matching pattern (globs or regex)
     #[serde(default)]
@@ -134,10 +160,11 @@ impl FileSelector {
 ///     fix = "eslint --fix {{files}}"
 /// }
 /// ```
-#[serde_as]
-#[derive(Debug, Default, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde_as(schemars = false)]
+#[derive(Debug, Default, Clone, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 #[cfg_attr(debug_assertions, serde(deny_unknown_fields))]
+#[schemars(deny_unknown_fields)]
 pub struct Step {
     /// Internal type marker (used by Pkl)
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -187,18 +214,22 @@ pub struct Step {
 
     /// Custom shell to use (default: `sh -o errexit -c`)
     #[serde_as(as = "Option<PickFirst<(_, DisplayFromStr)>>")]
+    #[schemars(with = "Option<OrString<Script>>")]
     pub shell: Option<Script>,
 
     /// Command to check for issues (exit non-zero if issues found)
     #[serde_as(as = "Option<PickFirst<(_, DisplayFromStr)>>")]
+    #[schemars(with = "Option<OrString<Command>>")]
     pub check: Option<Command>,
 
     /// Command that outputs a list of files needing fixes (one per line)
     #[serde_as(as = "Option<PickFirst<(_, DisplayFromStr)>>")]
+    #[schemars(with = "Option<OrString<Command>>")]
     pub check_list_files: Option<Command>,
 
     /// Command that outputs a unified diff of needed changes
     #[serde_as(as = "Option<PickFirst<(_, DisplayFromStr)>>")]
+    #[schemars(with = "Option<OrString<Command>>")]
     pub check_diff: Option<Command>,
 
     /// Run a file-listing check first, then check only the failing files
@@ -207,6 +238,7 @@ pub struct Step {
 
     /// Command to fix issues
     #[serde_as(as = "Option<PickFirst<(_, DisplayFromStr)>>")]
+    #[schemars(with = "Option<OrString<Command>>")]
     pub fix: Option<Command>,
 
     /// File that indicates workspace roots (e.g., `Cargo.toml` for Rust)
@@ -317,7 +349,7 @@ impl fmt::Display for RunType {
 /// How command output should be captured for the end-of-run summary.
 ///
 /// This controls what output is shown to the user after all steps complete.
-#[derive(Debug, Clone, Deserialize, Serialize, Default, PartialEq, Eq)]
+#[derive(Debug, Clone, Deserialize, Serialize, Default, PartialEq, Eq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum OutputSummary {
     /// Capture stderr output (default)
@@ -345,7 +377,7 @@ pub enum OutputSummary {
 ///     other = "find . -name '*.bak'"
 /// }
 /// ```
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 #[serde_as]
 #[serde(deny_unknown_fields)]
 pub struct Script {
@@ -363,21 +395,23 @@ pub struct Script {
 ///
 /// Exact `{{files}}` and `{{workspace_files}}` entries expand to multiple
 /// arguments. Every other entry is rendered as one Tera-templated argument.
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub struct ArgvCommand {
     pub argv: Vec<String>,
 }
 
 /// A command prefix that preserves the execution mode of the command it wraps.
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 #[serde(untagged)]
 pub enum CommandPrefix {
     Shell(String),
     Argv(Vec<String>),
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, JsonSchema,
+)]
 #[serde(rename_all = "lowercase")]
 pub enum CommandEffect {
     Read,
@@ -385,7 +419,7 @@ pub enum CommandEffect {
     Destructive,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "kebab-case")]
 pub enum DiagnosticFormat {
     Sarif,
@@ -394,10 +428,11 @@ pub enum DiagnosticFormat {
     Gcc,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub struct CommandSpec {
     #[serde(deserialize_with = "deserialize_boxed_command")]
+    #[schemars(with = "OrString<Command>")]
     pub command: Box<Command>,
     pub effect: CommandEffect,
 }
@@ -420,7 +455,7 @@ where
 }
 
 /// A step command that either runs through a shell or executes an argv directly.
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 #[serde(untagged)]
 pub enum Command {
     Spec(CommandSpec),
@@ -435,6 +470,19 @@ pub(crate) enum RenderedCommand {
 }
 
 impl Command {
+    /// The command's template text, for every platform.
+    pub(crate) fn templates(&self) -> Vec<&str> {
+        match self {
+            Self::Spec(spec) => spec.command.templates(),
+            Self::Shell(script) => [&script.linux, &script.macos, &script.windows, &script.other]
+                .into_iter()
+                .flatten()
+                .map(String::as_str)
+                .collect(),
+            Self::Argv(command) => command.argv.iter().map(String::as_str).collect(),
+        }
+    }
+
     pub fn is_empty(&self) -> bool {
         match self {
             Self::Spec(spec) => spec.command.is_empty(),
@@ -1,6 +1,7 @@
 use clx::progress::{ProgressJob, ProgressJobBuilder, ProgressStatus};
 use eyre::Context;
 use indexmap::{IndexMap, IndexSet};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_with::{DisplayFromStr, PickFirst, serde_as};
 
@@ -18,8 +19,8 @@ use std::{
     sync::{Arc, Mutex},
 };
 
-#[serde_as]
-#[derive(Debug, Clone, Default, Deserialize, Serialize, Eq, PartialEq)]
+#[serde_as(schemars = false)]
+#[derive(Debug, Clone, Default, Deserialize, Serialize, Eq, PartialEq, JsonSchema)]
 pub struct StepGroup {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub _type: Option<String>,
@@ -28,6 +29,7 @@ pub struct StepGroup {
     pub prefix: Option<CommandPrefix>,
     pub dir: Option<String>,
     #[serde_as(as = "Option<PickFirst<(_, DisplayFromStr)>>")]
+    #[schemars(with = "Option<crate::schema::OrString<Script>>")]
     pub shell: Option<Script>,
     pub stage: Option<Vec<String>>,
     pub exclude: Option<Pattern>,
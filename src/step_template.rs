@@ -0,0 +1,264 @@
+//! Step templates and matrices for TOML, YAML and JSON configs.
+//!
+//! Pkl configs reuse steps with Pkl itself. The other formats get top-level
+//! `templates`, partial steps that a step pulls in with `extends`, and a
+//! step `matrix` that expands one step into one step per combination of
+//! field values:
+//!
+//! ```toml
+//! [templates.cargo]
+//! workspace_indicator = "Cargo.toml"
+//! check = "cargo clippy"
+//!
+//! [hooks.check.steps.clippy]
+//! extends = "cargo"
+//! matrix = { dir = ["crates/a", "crates/b"] }
+//! ```
+//!
+//! Expansion rewrites the raw config before it is deserialized, so the
+//! generated steps (`clippy[crates/a]`, `clippy[crates/b]`) are ordinary steps
+//! to validation, `--plan` and `hk config export`.
+
+use eyre::{bail, eyre};
+use indexmap::IndexSet;
+use itertools::Itertools;
+use serde_json::{Map, Value};
+
+use crate::Result;
+
+/// Expands `templates`, `extends` and `matrix` in a raw config in place.
+pub fn expand(config: &mut Value) -> Result<()> {
+    let Some(config) = config.as_object_mut() else {
+        return Ok(());
+    };
+    let templates = match config.remove("templates") {
+        Some(Value::Object(templates)) => templates,
+        Some(_) => bail!("templates must be a table of partial steps"),
+        None => Map::new(),
+    };
+    let Some(Value::Object(hooks)) = config.get_mut("hooks") else {
+        return Ok(());
+    };
+    for (hook_name, hook) in hooks.iter_mut() {
+        if let Some(Value::Object(steps)) = hook.get_mut("steps") {
+            expand_steps(steps, &templates).map_err(|err| eyre!("in hook '{hook_name}': {err}"))?;
+        }
+    }
+    Ok(())
+}
+
+fn expand_steps(steps: &mut Map<String, Value>, templates: &Map<String, Value>) -> Result<()> {
+    let mut expanded = Map::new();
+    // Generated names of each matrix step, to rewrite `depends` on it
+    let mut generated: Vec<(String, Vec<String>)> = vec![];
+    for (name, step) in std::mem::take(steps) {
+        let Value::Object(mut step) = step else {
+            expanded.insert(name, step);
+            continue;
+        };
+        if is_group(&step) {
+            if let Some(Value::Object(group_steps)) = step.get_mut("steps") {
+                expand_steps(group_steps, templates)
+                    .map_err(|err| eyre!("in group '{name}': {err}"))?;
+            }
+            expanded.insert(name, Value::Object(step));
+            continue;
+        }
+        if let Some(extends) = step.remove("extends") {
+            let Value::String(template) = extends else {
+                bail!("step '{name}': extends must be a template name");
+            };
+            step = extend(&template, step, templates, &mut IndexSet::new())
+                .map_err(|err| eyre!("step '{name}': {err}"))?;
+        }
+        match step.remove("matrix") {
+            None => {
+                expanded.insert(name, Value::Object(step));
+            }
+            Some(matrix) => {
+                let steps = matrix_steps(&name, &step, matrix)
+                    .map_err(|err| eyre!("step '{name}': {err}"))?;
+                generated.push((name, steps.iter().map(|(n, _)| n.clone()).collect()));
+                for (name, step) in steps {
+                    if expanded.contains_key(&name) {
+                        bail!("matrix step '{name}' is defined more than once");
+                    }
+                    expanded.insert(name, Value::Object(step));
+                }
+            }
+        }
+    }
+    for (name, names) in &generated {
+        for step in expanded.values_mut() {
+            rewrite_depends(step, name, names);
+        }
+    }
+    *steps = expanded;
+    Ok(())
+}
+
+/// Groups are recognized the same way `StepOrGroup` deserializes them
+fn is_group(step: &Map<String, Value>) -> bool {
+    match step.get("_type") {
+        Some(Value::String(ty)) if ty == "group" => true,
+        _ => step.contains_key("steps"),
+    }
+}
+
+/// The template's fields overridden by the step's. Templates may themselves
+/// extend another template.
+fn extend(
+    template: &str,
+    overrides: Map<String, Value>,
+    templates: &Map<String, Value>,
+    seen: &mut IndexSet<String>,
+) -> Result<Map<String, Value>> {
+    if !seen.insert(template.to_string()) {
+        bail!(
+            "templates extend each other in a cycle: {} -> {template}",
+            seen.iter().join(" -> ")
+        );
+    }
+    let Some(definition) = templates.get(template) else {
+        bail!(
+            "unknown template '{template}', expected one of: {}",
+            templates.keys().join(", ")
+        );
+    };
+    let Value::Object(definition) = definition else {
+        bail!("template '{template}' must be a table of step fields");
+    };
+    let mut base = definition.clone();
+    if let Some(parent) = base.remove("extends") {
+        let Value::String(parent) = parent else {
+            bail!("template '{template}': extends must be a template name");
+        };
+        base = extend(&parent, base, templates, seen)?;
+    }
+    base.extend(overrides);
+    Ok(base)
+}
+
+/// One step per combination of the matrix values, named `<step>[<value>]...`
+fn matrix_steps(
+    name: &str,
+    step: &Map<String, Value>,
+    matrix: Value,
+) -> Result<Vec<(String, Map<String, Value>)>> {
+    let Value::Object(matrix) = matrix else {
+        bail!("matrix must be a table of field names to lists of values");
+    };
+    let mut axes = vec![];
+    for (field, values) in matrix {
+        match values {
+            Value::Array(values) if !values.is_empty() => axes.push((field, values)),
+            _ => bail!("matrix field '{field}' must be a non-empty list"),
+        }
+    }
+    if axes.is_empty() {
+        bail!("matrix must list at least one field");
+    }
+    let combinations = axes
+        .iter()
+        .map(|(field, values)| values.iter().map(move |value| (field, value)))
+        .multi_cartesian_product();
+    Ok(combinations
+        .map(|combination| {
+            let mut step = step.clone();
+            let mut name = name.to_string();
+            for (field, value) in combination {
+                match value {
+                    Value::String(s) => name.push_str(&format!("[{s}]")),
+                    other => name.push_str(&format!("[{other}]")),
+                }
+                step.insert(field.clone(), value.clone());
+            }
+            (name, step)
+        })
+        .collect())
+}
+
+/// `depends` on a matrix step waits for every step it expanded into
+fn rewrite_depends(step: &mut Value, name: &str, names: &[String]) {
+    let Some(Value::Array(depends)) = step.get_mut("depends") else {
+        return;
+    };
+    if !depends.iter().any(|d| d.as_str() == Some(name)) {
+        return;
+    }
+    *depends = std::mem::take(depends)
+        .into_iter()
+        .flat_map(|d| {
+            if d.as_str() == Some(name) {
+                names.iter().cloned().map(Value::String).collect_vec()
+            } else {
+                vec![d]
+            }
+        })
+        .collect();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_extends_overrides_template_fields() {
+        let mut config = json!({
+            "templates": {
+                "base": {"glob": ["*.rs"], "batch": true},
+                "cargo": {"extends": "base", "check": "cargo check", "batch": false},
+            },
+            "hooks": {"check": {"steps": {"a": {"extends": "cargo", "check": "cargo clippy"}}}}
+        });
+        expand(&mut config).unwrap();
+        assert_eq!(
+            config,
+            json!({
+                "hooks": {"check": {"steps": {"a": {
+                    "glob": ["*.rs"],
+                    "batch": false,
+                    "check": "cargo clippy",
+                }}}}
+            })
+        );
+    }
+
+    #[test]
+    fn test_matrix_expands_steps_and_depends() {
+        let mut config = json!({
+            "hooks": {"check": {"steps": {
+                "lint": {"check": "lint", "matrix": {"dir": ["a", "b"], "shell": ["sh"]}},
+                "report": {"check": "report", "depends": ["lint"]},
+            }}}
+        });
+        expand(&mut config).unwrap();
+        let steps = &config["hooks"]["check"]["steps"];
+        assert_eq!(
+            steps.as_object().unwrap().keys().collect_vec(),
+            vec!["lint[a][sh]", "lint[b][sh]", "report"]
+        );
+        assert_eq!(steps["lint[b][sh]"]["dir"], "b");
+        assert_eq!(
+            steps["report"]["depends"],
+            json!(["lint[a][sh]", "lint[b][sh]"])
+        );
+    }
+
+    #[test]
+    fn test_unknown_template_and_cycles() {
+        let mut config = json!({
+            "hooks": {"check": {"steps": {"a": {"extends": "missing"}}}}
+        });
+        let err = expand(&mut config).unwrap_err().to_string();
+        assert!(err.contains("unknown template 'missing'"), "{err}");
+
+        let mut config = json!({
+            "templates": {"x": {"extends": "y"}, "y": {"extends": "x"}},
+            "hooks": {"check": {"steps": {"a": {"extends": "x"}}}}
+        });
+        let err = expand(&mut config).unwrap_err().to_string();
+        assert!(err.contains("cycle: x -> y -> x"), "{err}");
+    }
+}
@@ -1,9 +1,11 @@
 use indexmap::IndexMap;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Deserialize, Serialize, Default, PartialEq, Eq)]
+#[derive(Debug, Clone, Deserialize, Serialize, Default, PartialEq, Eq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 #[cfg_attr(debug_assertions, serde(deny_unknown_fields))]
+#[schemars(deny_unknown_fields)]
 pub struct StepTest {
     /// One of: "check" or "fix"
     #[serde(default)]
@@ -32,7 +34,7 @@ pub struct StepTest {
     pub tmpdir: Option<bool>,
 }
 
-#[derive(Debug, Default, Clone, Deserialize, Serialize, PartialEq, Eq)]
+#[derive(Debug, Default, Clone, Deserialize, Serialize, PartialEq, Eq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum RunKind {
     #[default]
@@ -40,9 +42,10 @@ pub enum RunKind {
     Fix,
 }
 
-#[derive(Debug, Clone, Deserialize, Serialize, Default, PartialEq, Eq)]
+#[derive(Debug, Clone, Deserialize, Serialize, Default, PartialEq, Eq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 #[cfg_attr(debug_assertions, serde(deny_unknown_fields))]
+#[schemars(deny_unknown_fields)]
 pub struct StepTestExpect {
     #[serde(default)]
     pub code: i32,
@@ -2,7 +2,7 @@ use crate::{
     Result,
     diagnostics::{self, Diagnostic},
     hook::HookContext,
-    step::{CommandEffect, OutputSummary},
+    step::{CommandEffect, OutputSummary, Step},
 };
 use serde::Serialize;
 use std::path::Path;
@@ -317,6 +317,67 @@ pub fn emit_noop_run(
     Ok(())
 }
 
+/// A step's check of a buffer read with `--stdin-filepath`
+pub struct StdinStepRun<'a> {
+    pub step: &'a Step,
+    pub passed: bool,
+    pub duration_ms: u128,
+    pub output: String,
+}
+
+/// Emit the result of checking a buffer with `--stdin-filepath`, listing only
+/// the steps that ran.
+pub fn emit_stdin_run(
+    format: OutputFormat,
+    hook: &str,
+    started_at: String,
+    duration_ms: u128,
+    runs: Vec<StdinStepRun>,
+    failure: Option<String>,
+) -> Result<()> {
+    let steps = runs
+        .into_iter()
+        .map(|run| {
+            let step = run.step;
+            let parsed = step
+                .diagnostic_format
+                .filter(|_| !run.output.is_empty())
+                .map(|diagnostic_format| {
+                    diagnostics::parse(
+                        diagnostic_format,
+                        &step.name,
+                        step.diagnostic_tool.as_deref().unwrap_or(&step.name),
+                        &run.output,
+                    )
+                })
+                .unwrap_or_default();
+            StepResult {
+                name: step.name.clone(),
+                status: if run.passed { "passed" } else { "failed" },
+                duration_ms: run.duration_ms,
+                effects: vec![],
+                diagnostics: parsed.diagnostics,
+                parse_warnings: parsed.warnings,
+                output_kind: Some(step.output_summary.clone()),
+                output: Some(run.output).filter(|output| !output.is_empty()),
+                skip_reason: None,
+            }
+        })
+        .collect();
+    let result = RunResult {
+        schema_version: 1,
+        kind: "run_result",
+        hook: hook.to_string(),
+        status: run_status(failure.as_deref(), false),
+        started_at,
+        duration_ms,
+        failure,
+        reason: None,
+        steps,
+    };
+    emit_result(format, &result)
+}
+
 pub fn emit_error_run(
     format: OutputFormat,
     hook: &str,
@@ -0,0 +1,62 @@
+#!/usr/bin/env bats
+
+setup() {
+    load 'test_helper/common_setup'
+    _common_setup
+    cat <<EOF > hk.pkl
+amends "$PKL_PATH/Config.pkl"
+hooks {
+    ["commit-msg"] {
+        steps {
+            ["subject"] {
+                check = "hk util check-commit-msg --max-subject-length 20 {{commit_msg_file}}"
+            }
+        }
+    }
+    ["check"] {
+        steps {
+            ["noop"] { check = "true" }
+        }
+    }
+    ["pre-push"] {
+        check_commit_msgs = true
+        steps {
+            ["noop"] { check = "true" }
+        }
+    }
+}
+EOF
+    git add hk.pkl
+    git commit -m "init" --no-verify
+    git checkout -b feature
+    echo a > a.txt && git add a.txt && git commit -m "short one" --no-verify
+    echo b > b.txt && git add b.txt && git commit -m "this subject is far too long" --no-verify
+}
+teardown() {
+    _common_teardown
+}
+
+@test "check --check-commit-msgs reports failing commits by sha" {
+    local bad
+    bad=$(git rev-parse --short=12 HEAD)
+
+    run hk check --from-ref main --to-ref HEAD --check-commit-msgs
+    assert_failure
+    assert_output --partial "$bad this subject is far too long"
+    assert_output --partial "[subject-length]"
+    assert_output --partial "commit message checks failed for 1 commit(s): $bad"
+    refute_output --partial "short one:"
+}
+
+@test "check --check-commit-msgs passes when every message is valid" {
+    git commit --amend -m "short two" --no-verify
+
+    run hk check --from-ref main --to-ref HEAD --check-commit-msgs
+    assert_success
+}
+
+@test "pre-push validates pushed commit messages when check_commit_msgs is set" {
+    run bash -c "echo 'refs/heads/feature $(git rev-parse HEAD) refs/heads/feature $(git rev-parse main)' | hk run pre-push origin url"
+    assert_failure
+    assert_output --partial "commit message checks failed for 1 commit(s)"
+}
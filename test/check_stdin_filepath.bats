@@ -0,0 +1,58 @@
+#!/usr/bin/env bats
+
+setup() {
+    load 'test_helper/common_setup'
+    _common_setup
+    # A linter that reports each TODO in gcc format
+    cat <<'EOF' > todo-lint
+#!/usr/bin/env bash
+awk '/TODO/ { print FILENAME ":" FNR ":1: error: found TODO"; bad = 1 } END { exit bad }' "$@"
+EOF
+    chmod +x todo-lint
+    cat <<EOF > hk.pkl
+amends "$PKL_PATH/Config.pkl"
+hooks {
+    ["check"] {
+        steps {
+            ["todo"] {
+                glob = "**/*.txt"
+                check = "$PWD/todo-lint {{files}}"
+                diagnostic_format = "gcc"
+            }
+            ["markdown"] {
+                glob = "*.md"
+                check = "exit 1"
+            }
+        }
+    }
+}
+EOF
+    mkdir -p docs
+    echo "clean" > docs/notes.txt
+    git add -A
+    git commit -m "initial commit"
+}
+
+teardown() {
+    _common_teardown
+}
+
+@test "hk check --stdin-filepath checks the buffer instead of the file" {
+    run bash -c 'printf "one\nTODO two\n" | hk check --stdin-filepath docs/notes.txt'
+    assert_failure
+    assert_output --partial "docs/notes.txt:2:1: error: found TODO"
+    refute_output --partial ".hk-stdin-"
+
+    run bash -c 'printf "clean\n" | hk check --stdin-filepath docs/notes.txt'
+    assert_success
+    run git status --porcelain --ignored
+    assert_output ""
+}
+
+@test "hk check --stdin-filepath reports diagnostics for the virtual path" {
+    run bash -c 'printf "TODO\n" | hk --format json check --stdin-filepath docs/notes.txt 2>/dev/null'
+    assert_failure
+    run jq -r '[.status, (.steps | length), .steps[0].name, .steps[0].diagnostics[0].path, .steps[0].diagnostics[0].range.start.line] | @tsv' <<<"$output"
+    assert_success
+    assert_output $'failed\t1\ttodo\tdocs/notes.txt\t1'
+}
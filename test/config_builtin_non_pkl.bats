@@ -0,0 +1,50 @@
+#!/usr/bin/env bats
+
+setup() {
+    load 'test_helper/common_setup'
+    _common_setup
+}
+teardown() {
+    _common_teardown
+}
+
+@test "hk.toml steps can reference builtins" {
+    cat > hk.toml <<EOF
+[hooks.check.steps.trailing]
+builtin = "trailing_whitespace"
+EOF
+    printf 'x = 1   \n' > a.txt
+    git add a.txt
+
+    run hk check --all
+    assert_failure
+    assert_output --partial "hk util trailing-whitespace"
+}
+
+@test "hk.yaml builtin steps can override fields" {
+    cat > hk.yaml <<EOF
+hooks:
+  check:
+    steps:
+      trailing:
+        builtin: trailing_whitespace
+        glob: ["src/*.txt"]
+EOF
+    mkdir src
+    printf 'x = 1   \n' > a.txt
+    printf 'y = 2\n' > src/b.txt
+    git add a.txt src/b.txt
+
+    run hk check --all
+    assert_success
+}
+
+@test "hk.json rejects unknown builtins" {
+    cat > hk.json <<EOF
+{"hooks": {"check": {"steps": {"lint": {"builtin": "not_a_builtin"}}}}}
+EOF
+
+    run hk check --all
+    assert_failure
+    assert_output --partial 'unknown builtin "not_a_builtin"'
+}
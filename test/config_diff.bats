@@ -0,0 +1,85 @@
+#!/usr/bin/env bats
+
+setup() {
+    load 'test_helper/common_setup'
+    _common_setup
+    cat > hk.pkl <<EOF
+amends "$PKL_PATH/Config.pkl"
+hooks {
+    ["pre-commit"] {
+        steps {
+            ["lint"] { check = "echo lint"; glob = "*.py" }
+            ["old"] { check = "echo old" }
+        }
+    }
+}
+EOF
+    git add -A
+    git commit -qm "initial config"
+    cat > hk.pkl <<EOF
+amends "$PKL_PATH/Config.pkl"
+exclude = "vendor/**"
+hooks {
+    ["pre-commit"] {
+        steps {
+            ["lint"] { check = "echo lint"; glob = "src/*.py" }
+            ["new"] { check = "echo new" }
+        }
+    }
+    ["pre-push"] {
+        steps {
+            ["test"] { check = "echo test" }
+        }
+    }
+}
+EOF
+}
+teardown() {
+    _common_teardown
+}
+
+@test "config diff compares a revision with the working tree" {
+    run hk config diff HEAD
+    assert_success
+    assert_output - <<'EOF'
+~ exclude: (unset) -> "vendor/**"
+~ hook pre-commit
+  ~ lint
+      glob: ["*.py"] -> ["src/*.py"]
+  - old
+  + new
++ hook pre-push
+  + test
+EOF
+    run git worktree list
+    assert_line --index 0 --partial "$TEST_TEMP_DIR"
+    assert_equal "${#lines[@]}" 1
+}
+
+@test "config diff compares two revisions as JSON" {
+    git commit -qam "update config"
+    run hk config diff HEAD~1 HEAD --format json
+    assert_success
+    assert_equal "$(echo "$output" | jq -r '.hooks[0].steps[0] | "\(.name) \(.change) \(.fields[0].field)"')" "lint changed glob"
+    assert_equal "$(echo "$output" | jq -r '.hooks[1] | "\(.name) \(.change)"')" "pre-push added"
+    assert_equal "$(echo "$output" | jq -r '.to')" "HEAD"
+}
+
+@test "config diff reports identical configs" {
+    git commit -qam "update config"
+    run hk config diff HEAD
+    assert_success
+    assert_output --partial "no changes to the evaluated config"
+}
+
+@test "config diff fails on unknown revisions" {
+    run hk config diff not-a-rev
+    assert_failure
+    assert_output --partial "failed to check out not-a-rev"
+}
+
+@test "config diff does not pass revisions to git as options" {
+    run hk config diff -- --lock
+    assert_failure
+    assert_output --partial "failed to check out --lock"
+}
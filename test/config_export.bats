@@ -0,0 +1,60 @@
+#!/usr/bin/env bats
+
+setup() {
+    load 'test_helper/common_setup'
+    _common_setup
+    mkdir -p sub
+    cat > hk.pkl <<EOF
+amends "$PKL_PATH/Config.pkl"
+env { ["FOO"] = "bar" }
+subprojects = List("sub")
+hooks {
+    ["check"] {
+        steps {
+            ["root"] { check = "echo root \$FOO" }
+        }
+    }
+}
+EOF
+    cat > sub/hk.pkl <<EOF
+amends "$PKL_PATH/Config.pkl"
+hooks {
+    ["check"] {
+        steps {
+            ["lint"] { check = "echo lint in \$(basename \$PWD)" }
+        }
+    }
+}
+EOF
+    printf 'x\n' > sub/a.txt
+    git add -A
+}
+teardown() {
+    _common_teardown
+}
+
+@test "config export includes merged subprojects and env" {
+    run hk config export
+    assert_success
+    assert_output --partial '"sub:lint"'
+    assert_output --partial '"dir": "sub"'
+    assert_output --partial '"FOO": "bar"'
+    refute_output --partial 'subprojects'
+    refute_output --partial 'null'
+}
+
+@test "config export output loads back without pkl" {
+    hk config export --output "$TEST_TEMP_DIR/hk.json"
+    hk config export --output "$TEST_TEMP_DIR/hk.toml"
+    run grep -c '^\[hooks\.check\.steps\.[^.]*\]$' "$TEST_TEMP_DIR/hk.toml"
+    assert_output "2"
+
+    run env HK_FILE="$TEST_TEMP_DIR/hk.toml" hk config export
+    assert_success
+    assert_output "$(cat "$TEST_TEMP_DIR/hk.json")"
+
+    run env HK_FILE="$TEST_TEMP_DIR/hk.json" hk check --all
+    assert_success
+    assert_output --partial "root bar"
+    assert_output --partial "lint in sub"
+}
@@ -0,0 +1,39 @@
+#!/usr/bin/env bats
+
+setup() {
+    load 'test_helper/common_setup'
+    _common_setup
+}
+teardown() {
+    _common_teardown
+}
+
+@test "config schema describes steps" {
+    run hk config schema
+    assert_success
+    echo "$output" | jq -e '."$defs".Step.properties.check' >/dev/null
+    echo "$output" | jq -e '."$defs".StepGroup.properties.steps' >/dev/null
+    echo "$output" | jq -e '.properties.hooks' >/dev/null
+}
+
+@test "config schema --output writes the schema" {
+    hk config schema --output hk.schema.json
+    assert_file_exists hk.schema.json
+    run jq -r '.title' hk.schema.json
+    assert_output "hk config"
+
+    hk config schema --user --output user.schema.json
+    run jq -r '.title' user.schema.json
+    assert_output "hk user config"
+}
+
+@test "hk.json may reference its schema" {
+    cat > hk.json <<EOF
+{
+  "\$schema": "hk.schema.json",
+  "hooks": {"check": {"steps": {"hello": {"check": "echo hello"}}}}
+}
+EOF
+    run hk check --all
+    assert_success
+}
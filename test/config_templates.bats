@@ -0,0 +1,59 @@
+#!/usr/bin/env bats
+
+setup() {
+    load 'test_helper/common_setup'
+    _common_setup
+}
+teardown() {
+    _common_teardown
+}
+
+@test "hk.toml steps extend templates" {
+    cat > hk.toml <<EOF
+[templates.echo]
+glob = ["*.txt"]
+check = "echo template {{files}}"
+
+[hooks.check.steps.a]
+extends = "echo"
+check = "echo override {{files}}"
+EOF
+    touch a.txt
+    git add a.txt
+
+    run hk check --all
+    assert_success
+    assert_output --partial "override a.txt"
+    refute_output --partial "template a.txt"
+}
+
+@test "hk.yaml matrix steps appear in the plan" {
+    cat > hk.yaml <<EOF
+hooks:
+  check:
+    steps:
+      lint:
+        check: "echo linting"
+        matrix:
+          dir: ["a", "b"]
+EOF
+    mkdir a b
+    touch a/x.txt b/y.txt
+    git add .
+
+    run hk check --all --plan
+    assert_success
+    assert_output --partial "lint[a]"
+    assert_output --partial "lint[b]"
+}
+
+@test "unknown templates are reported" {
+    cat > hk.toml <<EOF
+[hooks.check.steps.a]
+extends = "missing"
+EOF
+
+    run hk validate
+    assert_failure
+    assert_output --partial "unknown template 'missing'"
+}
@@ -0,0 +1,73 @@
+#!/usr/bin/env bats
+
+setup() {
+    load 'test_helper/common_setup'
+    _common_setup
+    mkdir -p "$TEST_TEMP_DIR/srv"
+    cat > "$TEST_TEMP_DIR/srv/base.pkl" <<EOF
+import "other.pkl"
+greeting = "hello " + other.name
+EOF
+    echo 'name = "world"' > "$TEST_TEMP_DIR/srv/other.pkl"
+    PORT=$((20000 + RANDOM % 10000))
+    python3 -m http.server "$PORT" --bind 127.0.0.1 --directory "$TEST_TEMP_DIR/srv" >/dev/null 2>&1 &
+    SERVER_PID=$!
+    for _ in $(seq 50); do
+        curl -sf "http://127.0.0.1:$PORT/base.pkl" >/dev/null && break
+        sleep 0.1
+    done
+    cat > hk.pkl <<EOF
+amends "$PKL_PATH/Config.pkl"
+import "http://127.0.0.1:$PORT/base.pkl"
+hooks {
+    ["check"] {
+        steps {
+            ["greet"] { check = "echo \(base.greeting)" }
+        }
+    }
+}
+EOF
+    touch a.txt
+    git add -A
+}
+teardown() {
+    kill "$SERVER_PID" 2>/dev/null || true
+    _common_teardown
+}
+
+# The vendored copy of a served file: its URL path plus a short URL hash
+vendored() {
+    local hash
+    hash=$(printf '%s' "http://127.0.0.1:$PORT/$1.pkl" | sha256sum | cut -c1-8)
+    echo ".hk/pkl/127.0.0.1_$PORT/$1-$hash.pkl"
+}
+
+@test "config vendor writes remote imports and a lockfile" {
+    run hk config vendor
+    assert_success
+    assert_output --partial "vendored 2 remote modules into ./.hk/pkl"
+    assert_file_exists "$(vendored base)"
+    assert_file_exists "$(vendored other)"
+    run cat .hk/pkl/lock.json
+    assert_output --partial "\"http://127.0.0.1:$PORT/other.pkl\""
+    assert_output --partial "\"sha256\": \"$(sha256sum "$TEST_TEMP_DIR/srv/other.pkl" | cut -d' ' -f1)\""
+}
+
+@test "vendored imports load without network access" {
+    hk config vendor
+    kill "$SERVER_PID"
+    wait "$SERVER_PID" 2>/dev/null || true
+
+    run hk check --all
+    assert_success
+    assert_output --partial "hello world"
+}
+
+@test "vendored imports fail on checksum mismatch" {
+    hk config vendor
+    echo 'name = "mallory"' > "$(vendored other)"
+
+    run hk check --all
+    assert_failure
+    assert_output --partial "checksum mismatch for vendored http://127.0.0.1:$PORT/other.pkl"
+}
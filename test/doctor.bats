@@ -0,0 +1,80 @@
+#!/usr/bin/env bats
+
+setup() {
+    load 'test_helper/common_setup'
+    _common_setup
+}
+
+teardown() {
+    _common_teardown
+}
+
+@test "hk doctor passes after hk install" {
+    cat <<EOF > hk.pkl
+amends "$PKL_PATH/Config.pkl"
+hooks {
+    ["pre-commit"] {
+        steps {
+            ["cat"] { check = "cat {{files}}" }
+        }
+    }
+}
+EOF
+    hk install
+    run hk doctor
+    assert_success
+    assert_output --partial "hook:pre-commit: installed"
+    assert_output --partial "executables: 1 executable(s) found"
+}
+
+@test "hk doctor reports missing hooks and executables as json" {
+    cat <<EOF > hk.pkl
+amends "$PKL_PATH/Config.pkl"
+min_hk_version = "0.1.0"
+hooks {
+    ["pre-commit"] {
+        steps {
+            ["missing"] { check = "definitely-not-installed-tool {{files}}" }
+            ["builtin"] { check = "test -f {{files}}" }
+        }
+    }
+}
+EOF
+    run bash -c 'hk doctor --format json 2>/dev/null'
+    assert_failure
+    jq -e '.checks[] | select(.name == "hook:pre-commit") | .status == "error"' <<<"$output"
+    jq -e '.checks[] | select(.name == "min_hk_version") | .status == "ok"' <<<"$output"
+    jq -e '.checks[] | select(.name == "executables") | .details.missing == [{"program": "definitely-not-installed-tool", "steps": ["missing"]}]' <<<"$output"
+}
+
+@test "hk doctor reports core.hooksPath bypassing hook scripts" {
+    cat <<EOF > hk.pkl
+amends "$PKL_PATH/Config.pkl"
+hooks {
+    ["pre-commit"] {
+        steps {
+            ["cat"] { check = "cat {{files}}" }
+        }
+    }
+}
+EOF
+    hk install --legacy
+    git config core.hooksPath .husky
+    run hk doctor
+    assert_failure
+    assert_output --partial "core.hooksPath: set to .husky; hk hook scripts are bypassed"
+}
+
+@test "hk doctor reports a stale index.lock" {
+    touch .git/index.lock
+    run bash -c 'hk doctor --format json 2>/dev/null'
+    jq -e '.checks[] | select(.name == "index.lock") | .status == "warn"' <<<"$output"
+}
+
+@test "hk doctor reports config errors" {
+    # A syntax error, so loading fails with every Pkl backend
+    printf 'amends "%s/Config.pkl"\nhooks {\n' "$PKL_PATH" > hk.pkl
+    run hk doctor
+    assert_failure
+    assert_output --partial "hk.pkl failed to load"
+}
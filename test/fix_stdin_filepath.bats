@@ -0,0 +1,124 @@
+#!/usr/bin/env bats
+
+setup() {
+    load 'test_helper/common_setup'
+    _common_setup
+}
+
+teardown() {
+    _common_teardown
+}
+
+@test "hk fix --stdin-filepath chains matching fixers in depends order" {
+    cat <<EOF > hk.pkl
+amends "$PKL_PATH/Config.pkl"
+hooks {
+    ["fix"] {
+        steps {
+            ["suffix"] {
+                glob = "*.txt"
+                depends = List("upper")
+                fix = "sed -i.bak 's/\$/!/' {{files}}"
+            }
+            ["upper"] {
+                glob = "*.txt"
+                fix = "tr a-z A-Z < {{files}} > {{files}}.tmp && mv {{files}}.tmp {{files}}"
+            }
+            ["markdown"] {
+                glob = "*.md"
+                fix = "echo wrong > {{files}}"
+            }
+        }
+    }
+}
+EOF
+    echo "on disk" > notes.txt
+    git add -A
+    git commit -m "initial commit"
+
+    run bash -c 'printf "hello\n" | hk fix --stdin-filepath notes.txt'
+    assert_success
+    assert_output "HELLO!"
+    assert_equal "$(cat notes.txt)" "on disk"
+    run git status --porcelain
+    assert_output ""
+}
+
+@test "hk fix --stdin-filepath passes through unmatched files" {
+    cat <<EOF > hk.pkl
+amends "$PKL_PATH/Config.pkl"
+hooks {
+    ["fix"] {
+        steps {
+            ["upper"] {
+                glob = "*.txt"
+                fix = "tr a-z A-Z < {{files}} > {{files}}.tmp && mv {{files}}.tmp {{files}}"
+            }
+        }
+    }
+}
+EOF
+    run bash -c 'printf "hello\n" | hk fix --stdin-filepath src/main.rs'
+    assert_success
+    assert_output "hello"
+    assert [ ! -e src/main.rs ]
+}
+
+@test "hk fix --stdin-filepath fails when a fixer fails" {
+    cat <<EOF > hk.pkl
+amends "$PKL_PATH/Config.pkl"
+hooks {
+    ["fix"] {
+        steps {
+            ["broken"] {
+                glob = "*.txt"
+                fix = "echo cannot parse >&2; exit 1"
+            }
+        }
+    }
+}
+EOF
+    run bash -c 'printf "hello\n" | hk fix --stdin-filepath a.txt'
+    assert_failure
+    assert_output --partial "cannot parse"
+}
+
+@test "hk fix --stdin-filepath finds the project's formatter config" {
+    # A formatter that, like prettier or rustfmt, reads its config from the
+    # nearest .suffixrc above the file it formats
+    cat <<'EOF' > suffix-fmt
+#!/usr/bin/env bash
+dir=$(cd "$(dirname "$1")" && pwd)
+while [ "$dir" != / ] && [ ! -f "$dir/.suffixrc" ]; do dir=$(dirname "$dir"); done
+suffix=$(cat "$dir/.suffixrc" 2>/dev/null || echo "?")
+sed -i.bak "s/\$/$suffix/" "$1" && rm "$1.bak"
+EOF
+    chmod +x suffix-fmt
+    echo "!" > .suffixrc
+    cat <<EOF > hk.pkl
+amends "$PKL_PATH/Config.pkl"
+hooks {
+    ["fix"] {
+        steps {
+            ["suffix"] {
+                glob = "**/*.txt"
+                fix = "$PWD/suffix-fmt {{files}}"
+            }
+        }
+    }
+}
+EOF
+    mkdir -p docs
+    echo "hello" > docs/notes.txt
+    git add -A
+    git commit -m "initial commit"
+
+    run bash -c 'printf "hello\n" | hk fix --stdin-filepath docs/notes.txt'
+    assert_success
+    assert_output "hello!"
+    run git status --porcelain --ignored
+    assert_output ""
+
+    hk fix --all
+    assert_equal "$(cat docs/notes.txt)" "hello!"
+}
@@ -114,3 +114,74 @@ teardown() {
     assert_file_contains hk.pkl "Builtins.prettier"
     assert_file_contains hk.pkl "Builtins.cargo_clippy"
 }
+
+@test "hk init scopes tools detected in subdirectories with dir" {
+    mkdir -p apps/web services/api infra
+    echo '{"name": "web"}' > apps/web/package.json
+    echo 'module api' > services/api/go.mod
+    touch infra/main.tf
+    run hk init
+    assert_success
+    assert_output --partial "apps/web: prettier (apps/web/package.json)"
+    assert_output --partial "services/api: go_fmt (services/api/go.mod)"
+    assert_file_contains hk.pkl '"apps/web:prettier"] = (Builtins.prettier) { dir = "apps/web" }'
+    assert_file_contains hk.pkl '"services/api:go_fmt"] = (Builtins.go_fmt) { dir = "services/api" }'
+}
+
+@test "hk init skips ignored subdirectories" {
+    mkdir -p vendor/lib
+    echo 'vendor/' > .gitignore
+    echo 'module lib' > vendor/lib/go.mod
+    run hk init
+    assert_success
+    assert_file_not_contains hk.pkl "go_fmt"
+}
+
+@test "hk init --subprojects writes an hk.pkl per subproject" {
+    mkdir -p apps/web services/api
+    echo '{"name": "web"}' > apps/web/package.json
+    echo 'module api' > services/api/go.mod
+    run hk init --subprojects
+    assert_success
+    assert_file_contains hk.pkl 'subprojects = List("apps/web", "services/api")'
+    assert_file_contains apps/web/hk.pkl '"prettier"] = Builtins.prettier'
+    assert_file_contains services/api/hk.pkl '"go_fmt"] = Builtins.go_fmt'
+}
+
+@test "hk init prefers biome when biome.json is present" {
+    echo '{"devDependencies": {"@biomejs/biome": "^1.0.0", "prettier": "^3.0.0"}}' > package.json
+    echo '{}' > biome.json
+    run hk init
+    assert_success
+    assert_file_contains hk.pkl '"biome"] = Builtins.biome'
+    assert_file_not_contains hk.pkl "Builtins.prettier"
+}
+
+@test "hk init carries legacy eslint config and ignore files into steps" {
+    echo '{"devDependencies": {"eslint": "^8.0.0"}}' > package.json
+    echo '{}' > .eslintrc.json
+    echo 'dist/' > .eslintignore
+    echo '{}' > .prettierrc
+    echo '/coverage' > .prettierignore
+    run hk init
+    assert_success
+    assert_file_contains hk.pkl 'ESLINT_USE_FLAT_CONFIG"] = "false" }'
+    assert_file_contains hk.pkl 'exclude = List("\*\*/dist/\*\*")'
+    assert_file_contains hk.pkl 'exclude = List("coverage", "coverage/\*\*")'
+}
+
+@test "hk init uses black and isort when configured in pyproject.toml" {
+    printf '[tool.black]\nline-length = 100\n\n[tool.isort]\nprofile = "black"\n' > pyproject.toml
+    run hk init
+    assert_success
+    assert_file_contains hk.pkl "Builtins.black"
+    assert_file_contains hk.pkl "Builtins.isort"
+    assert_file_not_contains hk.pkl "Builtins.ruff"
+}
+
+@test "hk init adds editorconfig-checker for .editorconfig" {
+    printf 'root = true\n' > .editorconfig
+    run hk init
+    assert_success
+    assert_file_contains hk.pkl '"editorconfig-checker"] = Builtins.editorconfig_checker'
+}
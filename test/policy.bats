@@ -0,0 +1,131 @@
+#!/usr/bin/env bats
+
+setup() {
+    load 'test_helper/common_setup'
+    _common_setup
+    cat > "$TEST_TEMP_DIR/policy.toml" <<EOF
+unskippable_steps = ["lint"]
+
+[hooks.check.steps.secrets]
+check = "echo scanning secrets"
+EOF
+    export HK_POLICY="$TEST_TEMP_DIR/policy.toml"
+}
+teardown() {
+    _common_teardown
+}
+
+@test "policy steps are added to project hooks" {
+    cat > hk.toml <<EOF
+[hooks.check.steps.lint]
+check = "echo linting"
+EOF
+    touch a.txt
+    git add a.txt
+
+    run hk check --all
+    assert_success
+    assert_output --partial "scanning secrets"
+    assert_output --partial "linting"
+}
+
+@test "policy steps replace project steps of the same name" {
+    cat > hk.toml <<EOF
+[hooks.check.steps.secrets]
+check = "echo project secrets"
+EOF
+    touch a.txt
+    git add a.txt
+
+    run hk check --all
+    assert_success
+    assert_output --partial "scanning secrets"
+    refute_output --partial "project secrets"
+}
+
+@test "required steps cannot be skipped" {
+    cat > hk.toml <<EOF
+[hooks.check.steps.lint]
+check = "echo linting"
+EOF
+    touch a.txt
+    git add a.txt
+
+    HK_SKIP_STEPS=lint,secrets run hk check --all --skip-step lint
+    assert_success
+    assert_output --partial "lint: not skipping, required by policy"
+    assert_output --partial "linting"
+    assert_output --partial "scanning secrets"
+}
+
+@test "skipped hooks still run required steps" {
+    cat > hk.toml <<EOF
+[hooks.check.steps.lint]
+check = "echo linting"
+
+[hooks.check.steps.fmt]
+check = "echo formatting"
+EOF
+    touch a.txt
+    git add a.txt
+
+    HK_SKIP_HOOK=check run hk check --all
+    assert_success
+    assert_output --partial "check: not skipping lint, required by policy"
+    assert_output --partial "check: not skipping secrets, required by policy"
+    assert_output --partial "linting"
+    assert_output --partial "scanning secrets"
+    refute_output --partial "formatting"
+}
+
+@test "policy pins min_hk_version" {
+    cat > "$HK_POLICY" <<EOF
+min_hk_version = "999.0.0"
+EOF
+    cat > hk.toml <<EOF
+[hooks.check.steps.lint]
+check = "echo linting"
+EOF
+
+    run hk check --all
+    assert_failure
+    assert_output --partial "minimum required version 999.0.0"
+}
+
+@test "policy min_hk_version is checked before the project config is read" {
+    cat > "$HK_POLICY" <<EOF
+min_hk_version = "999.0.0"
+EOF
+    echo 'not [valid toml' > hk.toml
+
+    run hk check --all
+    assert_failure
+    assert_output --partial "minimum required version 999.0.0"
+}
+
+@test "missing HK_POLICY file is an error" {
+    cat > hk.toml <<EOF
+[hooks.check.steps.lint]
+check = "echo linting"
+EOF
+    HK_POLICY="$TEST_TEMP_DIR/missing.toml" run hk check --all
+    assert_failure
+    assert_output --partial "policy file not found"
+}
+
+@test "config sources and explain show the policy" {
+    cat > hk.toml <<EOF
+[hooks.check.steps.lint]
+check = "echo linting"
+EOF
+
+    run hk config sources
+    assert_success
+    assert_output --partial "Org policy: "
+    assert_output --partial "policy.toml"
+
+    run hk config explain skip_steps
+    assert_success
+    assert_output --partial "ORG POLICY"
+    assert_output --partial '"lint", "secrets"'
+}
@@ -0,0 +1,63 @@
+#!/usr/bin/env bats
+
+setup() {
+    load 'test_helper/common_setup'
+    _common_setup
+}
+teardown() {
+    _common_teardown
+}
+
+@test "util check-added-large-files - checks the staged blob, not the work tree" {
+    echo "small" > data.bin
+    git add data.bin
+    head -c 3000 /dev/zero > data.bin
+
+    run hk util check-added-large-files --maxkb 1 data.bin
+    assert_success
+
+    git add data.bin
+    run hk util check-added-large-files --maxkb 1 data.bin
+    assert_failure
+    assert_output --partial 'data.bin: 3 KB exceeds the 1 KB limit; consider tracking it with `git lfs track "*.bin"`'
+}
+
+@test "util check-added-large-files - skips files already large in HEAD unless --enforce-all" {
+    head -c 3000 /dev/zero > old.bin
+    git add old.bin
+    git commit -m "add old.bin" --no-verify
+    head -c 4000 /dev/zero > old.bin
+    git add old.bin
+
+    run hk util check-added-large-files --maxkb 1 old.bin
+    assert_success
+
+    run hk util check-added-large-files --maxkb 1 --enforce-all old.bin
+    assert_failure
+    assert_output --partial "old.bin: 4 KB exceeds the 1 KB limit"
+}
+
+@test "util check-added-large-files - applies per-glob limits" {
+    mkdir -p assets
+    head -c 3000 /dev/zero > assets/logo.png
+    head -c 3000 /dev/zero > notes.txt
+    git add assets notes.txt
+
+    run hk util check-added-large-files --maxkb 1 --limit 'assets/**=10' assets/logo.png notes.txt
+    assert_failure
+    assert_output --partial "notes.txt: 3 KB exceeds the 1 KB limit"
+    refute_output --partial "logo.png"
+}
+
+@test "util check-added-large-files - reports lfs files staged without lfs" {
+    echo '*.psd filter=lfs diff=lfs merge=lfs -text' > .gitattributes
+    head -c 3000 /dev/zero > art.psd
+
+    run hk util check-added-large-files --maxkb 1 art.psd
+    assert_success
+
+    git -c filter.lfs.clean=cat -c filter.lfs.required=false add art.psd
+    run hk util check-added-large-files --maxkb 1 art.psd
+    assert_failure
+    assert_output --partial "it matches filter=lfs in .gitattributes but was not staged as an LFS pointer"
+}
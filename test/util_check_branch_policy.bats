@@ -0,0 +1,62 @@
+#!/usr/bin/env bats
+
+setup() {
+    load 'test_helper/common_setup'
+    _common_setup
+    git init -q --bare "$TEST_TEMP_DIR/remote.git"
+    git remote add origin "$TEST_TEMP_DIR/remote.git"
+    git commit -q --allow-empty -m "init" --no-verify
+    git push -q origin HEAD:refs/heads/main
+    git fetch -q origin
+    ZERO=0000000000000000000000000000000000000000
+}
+teardown() {
+    _common_teardown
+}
+
+@test "util check-branch-policy - validates the current branch name" {
+    git checkout -q -b feat/login
+    run hk util check-branch-policy --branch-pattern 'feat/[a-z-]+'
+    assert_success
+
+    git checkout -q -b Login
+    run hk util check-branch-policy --branch-pattern 'feat/[a-z-]+' --branch-pattern main
+    assert_failure
+    assert_output --partial "branch name 'Login' does not match any of: feat/[a-z-]+, main"
+}
+
+@test "util check-branch-policy - rejects force-pushes and deletions of matching refs" {
+    git commit -q --amend --allow-empty -m "rewritten" --no-verify
+    local head remote
+    head=$(git rev-parse HEAD)
+    remote=$(git rev-parse origin/main)
+
+    run bash -c "echo 'refs/heads/main $head refs/heads/main $remote' | hk util check-branch-policy --stdin --no-force main"
+    assert_failure
+    assert_output --partial "refs/heads/main: force-pushing 'main' is not allowed"
+
+    run bash -c "echo '(delete) $ZERO refs/heads/main $remote' | hk util check-branch-policy --stdin --no-delete main"
+    assert_failure
+    assert_output --partial "refs/heads/main: deleting 'main' is not allowed"
+
+    run bash -c "echo '(delete) $ZERO refs/heads/old $remote' | hk util check-branch-policy --stdin --no-delete main"
+    assert_success
+}
+
+@test "util check-branch-policy - blocks pushes to protected refs" {
+    run bash -c "echo 'refs/heads/x $(git rev-parse HEAD) refs/heads/release/1.0 $ZERO' | hk util check-branch-policy --stdin --protect 'release/*'"
+    assert_failure
+    assert_output --partial "refs/heads/release/1.0: 'release/1.0' is protected"
+}
+
+@test "util check-branch-policy - enforces linear history" {
+    git checkout -q -b side
+    git commit -q --allow-empty -m "side" --no-verify
+    git checkout -q -b feat/merge origin/main
+    git commit -q --allow-empty -m "work" --no-verify
+    git merge -q --no-ff side -m "Merge side" --no-verify
+
+    run bash -c "echo 'refs/heads/feat/merge $(git rev-parse HEAD) refs/heads/feat/merge $ZERO' | hk util check-branch-policy --stdin --linear"
+    assert_failure
+    assert_output --partial '"Merge side" breaks linear history'
+}
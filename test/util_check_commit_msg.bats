@@ -0,0 +1,76 @@
+#!/usr/bin/env bats
+
+setup() {
+    load 'test_helper/common_setup'
+    _common_setup
+}
+teardown() {
+    _common_teardown
+}
+
+@test "util check-commit-msg - reports policy violations" {
+    printf 'feat(web): add a page\nno blank line\n# Please enter the commit message\n' > msg
+
+    run hk util check-commit-msg --conventional --allowed-scopes cli,core \
+        --require-trailer Signed-off-by,Change-Id --issue-pattern '#[0-9]+' msg
+    assert_failure
+    assert_output --partial "msg:1:1: error: scope 'web' is not one of: cli, core [scope]"
+    assert_output --partial "msg:1:1: error: no issue reference matching"
+    assert_output --partial "msg:2:1: error: the subject must be followed by a blank line [blank-line]"
+    assert_output --partial 'missing `Signed-off-by` trailer [trailer]'
+    assert_output --partial 'missing `Change-Id` trailer [trailer]'
+}
+
+@test "util check-commit-msg - validates breaking change footers" {
+    printf 'feat!: drop the old flag\n\nBreaking change: the flag is gone\n' > msg
+
+    run hk util check-commit-msg --conventional msg
+    assert_failure
+    assert_output --partial 'must be written as `BREAKING CHANGE` [breaking-change]'
+
+    printf 'feat!: drop the old flag\n\nBREAKING CHANGE: the flag is gone\n' > msg
+    run hk util check-commit-msg --conventional msg
+    assert_success
+}
+
+@test "util check-commit-msg - fix wraps the body and signs off" {
+    git config user.name "Test User"
+    git config user.email "test@example.com"
+    printf 'fix: handle empty input\nThe parser used to panic when the input was empty, now it returns an error.\n' > msg
+
+    run hk util check-commit-msg --fix --max-body-line-length 40 --require-trailer Signed-off-by msg
+    assert_success
+    run cat msg
+    assert_output "fix: handle empty input
+
+The parser used to panic when the input
+was empty, now it returns an error.
+
+Signed-off-by: Test User <test@example.com>"
+}
+
+@test "util check-commit-msg - runs from the commit-msg hook" {
+    cat <<EOF > hk.pkl
+amends "$PKL_PATH/Config.pkl"
+import "$PKL_PATH/Builtins.pkl"
+hooks {
+    ["commit-msg"] {
+        steps {
+            ["commit-msg"] = (Builtins.check_commit_msg) {
+                check = "hk util check-commit-msg --max-subject-length 20 {{commit_msg_file}}"
+            }
+        }
+    }
+}
+EOF
+    hk install
+    echo "test" > test.txt
+    git add test.txt
+
+    run git commit -m "this subject is much too long for the policy"
+    assert_failure
+    assert_output --partial "[subject-length]"
+
+    run git commit -m "short subject"
+    assert_success
+}
@@ -0,0 +1,44 @@
+#!/usr/bin/env bats
+
+setup() {
+    load 'test_helper/common_setup'
+    _common_setup
+}
+teardown() {
+    _common_teardown
+}
+
+@test "util check-spelling - reports misspellings in identifiers and comments" {
+    printf 'fn recieveData() {\n    // teh loader\n    let s = "teh";\n}\n' > main.rs
+    printf 'Data is seperated by commas.\n' > README.md
+
+    run hk util check-spelling main.rs README.md
+    assert_failure
+    assert_output "main.rs:1:4: \`recieve\` should be \`receive\`
+main.rs:2:8: \`teh\` should be \`the\`
+README.md:1:9: \`seperated\` should be \`separated\`"
+}
+
+@test "util check-spelling - project dictionary allows words and adds corrections" {
+    printf '# project words\nteh\nrecieveData\nfoobr->foobar\n' > .hk-words.txt
+    printf 'fn recieveData() {}\n// teh foobr\n' > main.rs
+
+    run hk util check-spelling main.rs
+    assert_failure
+    assert_output "main.rs:2:8: \`foobr\` should be \`foobar\`"
+
+    run hk util check-spelling --allow foobr main.rs
+    assert_success
+}
+
+@test "util check-spelling - fix applies unambiguous corrections" {
+    printf '# Teh README\n\nCheck wether the TEH value is seperated.\n' > README.md
+
+    run hk util check-spelling --fix README.md
+    assert_failure
+    assert_output "README.md:3:7: \`wether\` should be one of \`whether\`, \`weather\`"
+    run cat README.md
+    assert_output "# The README
+
+Check wether the THE value is separated."
+}
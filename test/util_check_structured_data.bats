@@ -0,0 +1,99 @@
+#!/usr/bin/env bats
+
+setup() {
+    load 'test_helper/common_setup'
+    _common_setup
+}
+teardown() {
+    _common_teardown
+}
+
+@test "util check-json - reports syntax errors with line and column" {
+    printf '{\n  "a": 1,\n}\n' > bad.json
+    echo '{"a": 1}' > good.json
+
+    run hk util check-json good.json bad.json
+    assert_failure
+    assert_output --partial "bad.json:3:1: error: trailing comma [syntax]"
+    refute_output --partial "good.json"
+}
+
+@test "util check-json - rejects duplicate keys" {
+    printf '{\n  "a": 1,\n  "a": 2\n}\n' > dup.json
+
+    run hk util check-json dup.json
+    assert_failure
+    assert_output --partial 'dup.json:3:3:'
+    assert_output --partial 'duplicate key "a" [duplicate-key]'
+}
+
+@test "util check-yaml - rejects duplicate keys at the repeated key" {
+    printf 'a: 1\nb:\n  c: 1\n  c: 2\n' > dup.yaml
+
+    run hk util check-yaml dup.yaml
+    assert_failure
+    assert_output --partial 'dup.yaml:4:3: error: b: duplicate key "c" [duplicate-key]'
+}
+
+@test "util check-yaml - multiple documents require a flag" {
+    printf 'a: 1\n---\nb: 2\n' > multi.yaml
+
+    run hk util check-yaml multi.yaml
+    assert_failure
+    assert_output --partial "multi.yaml:2:1:"
+    assert_output --partial "[multiple-documents]"
+
+    run hk util check-yaml --allow-multiple-documents multi.yaml
+    assert_success
+    refute_output
+}
+
+@test "util check-yaml - custom tags require a flag" {
+    printf 'Bucket: !Ref MyBucket\n' > template.yaml
+
+    run hk util check-yaml template.yaml
+    assert_failure
+    assert_output --partial "template.yaml:1:9: error: Bucket: unknown tag !Ref [custom-tag]"
+
+    run hk util check-yaml --allow-custom-tags template.yaml
+    assert_success
+}
+
+@test "util check-toml - reports duplicate keys" {
+    printf 'a = 1\nb = 2\na = 3\n' > dup.toml
+
+    run hk util check-toml dup.toml
+    assert_failure
+    assert_output --partial "dup.toml:3:1: error:"
+    assert_output --partial "[duplicate-key]"
+}
+
+@test "util check-xml - reports mismatched tags" {
+    printf '<note>\n  <to>x</from>\n</note>\n' > bad.xml
+    printf '<?xml version="1.0"?>\n<note/>\n' > good.xml
+
+    run hk util check-xml good.xml bad.xml
+    assert_failure
+    assert_output --partial "bad.xml:2:"
+    refute_output --partial "good.xml"
+}
+
+@test "util check-json - diagnostics are parsed as gcc" {
+    cat <<EOF > hk.pkl
+amends "$PKL_PATH/Config.pkl"
+import "$PKL_PATH/Builtins.pkl"
+hooks {
+    ["check"] {
+        steps {
+            ["check-json"] = Builtins.check_json
+        }
+    }
+}
+EOF
+    printf '{"a": 1, "a": 2}\n' > dup.json
+
+    run bash -c 'hk check --format json dup.json 2>/dev/null'
+    assert_failure
+    jq -e '.steps[] | select(.name == "check-json") | .diagnostics[0].rule == "duplicate-key"' <<<"$output"
+    jq -e '.steps[] | select(.name == "check-json") | .diagnostics[0].path == "dup.json"' <<<"$output"
+}
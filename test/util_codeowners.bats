@@ -0,0 +1,77 @@
+#!/usr/bin/env bats
+
+setup() {
+    load 'test_helper/common_setup'
+    _common_setup
+    mkdir -p .github src docs
+    printf 'fn main() {}\n' > src/main.rs
+    printf '# Docs\n' > docs/index.md
+    printf '* @acme/core\n*.md @acme/docs\n/src/ @acme/security dev@example.com\n' > .github/CODEOWNERS
+}
+teardown() {
+    _common_teardown
+}
+
+@test "util check-codeowners - accepts a valid file" {
+    run hk util check-codeowners
+    assert_success
+}
+
+@test "util check-codeowners - reports invalid and stale entries" {
+    printf '* @acme/core\n/build/ @acme/ops\n*.rs not-an-owner\n!*.md @acme/docs\n' > .github/CODEOWNERS
+
+    run hk util check-codeowners
+    assert_failure
+    assert_output --partial ".github/CODEOWNERS:2:1: error: pattern \`/build/\` does not match any files"
+    assert_output --partial ".github/CODEOWNERS:3:1: error: invalid owner \`not-an-owner\`, expected @user, @org/team or an email address"
+    assert_output --partial ".github/CODEOWNERS:4:1: error: invalid pattern \`!*.md\`: negation is not supported"
+}
+
+@test "util check-codeowners - works from a subdirectory" {
+    printf '* @acme/core\n/build/ @acme/ops\n' > .github/CODEOWNERS
+    cd src
+
+    run hk util check-codeowners
+    assert_failure
+    assert_output --partial ".github/CODEOWNERS:2:1: error: pattern \`/build/\` does not match any files"
+}
+
+@test "util codeowners - groups files by owner" {
+    printf 'notes\n' > notes.txt
+
+    run hk util codeowners --show-files src/main.rs docs/index.md notes.txt
+    assert_success
+    assert_output "@acme/security: 1 file
+  src/main.rs
+dev@example.com: 1 file
+  src/main.rs
+@acme/docs: 1 file
+  docs/index.md
+@acme/core: 1 file
+  notes.txt"
+}
+
+@test "codeowners - conditions can route steps by owner" {
+    cat <<EOF > hk.pkl
+amends "$PKL_PATH/Config.pkl"
+hooks {
+    ["check"] {
+        steps {
+            ["security"] {
+                condition = "'@acme/security' in owners"
+                check = "echo security review: {{ codeowners['@acme/security'] | join(sep=' ') }}"
+            }
+            ["ops"] {
+                condition = "'@acme/ops' in owners"
+                check = "echo ops review"
+            }
+        }
+    }
+}
+EOF
+    git add -A
+    run hk check --all
+    assert_success
+    assert_output --partial "security review: src/main.rs"
+    refute_output --partial "ops review"
+}
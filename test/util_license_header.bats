@@ -0,0 +1,49 @@
+#!/usr/bin/env bats
+
+setup() {
+    load 'test_helper/common_setup'
+    _common_setup
+    printf 'Copyright {{year}} {{author}}\nSPDX-License-Identifier: MIT\n' > .license-header
+}
+teardown() {
+    _common_teardown
+}
+
+@test "util license-header - reports missing and outdated headers" {
+    printf 'fn main() {}\n' > main.rs
+    printf '# Copyright 2020 Someone Else\n# SPDX-License-Identifier: MIT\nprint(1)\n' > app.py
+    printf 'plain text\n' > notes.txt
+
+    run hk util license-header --template-file .license-header --author "Acme Inc." main.rs app.py notes.txt
+    assert_failure
+    assert_output "main.rs: missing license header
+app.py: outdated license header"
+}
+
+@test "util license-header - fix keeps shebangs and encoding lines" {
+    printf '#!/usr/bin/env python\n# -*- coding: utf-8 -*-\nprint(1)\n' > tool.py
+
+    run hk util license-header --template-file .license-header --author "Acme Inc." --year 2026 --fix tool.py
+    assert_success
+    run cat tool.py
+    assert_output "#!/usr/bin/env python
+# -*- coding: utf-8 -*-
+# Copyright 2026 Acme Inc.
+# SPDX-License-Identifier: MIT
+
+print(1)"
+
+    run hk util license-header --template-file .license-header --author "Acme Inc." --year 2026 tool.py
+    assert_success
+}
+
+@test "util license-header - update-year extends the copyright range" {
+    printf '/*\n * Copyright 2020 Acme Inc.\n * SPDX-License-Identifier: MIT\n */\nbody {}\n' > site.css
+
+    run hk util license-header --template-file .license-header --author "Acme Inc." --year 2026 site.css
+    assert_success
+
+    run hk util license-header --template-file .license-header --author "Acme Inc." --year 2026 --update-year --diff site.css
+    assert_failure
+    assert_output --partial "+ * Copyright 2020-2026 Acme Inc."
+}
@@ -0,0 +1,91 @@
+#!/usr/bin/env bats
+
+setup() {
+    load 'test_helper/common_setup'
+    _common_setup
+}
+teardown() {
+    _common_teardown
+}
+
+@test "util pretty-format-json - lists and diffs unformatted files" {
+    printf '{"a": [1, 2.50]}' > bad.json
+    printf '{\n  "b": 1,\n  "a": 2\n}\n' > good.json
+
+    run hk util pretty-format-json bad.json good.json
+    assert_failure
+    assert_output "bad.json"
+
+    run hk util pretty-format-json --diff bad.json good.json
+    assert_failure
+    assert_output --partial "+++ b/bad.json"
+    assert_output --partial '+    2.50'
+    refute_output --partial "good.json"
+}
+
+@test "util pretty-format-json - fixes indentation and sorts keys on request" {
+    printf '{"b": {"d": 1, "c": 2}, "a": []}\n' > data.json
+
+    run hk util pretty-format-json --fix --indent 4 --sort-keys data.json
+    assert_success
+    run cat data.json
+    assert_output '{
+    "a": [],
+    "b": {
+        "c": 2,
+        "d": 1
+    }
+}'
+}
+
+@test "util pretty-format-json - reports invalid json" {
+    printf '{"a": 1,\n' > bad.json
+
+    run hk util pretty-format-json --fix bad.json
+    assert_failure
+    assert_output --partial "bad.json: EOF while parsing"
+}
+
+@test "util sort-keys - orders top keys first in package.json" {
+    printf '{"scripts": {"test": "x"}, "version": "1.0.0", "name": "pkg"}\n' > package.json
+
+    run hk util sort-keys --top-keys name,version --fix package.json
+    assert_success
+    run cat package.json
+    assert_output '{
+  "name": "pkg",
+  "version": "1.0.0",
+  "scripts": {
+    "test": "x"
+  }
+}'
+}
+
+@test "util sort-keys - keeps toml comments with their keys" {
+    cat <<EOF > config.toml
+# the second table
+[z]
+b = 1 # bee
+a = 2
+
+# the first table
+[m]
+x = 1
+EOF
+
+    run hk util sort-keys --diff config.toml
+    assert_failure
+    assert_output --partial "+++ b/config.toml"
+
+    run hk util sort-keys --fix config.toml
+    assert_success
+    run cat config.toml
+    assert_output "# the first table
+[m]
+x = 1
+
+# the second table
+[z]
+a = 2
+b = 1 # bee"
+}
@@ -19,3 +19,61 @@ hooks {
 EOF
     hk validate
 }
+
+@test "validate reports depends cycles and unknown steps" {
+    cat <<EOF > hk.toml
+[hooks.check.steps.a]
+check = "echo a"
+depends = ["b"]
+
+[hooks.check.steps.b]
+check = "echo b"
+depends = ["a", "missing"]
+EOF
+    run hk validate
+    assert_failure
+    assert_output --partial "step 'a' in hook 'check': depends cycle: a -> b -> a"
+    assert_output --partial "step 'b' in hook 'check': depends on unknown step 'missing'"
+}
+
+@test "validate reports unknown template variables and bad conditions" {
+    cat <<EOF > hk.toml
+[hooks.check.steps.lint]
+check = "lint {{ filez }}"
+condition = "exists("
+EOF
+    run hk validate
+    assert_failure
+    assert_output --partial "'check' does not render"
+    assert_output --partial "filez"
+    assert_output --partial "'condition' does not parse"
+}
+
+@test "validate warns about globs matching no tracked files" {
+    cat <<EOF > hk.toml
+[hooks.check.steps.lint]
+glob = ["*.xyz"]
+check = "echo {{ files }}"
+EOF
+    echo "a" > a.txt
+    git add a.txt hk.toml
+    run hk validate
+    assert_success
+    assert_output --partial "glob [\"*.xyz\"] matches no tracked files"
+
+    run hk validate --strict
+    assert_failure
+}
+
+@test "validate --format json lists findings with severity" {
+    cat <<EOF > hk.toml
+[hooks.check.steps.a]
+check = "echo a"
+depends = ["missing"]
+EOF
+    run hk validate --format json
+    assert_failure
+    assert_output --partial '"severity": "error"'
+    assert_output --partial '"hook": "check"'
+    assert_output --partial '"errors": 1'
+}